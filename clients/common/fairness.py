@@ -0,0 +1,54 @@
+"""發牌公平性驗證 (Commit–Reveal)。
+
+ROOM_START / DEAL 帶有 seed_commitment = SHA-256("<seed>:<secret>")，
+GAME_OVER 揭露 seed 與 secret。用本模組重算承諾值並重建發牌，
+即可確認 Server 在開局後沒有更換牌。
+"""
+import hashlib
+from typing import List, Sequence
+
+MASK64 = (1 << 64) - 1
+SUITS = ["S", "H", "D", "C"]
+RANKS = ["2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K", "A"]
+
+
+def commitment(seed: int, secret: str) -> str:
+    """計算承諾值 (小寫 hex)。"""
+    return hashlib.sha256(f"{seed}:{secret}".encode("utf-8")).hexdigest()
+
+
+def _splitmix64(state: int):
+    """與 Server Deck::shuffle 相同的 SplitMix64 產生器。"""
+    while True:
+        state = (state + 0x9E3779B97F4A7C15) & MASK64
+        z = state
+        z = ((z ^ (z >> 30)) * 0xBF58476D1CE4E5B9) & MASK64
+        z = ((z ^ (z >> 27)) * 0x94D049BB133111EB) & MASK64
+        yield z ^ (z >> 31)
+
+
+def _sort_key(card: str):
+    return (SUITS.index(card[-1]), RANKS.index(card[:-1]))
+
+
+def recompute_deal(seed: int, num_players: int = 4, cards_per_player: int = 13) -> List[List[str]]:
+    """以 seed 重建每位玩家的手牌 (依座位順序，手牌依花色、點數排序)。"""
+    cards = [rank + suit for suit in SUITS for rank in RANKS]
+    rng = _splitmix64(seed)
+    for i in range(len(cards) - 1, 0, -1):
+        j = next(rng) % (i + 1)
+        cards[i], cards[j] = cards[j], cards[i]
+
+    hands: List[List[str]] = [[] for _ in range(num_players)]
+    for i, card in enumerate(cards[: num_players * cards_per_player]):
+        hands[i % num_players].append(card)
+    return [sorted(hand, key=_sort_key) for hand in hands]
+
+
+def verify_deal(seed_commitment: str, seed: int, secret: str, seat: int, hand: Sequence[str],
+                num_players: int = 4, cards_per_player: int = 13) -> bool:
+    """驗證揭露的 seed/secret 符合承諾值，且重建出的第 seat 手牌與 DEAL 收到的相同。"""
+    if commitment(seed, secret) != seed_commitment.lower():
+        return False
+    hands = recompute_deal(seed, num_players, cards_per_player)
+    return 0 <= seat < len(hands) and hands[seat] == sorted(hand, key=_sort_key)
@@ -73,8 +73,15 @@ namespace game {
 
     void GameManager::on_game_over(const std::string& json) {
         std::string winner = protocol::JsonHelper::get_string(json, "winner");
+        std::string tie_break = protocol::JsonHelper::get_string(json, "tie_break");
         std::cout << "\n===============================" << std::endl;
-        std::cout << "   GAME OVER! Winner: " << winner << std::endl;
+        if (winner == "DRAW") {
+            std::cout << "   GAME OVER! Draw" << std::endl;
+        } else if (!tie_break.empty()) {
+            std::cout << "   GAME OVER! Winner: " << winner << " (tie-break: " << tie_break << ")" << std::endl;
+        } else {
+            std::cout << "   GAME OVER! Winner: " << winner << std::endl;
+        }
         std::cout << "===============================" << std::endl;
     }
 
@@ -101,7 +101,13 @@ class HumanCLI:
             print("-" * 40)
 
         elif m_type == "GAME_OVER":
-            print(f"\n🏁 GAME OVER! Winner: {msg.get('winner')}")
+            winner = msg.get('winner')
+            if winner == "DRAW":
+                print("\n🏁 GAME OVER! Draw")
+            elif msg.get('tie_break'):
+                print(f"\n🏁 GAME OVER! Winner: {winner} (tie-break: {msg['tie_break']})")
+            else:
+                print(f"\n🏁 GAME OVER! Winner: {winner}")
             self.running = False
             sys.exit(0)
 
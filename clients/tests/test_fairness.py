@@ -0,0 +1,22 @@
+from clients.common.fairness import commitment, recompute_deal, verify_deal
+
+SECRET = "000102030405060708090a0b0c0d0e0f"
+
+
+def test_commitment_matches_server():
+    assert commitment(42, SECRET) == "a8a20deef2917c1aae27edf566dd48eb919390678c37ac5d7353d1a28c0a7405"
+
+
+def test_recompute_deal_matches_server():
+    hands = recompute_deal(42)
+    assert hands[0] == ["5S", "8S", "3H", "5H", "8H", "9H", "JH", "QH", "3D", "5D", "6D", "KD", "3C"]
+    assert sum(len(h) for h in hands) == 52
+    assert len({card for hand in hands for card in hand}) == 52
+
+
+def test_verify_deal():
+    seed_commitment = commitment(42, SECRET)
+    hand = list(reversed(recompute_deal(42)[2]))
+    assert verify_deal(seed_commitment, 42, SECRET, 2, hand)
+    assert not verify_deal(seed_commitment, 42, SECRET, 1, hand)
+    assert not verify_deal(seed_commitment, 43, SECRET, 2, hand)
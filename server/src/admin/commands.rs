@@ -3,6 +3,7 @@
 //! 處理來自管理介面的指令
 
 use super::logger::{EventType, GameLogger};
+use crate::game::DealConstraints;
 use std::sync::mpsc;
 
 /// Admin 事件 (傳送給 Game Loop)
@@ -24,6 +25,32 @@ pub enum AdminEvent {
         room_id: Option<String>,
         reply_tx: mpsc::Sender<AdminResponse>,
     },
+    /// 匯出房間目前這副牌的 PBN 記錄
+    ExportPbn {
+        room_id: String,
+        reply_tx: mpsc::Sender<AdminResponse>,
+    },
+    /// 查看房間每位玩家的手牌與評估
+    InspectRoom {
+        room_id: String,
+        reply_tx: mpsc::Sender<AdminResponse>,
+    },
+    /// 房間這副牌的雙明手分析
+    DoubleDummy {
+        room_id: String,
+        reply_tx: mpsc::Sender<AdminResponse>,
+    },
+    /// 房間這副牌的賽後分析 (代價高的出牌)
+    Analyse {
+        room_id: String,
+        reply_tx: mpsc::Sender<AdminResponse>,
+    },
+    /// 設定房間之後每副牌的發牌條件 (None = 改回洗牌)
+    SetDealConstraints {
+        room_id: String,
+        constraints: Option<DealConstraints>,
+        reply_tx: mpsc::Sender<AdminResponse>,
+    },
 }
 
 /// Admin 回應
@@ -74,6 +101,13 @@ pub enum ParsedCommand {
     Logs(usize, Option<EventType>),
     Kick(String),
     Reset(Option<String>),
+    Pbn(String),
+    Inspect(String),
+    DoubleDummy(String),
+    DoubleDummyDeal(String),
+    Analyse(String),
+    Constrain(String, Option<DealConstraints>),
+    DealGen(DealConstraints, Option<u64>),
     Quit,
     Unknown(String),
 }
@@ -117,6 +151,63 @@ pub fn parse_command(input: &str) -> ParsedCommand {
             let room_id = parts.get(1).map(|s| s.to_string());
             ParsedCommand::Reset(room_id)
         }
+        "PBN" => {
+            if parts.len() < 2 {
+                ParsedCommand::Unknown("PBN requires a room_id".to_string())
+            } else {
+                ParsedCommand::Pbn(parts[1].to_string())
+            }
+        }
+        "INSPECT" => {
+            if parts.len() < 2 {
+                ParsedCommand::Unknown("INSPECT requires a room_id".to_string())
+            } else {
+                ParsedCommand::Inspect(parts[1].to_string())
+            }
+        }
+        "DD" => {
+            if parts.len() < 2 {
+                ParsedCommand::Unknown("DD requires a room_id or a PBN deal".to_string())
+            } else if parts[1].contains(':') {
+                ParsedCommand::DoubleDummyDeal(parts[1..].join(" "))
+            } else {
+                ParsedCommand::DoubleDummy(parts[1].to_string())
+            }
+        }
+        "ANALYSE" | "ANALYZE" => {
+            if parts.len() < 2 {
+                ParsedCommand::Unknown("ANALYSE requires a room_id".to_string())
+            } else {
+                ParsedCommand::Analyse(parts[1].to_string())
+            }
+        }
+        "CONSTRAIN" => {
+            if parts.len() < 3 {
+                ParsedCommand::Unknown("CONSTRAIN requires a room_id and constraints (or OFF)".to_string())
+            } else if parts[2].eq_ignore_ascii_case("OFF") {
+                ParsedCommand::Constrain(parts[1].to_string(), None)
+            } else {
+                // 太少見的條件在這裡 (admin 執行緒) 試抽時就拒絕，不在開局時卡住 game loop
+                match DealConstraints::parse(parts[2]).and_then(|c| c.probe().map(|_| c)) {
+                    Ok(c) => ParsedCommand::Constrain(parts[1].to_string(), Some(c)),
+                    Err(e) => ParsedCommand::Unknown(e.to_string()),
+                }
+            }
+        }
+        "DEALGEN" => {
+            if parts.len() < 2 {
+                return ParsedCommand::Unknown("DEALGEN requires constraints".to_string());
+            }
+            let seed = match parts.get(2).map(|s| s.parse::<u64>()) {
+                Some(Ok(seed)) => Some(seed),
+                Some(Err(_)) => return ParsedCommand::Unknown("DEALGEN seed must be a number".to_string()),
+                None => None,
+            };
+            match DealConstraints::parse(parts[1]) {
+                Ok(c) => ParsedCommand::DealGen(c, seed),
+                Err(e) => ParsedCommand::Unknown(e.to_string()),
+            }
+        }
         "QUIT" | "EXIT" | "BYE" => ParsedCommand::Quit,
         _ => ParsedCommand::Unknown(format!("Unknown command: {}", cmd)),
     }
@@ -135,9 +226,22 @@ Commands:
   PLAYERS            List all players
   LOGS [n] [type]    Show recent n logs (default: 20)
                      Types: PLAYER_JOIN, PLAYER_LEAVE, GAME_START,
-                            GAME_END, PLAY, TRICK_RESULT, ADMIN, ERROR
+                            GAME_END, BID, PASS_CARDS, PLAY, TRICK_RESULT, ADMIN, ERROR
   KICK <player_id>   Kick a player (e.g., KICK P1)
   RESET [room_id]    Reset a room (e.g., RESET R001)
+  PBN <room_id>      Export the room's current board as PBN (e.g., PBN R001)
+  INSPECT <room_id>  Show each seat's hand with HCP, shape, quick tricks and LTC
+  DD <room_id|deal>  Double-dummy tricks per strain and opening leader for a room's
+                     board (live or finished) or a PBN Deal tag value
+                     (seats play in game order N, S, E, W)
+  ANALYSE <room_id>  Replay the room's board and list plays that cost tricks
+                     double dummy, with a better card for each
+  CONSTRAIN <room_id> <spec|OFF>
+                     Deal the room's next boards to constraints
+  DEALGEN <spec> [seed]
+                     Generate one deal matching constraints
+                     Spec: N:hcp=15-17,bal;E:S=6+ (seats N/E/S/W; hcp=,
+                           S=/H=/D=/C= lengths, bal, unbal; ranges 15-17, 6+, 5)
   QUIT               Disconnect from admin console
 
 Examples:
@@ -146,6 +250,13 @@ Examples:
   LOGS 10 PLAY
   KICK P1
   RESET R001
+  PBN R001
+  INSPECT R001
+  DD R001
+  DD N:AKQJ.T98.765.432 T98.765.432.AKQJ 765.432.AKQJ.T98 432.AKQJ.T98.765
+  ANALYSE R001
+  CONSTRAIN R001 N:hcp=15-17,bal
+  DEALGEN E:S=6+ 42
 "#
     .to_string()
 }
@@ -323,4 +434,110 @@ mod tests {
             _ => panic!("Expected Reset with room"),
         }
     }
+
+    #[test]
+    fn test_parse_pbn() {
+        match parse_command("pbn R002") {
+            ParsedCommand::Pbn(id) => assert_eq!(id, "R002"),
+            _ => panic!("Expected Pbn"),
+        }
+
+        match parse_command("PBN") {
+            ParsedCommand::Unknown(msg) => assert!(msg.contains("room_id")),
+            _ => panic!("Expected Unknown"),
+        }
+    }
+
+    #[test]
+    fn test_parse_inspect() {
+        match parse_command("INSPECT R003") {
+            ParsedCommand::Inspect(id) => assert_eq!(id, "R003"),
+            _ => panic!("Expected Inspect"),
+        }
+
+        match parse_command("INSPECT") {
+            ParsedCommand::Unknown(msg) => assert!(msg.contains("room_id")),
+            _ => panic!("Expected Unknown"),
+        }
+    }
+
+    #[test]
+    fn test_parse_double_dummy() {
+        match parse_command("DD R001") {
+            ParsedCommand::DoubleDummy(id) => assert_eq!(id, "R001"),
+            _ => panic!("Expected DoubleDummy"),
+        }
+
+        match parse_command("dd N:AKQJ.T98.765.432 T98.765.432.AKQJ 765.432.AKQJ.T98 432.AKQJ.T98.765") {
+            ParsedCommand::DoubleDummyDeal(deal) => assert!(deal.starts_with("N:AKQJ") && deal.ends_with(".765")),
+            _ => panic!("Expected DoubleDummyDeal"),
+        }
+
+        match parse_command("DD") {
+            ParsedCommand::Unknown(msg) => assert!(msg.contains("room_id")),
+            _ => panic!("Expected Unknown"),
+        }
+    }
+
+    #[test]
+    fn test_parse_analyse() {
+        match parse_command("ANALYSE R001") {
+            ParsedCommand::Analyse(id) => assert_eq!(id, "R001"),
+            _ => panic!("Expected Analyse"),
+        }
+
+        match parse_command("analyze R002") {
+            ParsedCommand::Analyse(id) => assert_eq!(id, "R002"),
+            _ => panic!("Expected Analyse"),
+        }
+
+        match parse_command("ANALYSE") {
+            ParsedCommand::Unknown(msg) => assert!(msg.contains("room_id")),
+            _ => panic!("Expected Unknown"),
+        }
+    }
+
+    #[test]
+    fn test_parse_constrain() {
+        match parse_command("CONSTRAIN R001 N:hcp=15-17,bal") {
+            ParsedCommand::Constrain(id, Some(c)) => {
+                assert_eq!(id, "R001");
+                assert_eq!(c.to_string(), "N:hcp=15-17,bal");
+            }
+            _ => panic!("Expected Constrain"),
+        }
+
+        match parse_command("constrain R001 off") {
+            ParsedCommand::Constrain(id, None) => assert_eq!(id, "R001"),
+            _ => panic!("Expected Constrain OFF"),
+        }
+
+        match parse_command("CONSTRAIN R001 Q:hcp=1") {
+            ParsedCommand::Unknown(msg) => assert!(msg.contains("invalid constraint")),
+            _ => panic!("Expected Unknown"),
+        }
+
+        match parse_command("CONSTRAIN R001 N:S=13") {
+            ParsedCommand::Unknown(msg) => assert!(msg.contains("too rare")),
+            _ => panic!("Expected Unknown"),
+        }
+    }
+
+    #[test]
+    fn test_parse_dealgen() {
+        match parse_command("DEALGEN E:S=6+ 42") {
+            ParsedCommand::DealGen(c, Some(42)) => assert_eq!(c.to_string(), "E:S=6+"),
+            _ => panic!("Expected DealGen with seed"),
+        }
+
+        match parse_command("DEALGEN N:bal") {
+            ParsedCommand::DealGen(_, None) => {}
+            _ => panic!("Expected DealGen"),
+        }
+
+        match parse_command("DEALGEN N:bal x") {
+            ParsedCommand::Unknown(msg) => assert!(msg.contains("seed")),
+            _ => panic!("Expected Unknown"),
+        }
+    }
 }
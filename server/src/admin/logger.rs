@@ -16,6 +16,8 @@ pub enum EventType {
     PlayerLeave,
     GameStart,
     GameEnd,
+    Bid,
+    PassCards,
     Play,
     TrickResult,
     AdminAction,
@@ -29,6 +31,8 @@ impl EventType {
             EventType::PlayerLeave => "PLAYER_LEAVE",
             EventType::GameStart => "GAME_START",
             EventType::GameEnd => "GAME_END",
+            EventType::Bid => "BID",
+            EventType::PassCards => "PASS_CARDS",
             EventType::Play => "PLAY",
             EventType::TrickResult => "TRICK_RESULT",
             EventType::AdminAction => "ADMIN",
@@ -42,6 +46,8 @@ impl EventType {
             "PLAYER_LEAVE" => Some(EventType::PlayerLeave),
             "GAME_START" => Some(EventType::GameStart),
             "GAME_END" => Some(EventType::GameEnd),
+            "BID" => Some(EventType::Bid),
+            "PASS_CARDS" => Some(EventType::PassCards),
             "PLAY" => Some(EventType::Play),
             "TRICK_RESULT" => Some(EventType::TrickResult),
             "ADMIN" => Some(EventType::AdminAction),
@@ -233,6 +239,14 @@ impl GameLogger {
         );
     }
 
+    pub fn bid(&self, player_id: &str, call: &str) {
+        self.log(EventType::Bid, format!("{} calls {}", player_id, call));
+    }
+
+    pub fn pass_cards(&self, player_id: &str, cards: &str) {
+        self.log(EventType::PassCards, format!("{} passes {}", player_id, cards));
+    }
+
     pub fn play(&self, player_id: &str, card: &str, trick: u32) {
         self.log(
             EventType::Play,
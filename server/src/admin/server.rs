@@ -7,6 +7,7 @@ use super::commands::{
     parse_command, AdminEvent, AdminResponse, ParsedCommand,
 };
 use super::logger::GameLogger;
+use crate::game::{DealSeed, solver};
 use log::{error, info, warn};
 use std::io::{BufRead, BufReader, Write};
 use std::net::{TcpListener, TcpStream};
@@ -14,6 +15,9 @@ use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
 
+/// 雙明手分析的等待時間 (一副完整的牌要解 20 次)
+const SOLVER_TIMEOUT: Duration = Duration::from_secs(300);
+
 /// Admin Server 設定
 pub struct AdminConfig {
     /// 認證 Token
@@ -237,6 +241,109 @@ fn handle_admin_connection(
                 }
             }
 
+            ParsedCommand::Pbn(room_id) => {
+                if !session.authenticated {
+                    session.send("ERROR: Not authenticated. Use AUTH <token> first.");
+                } else {
+                    match send_and_receive(&event_tx, |reply_tx| AdminEvent::ExportPbn { room_id, reply_tx }) {
+                        // PBN 原文輸出，方便直接存檔
+                        Some(AdminResponse::Ok(pbn)) => session.send(&pbn),
+                        Some(response) => session.send(&format_result(&response)),
+                        None => session.send("ERROR: Failed to export PBN"),
+                    }
+                }
+            }
+
+            ParsedCommand::Inspect(room_id) => {
+                if !session.authenticated {
+                    session.send("ERROR: Not authenticated. Use AUTH <token> first.");
+                } else {
+                    match send_and_receive(&event_tx, |reply_tx| AdminEvent::InspectRoom { room_id, reply_tx }) {
+                        Some(AdminResponse::Ok(text)) => session.send(&text),
+                        Some(response) => session.send(&format_result(&response)),
+                        None => session.send("ERROR: Failed to inspect room"),
+                    }
+                }
+            }
+
+            ParsedCommand::DoubleDummy(room_id) => {
+                if !session.authenticated {
+                    session.send("ERROR: Not authenticated. Use AUTH <token> first.");
+                } else {
+                    session.send("Solving, this may take a while...");
+                    match send_and_wait(&event_tx, SOLVER_TIMEOUT, |reply_tx| AdminEvent::DoubleDummy { room_id, reply_tx }) {
+                        Some(AdminResponse::Ok(text)) => session.send(&text),
+                        Some(response) => session.send(&format_result(&response)),
+                        None => session.send("ERROR: Double-dummy analysis timed out"),
+                    }
+                }
+            }
+
+            ParsedCommand::DoubleDummyDeal(deal) => {
+                if !session.authenticated {
+                    session.send("ERROR: Not authenticated. Use AUTH <token> first.");
+                } else {
+                    // 不需要遊戲狀態，直接在 admin 執行緒解算
+                    match solver::pbn_hands(&deal) {
+                        Ok((hands, teams)) => {
+                            session.send("Solving, this may take a while...");
+                            match solver::trick_table(hands, teams) {
+                                Ok(table) => session.send(&format!("\n{}", table.format(solver::PBN_SEAT_LABELS, teams))),
+                                Err(e) => session.send(&format!("ERROR: {}", e)),
+                            }
+                        }
+                        Err(e) => session.send(&format!("ERROR: {}", e)),
+                    }
+                }
+            }
+
+            ParsedCommand::Analyse(room_id) => {
+                if !session.authenticated {
+                    session.send("ERROR: Not authenticated. Use AUTH <token> first.");
+                } else {
+                    session.send("Analysing, this may take a while...");
+                    match send_and_wait(&event_tx, SOLVER_TIMEOUT, |reply_tx| AdminEvent::Analyse { room_id, reply_tx }) {
+                        Some(AdminResponse::Ok(text)) => session.send(&text),
+                        Some(response) => session.send(&format_result(&response)),
+                        None => session.send("ERROR: Analysis timed out"),
+                    }
+                }
+            }
+
+            ParsedCommand::Constrain(room_id, constraints) => {
+                if !session.authenticated {
+                    session.send("ERROR: Not authenticated. Use AUTH <token> first.");
+                } else {
+                    let spec = constraints.map(|c| c.to_string()).unwrap_or_else(|| "OFF".to_string());
+                    match send_and_receive(&event_tx, |reply_tx| AdminEvent::SetDealConstraints {
+                        room_id: room_id.clone(),
+                        constraints,
+                        reply_tx,
+                    }) {
+                        Some(response) => {
+                            session.send(&format_result(&response));
+                            if matches!(response, AdminResponse::Ok(_)) {
+                                logger.admin_action("CONSTRAIN", &format!("Room {} deals: {}", room_id, spec));
+                            }
+                        }
+                        None => session.send("ERROR: Failed to set constraints"),
+                    }
+                }
+            }
+
+            ParsedCommand::DealGen(constraints, seed) => {
+                if !session.authenticated {
+                    session.send("ERROR: Not authenticated. Use AUTH <token> first.");
+                } else {
+                    // 不需要遊戲狀態，直接在 admin 執行緒產生
+                    let seed = seed.unwrap_or_else(|| DealSeed::generate().seed);
+                    match constraints.generate(seed) {
+                        Ok(deal) => session.send(&format!("[Seed \"{}\"]\n[Deal \"{}\"]", seed, deal.deal_tag())),
+                        Err(e) => session.send(&format!("ERROR: {}", e)),
+                    }
+                }
+            }
+
             ParsedCommand::Quit => {
                 session.send("Goodbye!");
                 info!("[ADMIN] {} logged out", session.peer_addr);
@@ -258,8 +365,16 @@ fn handle_admin_connection(
     info!("[ADMIN] Connection closed: {}", session.peer_addr);
 }
 
-/// 發送事件並等待回應
+/// 發送事件並等待回應 (最多 5 秒)
 fn send_and_receive<F>(event_tx: &mpsc::Sender<AdminEvent>, make_event: F) -> Option<AdminResponse>
+where
+    F: FnOnce(mpsc::Sender<AdminResponse>) -> AdminEvent,
+{
+    send_and_wait(event_tx, Duration::from_secs(5), make_event)
+}
+
+/// 發送事件並等待回應 (自訂等待時間)
+fn send_and_wait<F>(event_tx: &mpsc::Sender<AdminEvent>, timeout: Duration, make_event: F) -> Option<AdminResponse>
 where
     F: FnOnce(mpsc::Sender<AdminResponse>) -> AdminEvent,
 {
@@ -270,8 +385,7 @@ where
         return None;
     }
 
-    // 等待回應 (最多 5 秒)
-    reply_rx.recv_timeout(Duration::from_secs(5)).ok()
+    reply_rx.recv_timeout(timeout).ok()
 }
 
 #[cfg(test)]
@@ -0,0 +1,166 @@
+//! 紅心 (Hearts) 出牌策略
+
+use super::strategy::AiStrategy;
+use crate::game::cardset::CardSet;
+use crate::game::deck::{CardData, Suit};
+use crate::game::rules::Hearts;
+
+/// 紅心策略 (HeartsStrategy)
+///
+/// ## 策略規則
+///
+/// ### 傳牌
+/// 依序傳出 ♠Q、♠A、♠K，其次是大的紅心，最後是其他大牌
+///
+/// ### 首家 (領牌)
+/// 出最小的非紅心牌 (不得已才領紅心)
+///
+/// ### 非首家 (跟牌)
+/// - 有同花色: 出「小於桌面最大牌」的最大牌 (避免吃墩)；
+///   一定會吃墩時出最大的牌，但盡量不出 ♠Q
+/// - 無同花色: 優先墊 ♠Q，其次最大的紅心，再來是最大的牌
+#[derive(Debug, Clone, Default)]
+pub struct HeartsStrategy;
+
+impl HeartsStrategy {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 傳牌優先度 (越大越先傳出)
+    fn pass_priority(card: &CardData) -> (u8, u8) {
+        let is_top_spade = card.suit == Suit::Spades && card.rank >= Hearts::QUEEN_OF_SPADES.rank;
+        let group = if *card == Hearts::QUEEN_OF_SPADES {
+            3
+        } else if is_top_spade {
+            2
+        } else if card.suit == Suit::Hearts {
+            1
+        } else {
+            0
+        };
+        (group, card.rank.0)
+    }
+
+    /// 墊牌優先度 (越大越先墊出)
+    fn discard_priority(card: &CardData) -> (u8, u8) {
+        let group = if *card == Hearts::QUEEN_OF_SPADES {
+            2
+        } else if card.suit == Suit::Hearts {
+            1
+        } else {
+            0
+        };
+        (group, card.rank.0)
+    }
+}
+
+impl AiStrategy for HeartsStrategy {
+    fn choose_card(
+        &self,
+        hand: CardSet,
+        legal_moves: CardSet,
+        table: &[(usize, CardData)],
+        is_leader: bool,
+    ) -> CardData {
+        let first = match legal_moves.first() {
+            Some(card) => card,
+            // 不應該發生，但作為 fallback
+            None => return hand.first().unwrap_or(Hearts::OPENING_CARD),
+        };
+
+        if is_leader || table.is_empty() {
+            // === 首家策略 ===
+            return legal_moves
+                .iter()
+                .min_by_key(|c| (c.suit == Suit::Hearts, c.rank.0))
+                .unwrap_or(first);
+        }
+
+        let lead_suit = table[0].1.suit;
+        let following = legal_moves.of_suit(lead_suit);
+
+        if following.is_empty() {
+            // === 墊牌 ===
+            return legal_moves
+                .iter()
+                .max_by_key(Self::discard_priority)
+                .unwrap_or(first);
+        }
+
+        let highest_on_table = table
+            .iter()
+            .filter(|(_, c)| c.suit == lead_suit)
+            .map(|(_, c)| c.rank)
+            .max()
+            .unwrap_or(table[0].1.rank);
+
+        // 能閃就出最大的閃牌
+        if let Some(duck) = following.iter().filter(|c| c.rank < highest_on_table).max_by_key(|c| c.rank.0) {
+            return duck;
+        }
+
+        // 一定會吃墩：出最大的牌，但不自己吃下 ♠Q
+        (following - CardSet::single(Hearts::QUEEN_OF_SPADES))
+            .highest_in(lead_suit)
+            .or_else(|| following.first())
+            .unwrap_or(first)
+    }
+
+    fn choose_pass(&self, hand: CardSet, count: usize) -> CardSet {
+        let mut cards = hand.to_vec();
+        cards.sort_by_key(|c| std::cmp::Reverse(Self::pass_priority(c)));
+        cards.into_iter().take(count).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(list: &[&str]) -> Vec<CardData> {
+        list.iter().map(|s| CardData::from_protocol_string(s).unwrap()).collect()
+    }
+
+    fn set(list: &[&str]) -> CardSet {
+        cards(list).into_iter().collect()
+    }
+
+    #[test]
+    fn test_pass_dangerous_cards() {
+        let hand = set(&["2C", "AC", "QS", "KS", "5S", "AH", "3H", "9D"]);
+        let passed = HeartsStrategy::new().choose_pass(hand, 3);
+        assert_eq!(passed, set(&["QS", "KS", "AH"]));
+    }
+
+    #[test]
+    fn test_lead_low_non_heart() {
+        let hand = set(&["2H", "9D", "4C"]);
+        let card = HeartsStrategy::new().choose_card(hand, hand, &[], true);
+        assert_eq!(card, cards(&["4C"])[0]);
+    }
+
+    #[test]
+    fn test_follow_ducks_under_winner() {
+        let strategy = HeartsStrategy::new();
+        let table = vec![(0, cards(&["JD"])[0])];
+        let hand = set(&["2D", "10D", "KD"]);
+        assert_eq!(strategy.choose_card(hand, hand, &table, false), cards(&["10D"])[0]);
+
+        // 一定會吃墩時出最大的牌，但不出 ♠Q
+        let table = vec![(0, cards(&["3S"])[0])];
+        let hand = set(&["QS", "9S"]);
+        assert_eq!(strategy.choose_card(hand, hand, &table, false), cards(&["9S"])[0]);
+    }
+
+    #[test]
+    fn test_discard_queen_then_hearts() {
+        let strategy = HeartsStrategy::new();
+        let table = vec![(0, cards(&["5C"])[0])];
+        let hand = set(&["QS", "AH", "KD"]);
+        assert_eq!(strategy.choose_card(hand, hand, &table, false), cards(&["QS"])[0]);
+
+        let hand = set(&["4H", "AH", "KD"]);
+        assert_eq!(strategy.choose_card(hand, hand, &table, false), cards(&["AH"])[0]);
+    }
+}
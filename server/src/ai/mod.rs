@@ -2,11 +2,13 @@
 //!
 //! 此模組提供 Bridge Mode 所需的 AI 功能：
 //! - AiPlayer: 虛擬 AI 玩家 (不佔用 TCP 連線)
-//! - Strategy: 可插拔的出牌策略
+//! - Strategy: 可插拔的出牌策略 (SmartStrategy / HeartsStrategy)
 //! - TurnHandler: AI 出牌處理
 
+mod hearts;
 mod player;
 mod strategy;
 
+pub use hearts::HeartsStrategy;
 pub use player::AiPlayer;
 pub use strategy::{AiStrategy, SmartStrategy};
@@ -8,7 +8,7 @@ use crate::protocol::{PlayerId, Team};
 /// 其出牌決策由 Server 端的 Strategy 處理。
 #[derive(Debug, Clone)]
 pub struct AiPlayer {
-    /// 玩家 ID (P3 或 P4)
+    /// 玩家 ID (4 人時為 P3 或 P4)
     pub player_id: PlayerId,
     /// 暱稱
     pub nickname: String,
@@ -18,27 +18,18 @@ pub struct AiPlayer {
 }
 
 impl AiPlayer {
-    /// 建立 AI Partner 1 (P3 位置)
-    pub fn partner1() -> Self {
+    /// 建立坐在 slot (1-based) 的第 index 個 AI (1-based)
+    pub fn new(slot: usize, index: usize) -> Self {
         Self {
-            player_id: "P3".to_string(),
-            nickname: "AI_Partner1".to_string(),
+            player_id: format!("P{}", slot),
+            nickname: format!("AI_Partner{}", index),
             team: Team::Ai,
         }
     }
 
-    /// 建立 AI Partner 2 (P4 位置)
-    pub fn partner2() -> Self {
-        Self {
-            player_id: "P4".to_string(),
-            nickname: "AI_Partner2".to_string(),
-            team: Team::Ai,
-        }
-    }
-
-    /// 建立 AI 玩家對 (Partner 1 & 2)
-    pub fn create_partners() -> (Self, Self) {
-        (Self::partner1(), Self::partner2())
+    /// 建立坐在前 human_seats 個座位之後的 count 個 AI 玩家 (4 人時為 P3, P4)
+    pub fn create_team(human_seats: usize, count: usize) -> Vec<Self> {
+        (1..=count).map(|i| Self::new(human_seats + i, i)).collect()
     }
 }
 
@@ -47,12 +38,17 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_create_partners() {
-        let (p1, p2) = AiPlayer::create_partners();
+    fn test_create_team() {
+        let team = AiPlayer::create_team(2, 2);
+        let (p1, p2) = (&team[0], &team[1]);
 
         assert_eq!(p1.player_id, "P3");
         assert_eq!(p2.player_id, "P4");
         assert_eq!(p1.team, Team::Ai);
         assert_eq!(p2.team, Team::Ai);
+
+        // 6 人桌：P4-P6
+        let ids: Vec<_> = AiPlayer::create_team(3, 3).into_iter().map(|ai| ai.player_id).collect();
+        assert_eq!(ids, ["P4", "P5", "P6"]);
     }
 }
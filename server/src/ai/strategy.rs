@@ -2,8 +2,10 @@
 //!
 //! 提供可插拔的出牌策略系統
 
+use crate::game::auction::{Auction, Bid, Call, Strain};
+use crate::game::cardset::CardSet;
 use crate::game::deck::{CardData, Rank, Suit};
-use std::collections::HashMap;
+use crate::game::eval;
 
 /// AI 策略 trait
 pub trait AiStrategy: Send + Sync {
@@ -19,11 +21,32 @@ pub trait AiStrategy: Send + Sync {
     /// 選擇要出的牌
     fn choose_card(
         &self,
-        hand: &[CardData],
-        legal_moves: &[CardData],
+        hand: CardSet,
+        legal_moves: CardSet,
         table: &[(usize, CardData)],
         is_leader: bool,
     ) -> CardData;
+
+    /// 選擇叫牌 (預設一律 PASS)
+    ///
+    /// # Arguments
+    /// * `hand` - AI 的手牌
+    /// * `auction` - 目前的叫牌過程
+    /// * `seat` - AI 的座位 index
+    fn choose_call(&self, _hand: CardSet, _auction: &Auction, _seat: usize) -> Call {
+        Call::Pass
+    }
+
+    /// 選擇出牌前要傳出的牌 (預設傳出點數最大的牌)
+    ///
+    /// # Arguments
+    /// * `hand` - AI 的手牌
+    /// * `count` - 要傳出的張數
+    fn choose_pass(&self, hand: CardSet, count: usize) -> CardSet {
+        let mut cards = hand.to_vec();
+        cards.sort_by_key(|c| std::cmp::Reverse(c.rank.0));
+        cards.into_iter().take(count).collect()
+    }
 }
 
 /// 智慧策略 (SmartStrategy)
@@ -38,84 +61,106 @@ pub trait AiStrategy: Send + Sync {
 ///
 /// ### 非首家 (跟牌)
 /// - 有同花色: 找「大於桌面最大牌至少 3 點」的最小牌，若無則出最小牌
-/// - 無同花色: 出任意花色最小牌 (墊牌)
+///   (已有人王吃時贏不了，直接出最小牌)
+/// - 無同花色且有王牌: 出「能贏過桌面最大王牌」的最小王牌 (王吃 / 超吃)
+/// - 無法王吃: 出非王牌的最小牌 (墊牌)
+///
+/// ### 叫牌
+/// - 開叫: 15-17 點平均牌型開叫 1NT，12 點以上開叫最長花色
+/// - 同伴叫過花色且有 3 張以上支持: 依點數加叫 (6-9 → 2 線, 10-12 → 3 線, 13+ → 4 線)
+/// - 同伴叫過 NT 且有 10 點以上: 加叫 3NT
+/// - 對手叫過: 12 點以上且有 5 張以上花色時在 2 線以內爭叫
+/// - 從不賭倍
 #[derive(Debug, Clone, Default)]
-pub struct SmartStrategy;
+pub struct SmartStrategy {
+    /// 王牌花色 (None = 無王)
+    trump: Option<Suit>,
+}
 
 impl SmartStrategy {
-    /// 建立新的 SmartStrategy 實例 - 預留供未來擴充
+    /// 建立新的 SmartStrategy 實例 (無王)
     #[allow(dead_code)]
     pub fn new() -> Self {
-        Self
+        Self::default()
     }
 
-    /// 找出最長花色
-    fn find_longest_suit(hand: &[CardData]) -> Suit {
-        let mut counts: HashMap<Suit, usize> = HashMap::new();
-        for card in hand {
-            *counts.entry(card.suit).or_insert(0) += 1;
-        }
+    /// 建立指定王牌花色的 SmartStrategy
+    pub fn with_trump(trump: Option<Suit>) -> Self {
+        Self { trump }
+    }
 
-        // 找出最大數量
-        let max_count = counts.values().max().copied().unwrap_or(0);
+    /// 找出能王吃 (或超吃) 的最小王牌
+    fn find_ruff(legal_moves: CardSet, table: &[(usize, CardData)], trump: Suit) -> Option<CardData> {
+        let highest_trump = table
+            .iter()
+            .filter(|(_, c)| c.suit == trump)
+            .map(|(_, c)| c.rank)
+            .max();
 
-        // 平手時依優先順序: S > H > D > C
-        let priority = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
-        for suit in priority {
-            if counts.get(&suit).copied().unwrap_or(0) == max_count {
-                return suit;
-            }
-        }
+        // 同花色由小到大列出，第一張壓得過的就是最小的
+        legal_moves
+            .of_suit(trump)
+            .iter()
+            .find(|c| highest_trump.is_none_or(|r| c.rank > r))
+    }
 
-        Suit::Spades // fallback
+    /// 叫出 strain 中最低的合法叫品 (不超過 max_level)
+    fn bid_up_to(auction: &Auction, strain: Strain, max_level: u8) -> Option<Call> {
+        auction
+            .lowest_bid_in(strain)
+            .filter(|b| b.level <= max_level)
+            .map(Call::Bid)
     }
 
-    /// 找出某花色中點數最小的牌
-    fn find_smallest_of_suit(cards: &[CardData], suit: Suit) -> Option<CardData> {
-        cards
-            .iter()
-            .filter(|c| c.suit == suit)
-            .min_by_key(|c| c.rank.0)
-            .copied()
+    /// 直接跳叫到 strain 的指定線位 (必須高於目前的叫品)
+    fn raise_to(auction: &Auction, strain: Strain, level: u8) -> Option<Call> {
+        Bid::new(level, strain)
+            .filter(|b| auction.last_bid().is_none_or(|(_, last)| *b > last))
+            .map(Call::Bid)
     }
 
-    /// 找出點數最小的牌
-    fn find_smallest(cards: &[CardData]) -> Option<CardData> {
-        cards.iter().min_by_key(|c| c.rank.0).copied()
+    /// 找出最長花色
+    fn find_longest_suit(hand: CardSet) -> Suit {
+        // 找出最大數量
+        let max_count = Suit::all().iter().map(|&s| hand.suit_len(s)).max().unwrap_or(0);
+
+        // 平手時依優先順序: S > H > D > C
+        Suit::all()
+            .into_iter()
+            .find(|&s| hand.suit_len(s) == max_count)
+            .unwrap_or(Suit::Spades)
     }
 
     /// 找出大於 threshold 至少 min_diff 點的最小牌
-    fn find_smallest_above_threshold(
-        cards: &[CardData],
-        threshold: Rank,
-        min_diff: u8,
-    ) -> Option<CardData> {
+    fn find_smallest_above_threshold(cards: CardSet, threshold: Rank, min_diff: u8) -> Option<CardData> {
         let target_min = threshold.0.saturating_add(min_diff);
 
         cards
             .iter()
             .filter(|c| c.rank.0 >= target_min)
             .min_by_key(|c| c.rank.0)
-            .copied()
     }
 }
 
 impl AiStrategy for SmartStrategy {
     fn choose_card(
         &self,
-        hand: &[CardData],
-        legal_moves: &[CardData],
+        hand: CardSet,
+        legal_moves: CardSet,
         table: &[(usize, CardData)],
         is_leader: bool,
     ) -> CardData {
         // 安全檢查
-        if legal_moves.is_empty() {
+        let first = match legal_moves.first() {
+            Some(card) => card,
             // 不應該發生，但作為 fallback
-            return hand.first().copied().unwrap_or_else(|| CardData {
-                suit: Suit::Clubs,
-                rank: Rank::TWO,
-            });
-        }
+            None => {
+                return hand.first().unwrap_or(CardData {
+                    suit: Suit::Clubs,
+                    rank: Rank::TWO,
+                });
+            }
+        };
 
         if is_leader {
             // === 首家策略 ===
@@ -123,20 +168,28 @@ impl AiStrategy for SmartStrategy {
             let longest_suit = Self::find_longest_suit(hand);
 
             // 從 legal_moves 中找該花色最小牌
-            if let Some(card) = Self::find_smallest_of_suit(legal_moves, longest_suit) {
+            if let Some(card) = legal_moves.lowest_in(longest_suit) {
                 return card;
             }
             // Fallback: 出任意最小牌
-            Self::find_smallest(legal_moves).unwrap_or(legal_moves[0])
+            legal_moves.lowest().unwrap_or(first)
         } else {
             // === 非首家策略 ===
             let lead_suit = table[0].1.suit;
 
             // 檢查 legal_moves 是否有同花色 (必須跟牌的情況)
-            let same_suit_moves: Vec<CardData> =
-                legal_moves.iter().filter(|c| c.suit == lead_suit).copied().collect();
+            let same_suit_moves = legal_moves.of_suit(lead_suit);
+
+            // 已有人王吃 (領牌花色不是王牌)，跟牌贏不了
+            let trumped = self
+                .trump
+                .is_some_and(|t| t != lead_suit && table.iter().any(|(_, c)| c.suit == t));
 
             if !same_suit_moves.is_empty() {
+                if trumped {
+                    return same_suit_moves.lowest().unwrap_or(first);
+                }
+
                 // 有同花色，找桌面同花色最大牌
                 let highest_on_table = table
                     .iter()
@@ -147,19 +200,73 @@ impl AiStrategy for SmartStrategy {
 
                 // 找「大於 highest 至少 3 點」的最小牌
                 if let Some(winning_card) =
-                    Self::find_smallest_above_threshold(&same_suit_moves, highest_on_table, 3)
+                    Self::find_smallest_above_threshold(same_suit_moves, highest_on_table, 3)
                 {
                     return winning_card;
                 }
 
                 // 無法贏取，出同花色最小牌
-                Self::find_smallest(&same_suit_moves).unwrap_or(legal_moves[0])
+                same_suit_moves.lowest().unwrap_or(first)
             } else {
-                // 無同花色，出任意最小牌 (墊牌)
-                Self::find_smallest(legal_moves).unwrap_or(legal_moves[0])
+                // 無同花色，能王吃 (或超吃) 就出最小的有效王牌
+                if let Some(trump) = self.trump
+                    && let Some(ruff) = Self::find_ruff(legal_moves, table, trump)
+                {
+                    return ruff;
+                }
+
+                // 墊牌：優先保留王牌
+                let discards = match self.trump {
+                    Some(trump) => legal_moves - CardSet::suit_mask(trump),
+                    None => legal_moves,
+                };
+                discards.lowest().or_else(|| legal_moves.lowest()).unwrap_or(first)
             }
         }
     }
+
+    fn choose_call(&self, hand: CardSet, auction: &Auction, seat: usize) -> Call {
+        let hcp = eval::high_card_points(&hand.to_vec());
+        let longest_suit = Self::find_longest_suit(hand);
+        let longest_strain = Strain::from_trump(Some(longest_suit));
+
+        let call = match auction.last_bid() {
+            // === 開叫 ===
+            None => {
+                if eval::is_balanced(&hand.to_vec()) && (15..=17).contains(&hcp) {
+                    Self::bid_up_to(auction, Strain::NoTrump, 1)
+                } else if hcp >= 12 {
+                    Self::bid_up_to(auction, longest_strain, 1)
+                } else {
+                    None
+                }
+            }
+            // === 回應同伴 ===
+            Some((by, bid)) if by != seat && auction.is_partner(by, seat) => match bid.strain.trump() {
+                Some(suit) if hand.suit_len(suit) >= 3 && hcp >= 6 => {
+                    let level = match hcp {
+                        6..=9 => 2,
+                        10..=12 => 3,
+                        _ => 4,
+                    };
+                    Self::raise_to(auction, bid.strain, level)
+                }
+                None if hcp >= 10 => Self::raise_to(auction, Strain::NoTrump, 3),
+                _ => None,
+            },
+            // === 爭叫 ===
+            Some((by, _)) if !auction.is_partner(by, seat) => {
+                if hcp >= 12 && hand.suit_len(longest_suit) >= 5 {
+                    Self::bid_up_to(auction, longest_strain, 2)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+
+        call.unwrap_or(Call::Pass)
+    }
 }
 
 #[cfg(test)]
@@ -175,15 +282,15 @@ mod tests {
         let strategy = SmartStrategy::new();
 
         // 手牌: 3H 5H 9H 2D 7D (H 最長=3張)
-        let hand = vec![
+        let hand: CardSet = [
             make_card(Suit::Hearts, 3),
             make_card(Suit::Hearts, 5),
             make_card(Suit::Hearts, 9),
             make_card(Suit::Diamonds, 2),
             make_card(Suit::Diamonds, 7),
-        ];
+        ].into_iter().collect();
 
-        let result = strategy.choose_card(&hand, &hand, &[], true);
+        let result = strategy.choose_card(hand, hand, &[], true);
 
         // 應該出 3H (Hearts 最長，最小牌)
         assert_eq!(result.suit, Suit::Hearts);
@@ -198,21 +305,21 @@ mod tests {
         let table = vec![(0, make_card(Suit::Hearts, 7))];
 
         // 手牌: 3H 9H QH 5D
-        let hand = vec![
+        let hand: CardSet = [
             make_card(Suit::Hearts, 3),
             make_card(Suit::Hearts, 9),
             make_card(Suit::Hearts, 12), // Q
             make_card(Suit::Diamonds, 5),
-        ];
+        ].into_iter().collect();
 
         // 合法牌 (跟牌必須出 Hearts)
-        let legal = vec![
+        let legal: CardSet = [
             make_card(Suit::Hearts, 3),
             make_card(Suit::Hearts, 9),
             make_card(Suit::Hearts, 12),
-        ];
+        ].into_iter().collect();
 
-        let result = strategy.choose_card(&hand, &legal, &table, false);
+        let result = strategy.choose_card(hand, legal, &table, false);
 
         // 需要 >= 7+3=10，9H 只有 9 點不夠，QH=12 符合
         assert_eq!(result.suit, Suit::Hearts);
@@ -227,18 +334,18 @@ mod tests {
         let table = vec![(0, make_card(Suit::Hearts, 7))];
 
         // 手牌: 3H 8H 5D (無法贏)
-        let hand = vec![
+        let hand: CardSet = [
             make_card(Suit::Hearts, 3),
             make_card(Suit::Hearts, 8),
             make_card(Suit::Diamonds, 5),
-        ];
+        ].into_iter().collect();
 
-        let legal = vec![
+        let legal: CardSet = [
             make_card(Suit::Hearts, 3),
             make_card(Suit::Hearts, 8),
-        ];
+        ].into_iter().collect();
 
-        let result = strategy.choose_card(&hand, &legal, &table, false);
+        let result = strategy.choose_card(hand, legal, &table, false);
 
         // 8H 只比 7H 大 1 點，不夠 +3，應該放棄出最小牌 3H
         assert_eq!(result.suit, Suit::Hearts);
@@ -253,28 +360,146 @@ mod tests {
         let table = vec![(0, make_card(Suit::Hearts, 7))];
 
         // 手牌: 2D 5S KC (無 Hearts)
-        let hand = vec![
+        let hand: CardSet = [
             make_card(Suit::Diamonds, 2),
             make_card(Suit::Spades, 5),
             make_card(Suit::Clubs, 13),
-        ];
+        ].into_iter().collect();
 
-        let result = strategy.choose_card(&hand, &hand, &table, false);
+        let result = strategy.choose_card(hand, hand, &table, false);
 
         // 無 Hearts，出最小牌 2D
         assert_eq!(result.suit, Suit::Diamonds);
         assert_eq!(result.rank.0, 2);
     }
 
+    #[test]
+    fn test_follower_ruffs_when_void() {
+        let strategy = SmartStrategy::with_trump(Some(Suit::Spades));
+
+        // 桌面: KH，手上沒有 Hearts
+        let table = vec![(0, make_card(Suit::Hearts, 13))];
+        let hand: CardSet = [
+            make_card(Suit::Diamonds, 2),
+            make_card(Suit::Spades, 9),
+            make_card(Suit::Spades, 4),
+        ].into_iter().collect();
+
+        let result = strategy.choose_card(hand, hand, &table, false);
+
+        // 用最小的王牌 4S 王吃
+        assert_eq!(result, make_card(Suit::Spades, 4));
+    }
+
+    #[test]
+    fn test_follower_overruffs() {
+        let strategy = SmartStrategy::with_trump(Some(Suit::Spades));
+
+        // 桌面: KH, 6S (已被王吃)
+        let table = vec![(0, make_card(Suit::Hearts, 13)), (1, make_card(Suit::Spades, 6))];
+        let hand: CardSet = [
+            make_card(Suit::Clubs, 3),
+            make_card(Suit::Spades, 4),
+            make_card(Suit::Spades, 10),
+        ].into_iter().collect();
+
+        let result = strategy.choose_card(hand, hand, &table, false);
+
+        // 4S 贏不了 6S，用 10S 超吃
+        assert_eq!(result, make_card(Suit::Spades, 10));
+    }
+
+    #[test]
+    fn test_follower_discards_when_cannot_overruff() {
+        let strategy = SmartStrategy::with_trump(Some(Suit::Spades));
+
+        // 桌面: KH, QS
+        let table = vec![(0, make_card(Suit::Hearts, 13)), (1, make_card(Suit::Spades, 12))];
+        let hand: CardSet = [
+            make_card(Suit::Clubs, 7),
+            make_card(Suit::Spades, 4),
+        ].into_iter().collect();
+
+        let result = strategy.choose_card(hand, hand, &table, false);
+
+        // 無法超吃，保留王牌，墊 7C
+        assert_eq!(result, make_card(Suit::Clubs, 7));
+    }
+
+    #[test]
+    fn test_follower_gives_up_after_ruff() {
+        let strategy = SmartStrategy::with_trump(Some(Suit::Spades));
+
+        // 桌面: 7H, 2S (已被王吃)
+        let table = vec![(0, make_card(Suit::Hearts, 7)), (1, make_card(Suit::Spades, 2))];
+        let hand: CardSet = [make_card(Suit::Hearts, 3), make_card(Suit::Hearts, 14)].into_iter().collect();
+
+        let result = strategy.choose_card(hand, hand, &table, false);
+
+        // 跟牌贏不了王牌，出最小的 3H
+        assert_eq!(result, make_card(Suit::Hearts, 3));
+    }
+
+    fn parse_hand(cards: &[&str]) -> CardSet {
+        cards.iter().map(|c| CardData::from_protocol_string(c).unwrap()).collect()
+    }
+
+    fn bridge_teams() -> Vec<crate::protocol::Team> {
+        use crate::protocol::Team;
+        vec![Team::Human, Team::Ai, Team::Human, Team::Ai]
+    }
+
+    #[test]
+    fn test_choose_call_openings() {
+        let strategy = SmartStrategy::new();
+        let auction = Auction::new(0, bridge_teams());
+
+        // 16 點平均牌型 → 1NT
+        let balanced = parse_hand(&[
+            "AS", "KS", "4S", "AH", "QH", "3H", "KD", "5D", "4D", "JC", "6C", "3C", "2C",
+        ]);
+        assert_eq!(strategy.choose_call(balanced, &auction, 0).to_protocol_string(), "1NT");
+
+        // 13 點，黑桃最長 → 1S
+        let spades = parse_hand(&[
+            "AS", "KS", "9S", "7S", "4S", "AH", "QH", "3H", "5D", "4D", "6C", "3C", "2C",
+        ]);
+        assert_eq!(strategy.choose_call(spades, &auction, 0).to_protocol_string(), "1S");
+
+        // 9 點 → PASS
+        let weak = parse_hand(&[
+            "KS", "9S", "7S", "4S", "AH", "8H", "3H", "5D", "4D", "QC", "6C", "3C", "2C",
+        ]);
+        assert_eq!(strategy.choose_call(weak, &auction, 0), Call::Pass);
+    }
+
+    #[test]
+    fn test_choose_call_raises_partner() {
+        let strategy = SmartStrategy::new();
+        let mut auction = Auction::new(0, bridge_teams());
+        auction.call(0, Call::Bid(Bid::from_protocol_string("1S").unwrap())).unwrap();
+        auction.call(1, Call::Pass).unwrap();
+
+        // 12 點、4 張黑桃支持 → 3S
+        let support = parse_hand(&[
+            "KS", "9S", "7S", "4S", "AH", "8H", "3H", "KD", "4D", "QC", "6C", "3C", "2C",
+        ]);
+        assert_eq!(strategy.choose_call(support, &auction, 2).to_protocol_string(), "3S");
+
+        // 叫出的叫品必須合法
+        let call = strategy.choose_call(support, &auction, 2);
+        assert!(auction.validate(2, call).is_ok());
+    }
+
     #[test]
     fn test_find_longest_suit_priority() {
         // 平手時 S > H > D > C
-        let hand = vec![
+        let hand: CardSet = [
             make_card(Suit::Hearts, 3),
             make_card(Suit::Spades, 5),
-        ];
+        ].into_iter().collect();
 
-        let result = SmartStrategy::find_longest_suit(&hand);
+        let result = SmartStrategy::find_longest_suit(hand);
         assert_eq!(result, Suit::Spades); // S 優先
     }
 }
@@ -0,0 +1,344 @@
+//! 賽後分析 (Post-game Analysis)
+//!
+//! 以這副牌的原始手牌逐張重播 trick 歷史，每出一張牌就用雙明手解算比較出牌前後
+//! 出牌方最多還能拿到的墩數；墩數下降的牌即為失誤，並找出一張能保住墩數的合法替代牌。
+
+use super::cardset::CardSet;
+use super::deck::{CardData, Suit};
+use super::engine::GameEngine;
+use super::solver::{self, Position, Solver, SolverError};
+use crate::protocol::{CostlyPlay, PlayerId, Score, ServerMessage, Team};
+use std::sync::mpsc;
+use std::thread;
+
+/// 排隊等待的解算工作上限 (佇列滿時拒絕新的工作)
+pub const MAX_QUEUED_JOBS: usize = 8;
+
+/// 重播需要的牌局記錄 (從引擎複製出來，可交給背景執行緒分析)
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    pub board: u32,
+    pub players: [PlayerId; 4],
+    /// 開始出牌時的手牌
+    pub hands: [CardSet; 4],
+    pub teams: [Team; 4],
+    pub trump: Option<Suit>,
+    /// 已打完的 trick：(贏家 index, 依出牌順序的牌)
+    pub tricks: Vec<(usize, Vec<CardData>)>,
+}
+
+/// 一副牌的分析結果
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    pub board: u32,
+    pub trump: Option<Suit>,
+    /// 首引前雙明手的墩數
+    pub par: Score,
+    /// 重播的 trick 中實際拿到的墩數
+    pub actual: Score,
+    /// 依出牌順序排列的失誤
+    pub mistakes: Vec<CostlyPlay>,
+}
+
+impl GameRecord {
+    pub fn from_engine(engine: &GameEngine) -> Result<Self, SolverError> {
+        let (hands, teams) = solver::dealt_hands(engine)?;
+        let players = [0, 1, 2, 3].map(|i| engine.players[i].player_id.clone());
+        let mut tricks = Vec::with_capacity(engine.history.len());
+        for trick in &engine.history {
+            let winner = players
+                .iter()
+                .position(|p| *p == trick.winner)
+                .ok_or(SolverError::InconsistentHands)?;
+            let cards = trick
+                .cards
+                .iter()
+                .map(|c| CardData::from_protocol_string(c).ok_or(SolverError::InconsistentHands))
+                .collect::<Result<Vec<_>, _>>()?;
+            tricks.push((winner, cards));
+        }
+        Ok(Self {
+            board: engine.board,
+            players,
+            hands,
+            teams,
+            trump: engine.trump,
+            tricks,
+        })
+    }
+
+    /// 發牌時持有這張牌的座位
+    fn owner(&self, card: CardData) -> Result<usize, SolverError> {
+        (0..4).find(|&i| self.hands[i].contains(card)).ok_or(SolverError::InconsistentHands)
+    }
+
+    /// 逐張重播並找出讓出牌方少拿墩數的牌
+    pub fn analyse(&self) -> Result<Analysis, SolverError> {
+        let first = self.tricks.first().and_then(|(_, cards)| cards.first());
+        let leader = match first {
+            Some(&card) => self.owner(card)?,
+            None => return Err(SolverError::NotPlaying),
+        };
+        let mut solver = Solver::new(self.trump, self.teams);
+        let mut position = Position::new(self.hands, self.teams, self.trump, leader);
+        // human = HUMAN 隊從目前局面 (含本 trick) 還能拿到的墩數
+        let mut human = solver.solve(&position, Team::Human)?;
+        let par = Score {
+            human,
+            ai: position.tricks_left() - human,
+        };
+        let mut actual = Score::default();
+        let mut mistakes = Vec::new();
+
+        for (t, (winner, cards)) in self.tricks.iter().enumerate() {
+            let leader = match cards.first() {
+                Some(&card) => self.owner(card)?,
+                None => return Err(SolverError::InconsistentHands),
+            };
+            position = Position::new(position.hands, self.teams, self.trump, leader);
+            let left = position.tricks_left();
+            // team 從 HUMAN 隊的墩數換算出的墩數
+            let value = |team: Team, human: u32| if team == Team::Human { human } else { left - human };
+
+            for &card in cards {
+                let seat = self.owner(card)?;
+                let team = self.teams[seat];
+                let after = solver.solve(&play(&position, seat, card), Team::Human)?;
+                let lost = value(team, human).saturating_sub(value(team, after));
+                if lost > 0 {
+                    let mut better = None;
+                    for alt in legal_cards(&position, seat).into_iter().filter(|&c| c != card) {
+                        let tricks = solver.solve(&play(&position, seat, alt), Team::Human)?;
+                        if value(team, tricks) >= value(team, human) {
+                            better = Some(alt);
+                            break;
+                        }
+                    }
+                    if let Some(better) = better {
+                        mistakes.push(CostlyPlay {
+                            trick: t as u32 + 1,
+                            player_id: self.players[seat].clone(),
+                            card: card.to_protocol_string(),
+                            better: better.to_protocol_string(),
+                            tricks_lost: lost,
+                        });
+                    }
+                }
+                position = play(&position, seat, card);
+                human = after;
+            }
+
+            match self.teams[*winner] {
+                Team::Human => {
+                    actual.human += 1;
+                    human = human.saturating_sub(1);
+                }
+                Team::Ai => actual.ai += 1,
+            }
+        }
+
+        Ok(Analysis {
+            board: self.board,
+            trump: self.trump,
+            par,
+            actual,
+            mistakes,
+        })
+    }
+}
+
+/// seat 出一張牌後的局面
+fn play(position: &Position, seat: usize, card: CardData) -> Position {
+    let mut next = position.clone();
+    next.hands[seat].remove(card);
+    next.table.push((seat, card));
+    next
+}
+
+/// seat 目前能出的牌 (有領牌花色就必須跟)
+fn legal_cards(position: &Position, seat: usize) -> Vec<CardData> {
+    let hand = position.hands[seat];
+    let follow: Vec<CardData> = match position.table.first() {
+        Some(&(_, lead)) => hand.iter().filter(|c| c.suit == lead.suit).collect(),
+        None => Vec::new(),
+    };
+    if follow.is_empty() { hand.iter().collect() } else { follow }
+}
+
+impl Analysis {
+    pub fn to_message(&self) -> ServerMessage {
+        ServerMessage::Analysis {
+            board: self.board,
+            trump: self.trump.map(|s| s.symbol().to_string()),
+            par: self.par.clone(),
+            actual: self.actual.clone(),
+            mistakes: self.mistakes.clone(),
+        }
+    }
+
+    /// 管理介面用的文字報告
+    pub fn format(&self) -> String {
+        let trump = self.trump.map(|s| s.symbol().to_string()).unwrap_or_else(|| "NT".to_string());
+        let mut out = format!(
+            "Trump {}: double dummy HUMAN {}, AI {}; played HUMAN {}, AI {}\n",
+            trump, self.par.human, self.par.ai, self.actual.human, self.actual.ai
+        );
+        if self.mistakes.is_empty() {
+            out.push_str("No costly plays.\n");
+        }
+        for m in &self.mistakes {
+            out.push_str(&format!(
+                "Trick {:>2}  {} played {:<3} {:<3} was better (-{} trick{})\n",
+                m.trick,
+                m.player_id,
+                m.card,
+                m.better,
+                m.tricks_lost,
+                if m.tricks_lost == 1 { "" } else { "s" }
+            ));
+        }
+        out
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// 雙明手解算的背景執行緒：所有房間的分析依序在同一條執行緒執行，
+/// 不會因為房間多而同時佔用大量 CPU
+pub struct Worker {
+    tx: mpsc::SyncSender<Job>,
+}
+
+impl Worker {
+    /// 啟動背景執行緒，最多排 `capacity` 個等待中的工作
+    pub fn spawn(capacity: usize) -> Self {
+        let (tx, rx) = mpsc::sync_channel::<Job>(capacity);
+        thread::Builder::new()
+            .name("analysis".to_string())
+            .spawn(move || {
+                for job in rx {
+                    job();
+                }
+            })
+            .expect("Failed to spawn analysis worker");
+        Self { tx }
+    }
+
+    /// 排入一個工作；佇列已滿時回傳 false (工作不會執行)
+    pub fn submit(&self, job: impl FnOnce() + Send + 'static) -> bool {
+        self.tx.try_send(Box::new(job)).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::testing;
+
+    fn card(s: &str) -> CardData {
+        CardData::from_protocol_string(s).unwrap()
+    }
+
+    fn cards(list: &[&str]) -> Vec<CardData> {
+        list.iter().map(|c| card(c)).collect()
+    }
+
+    /// 兩張牌的殘局 (王牌 H，P1 首引)：P4 第一 trick 就該用 2H 王吃
+    fn deal() -> Vec<Vec<CardData>> {
+        vec![cards(&["AS", "KS"]), cards(&["2D", "3D"]), cards(&["QS", "JS"]), cards(&["2H", "4D"])]
+    }
+
+    fn record(tricks: Vec<(usize, &[&str])>) -> GameRecord {
+        let hands = deal();
+        GameRecord {
+            board: 1,
+            players: ["P1", "P2", "P3", "P4"].map(String::from),
+            hands: [0, 1, 2, 3].map(|i| hands[i].iter().collect()),
+            teams: [Team::Human, Team::Human, Team::Ai, Team::Ai],
+            trump: Some(Suit::Hearts),
+            tricks: tricks.into_iter().map(|(w, c)| (w, cards(c))).collect(),
+        }
+    }
+
+    #[test]
+    fn test_best_play_has_no_mistakes() {
+        let analysis = record(vec![(3, &["AS", "2D", "QS", "2H"]), (3, &["KS", "3D", "JS", "4D"])])
+            .analyse()
+            .unwrap();
+        assert_eq!((analysis.par.human, analysis.par.ai), (0, 2));
+        assert_eq!((analysis.actual.human, analysis.actual.ai), (0, 2));
+        assert!(analysis.mistakes.is_empty());
+        assert!(analysis.format().contains("No costly plays."));
+    }
+
+    #[test]
+    fn test_flags_costly_card_with_alternative() {
+        let analysis = record(vec![(0, &["AS", "2D", "QS", "4D"]), (3, &["KS", "3D", "JS", "2H"])])
+            .analyse()
+            .unwrap();
+        assert_eq!((analysis.actual.human, analysis.actual.ai), (1, 1));
+        assert_eq!(
+            analysis.mistakes,
+            vec![CostlyPlay {
+                trick: 1,
+                player_id: "P4".to_string(),
+                card: "4D".to_string(),
+                better: "2H".to_string(),
+                tricks_lost: 1,
+            }]
+        );
+        assert!(analysis.format().contains("Trick  1  P4 played 4D  2H  was better (-1 trick)"));
+    }
+
+    #[test]
+    fn test_analyse_engine_game() {
+        let mut engine = testing::new_engine(7);
+        engine.trump = Some(Suit::Hearts);
+        engine.deal_hands(deal());
+        for trick in [["AS", "2D", "QS", "4D"], ["KS", "3D", "JS", "2H"]] {
+            for c in trick {
+                let idx = engine.current_player_idx().unwrap();
+                engine.play_card(idx, card(c));
+            }
+            engine.resolve_trick();
+        }
+        assert!(engine.is_game_over());
+
+        let analysis = GameRecord::from_engine(&engine).unwrap().analyse().unwrap();
+        assert_eq!(analysis.mistakes.len(), 1);
+        assert_eq!(analysis.mistakes[0].player_id, "P4");
+        match analysis.to_message() {
+            ServerMessage::Analysis { board, trump, par, .. } => {
+                assert_eq!(board, 1);
+                assert_eq!(trump.as_deref(), Some("H"));
+                assert_eq!((par.human, par.ai), (0, 2));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_needs_a_played_trick() {
+        assert_eq!(record(vec![]).analyse().unwrap_err(), SolverError::NotPlaying);
+    }
+
+    #[test]
+    fn test_worker_rejects_when_queue_is_full() {
+        let worker = Worker::spawn(1);
+        let (started_tx, started_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        assert!(worker.submit(move || {
+            started_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        }));
+        started_rx.recv().unwrap();
+
+        // 執行中的工作不佔佇列；再排一個就滿了
+        let (done_tx, done_rx) = mpsc::channel();
+        assert!(worker.submit(move || done_tx.send(()).unwrap()));
+        assert!(!worker.submit(|| {}));
+
+        release_tx.send(()).unwrap();
+        done_rx.recv().unwrap();
+    }
+}
@@ -0,0 +1,431 @@
+//! 叫牌 (Auction)
+//!
+//! 合約橋牌的叫牌階段：從莊家 (dealer) 開始順時針叫牌，
+//! 最後的合約決定主打者 (declarer)、王牌與首引者。
+
+use super::deck::Suit;
+use crate::protocol::Team;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 王牌種類 (依叫牌順序由低到高: C < D < H < S < NT)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Strain {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+    NoTrump,
+}
+
+impl Strain {
+    /// 對應的王牌花色 (NT 為 None)
+    pub fn trump(self) -> Option<Suit> {
+        match self {
+            Strain::Clubs => Some(Suit::Clubs),
+            Strain::Diamonds => Some(Suit::Diamonds),
+            Strain::Hearts => Some(Suit::Hearts),
+            Strain::Spades => Some(Suit::Spades),
+            Strain::NoTrump => None,
+        }
+    }
+
+    /// 由王牌花色取得 Strain
+    pub fn from_trump(trump: Option<Suit>) -> Strain {
+        match trump {
+            Some(Suit::Clubs) => Strain::Clubs,
+            Some(Suit::Diamonds) => Strain::Diamonds,
+            Some(Suit::Hearts) => Strain::Hearts,
+            Some(Suit::Spades) => Strain::Spades,
+            None => Strain::NoTrump,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Strain::Clubs => "C",
+            Strain::Diamonds => "D",
+            Strain::Hearts => "H",
+            Strain::Spades => "S",
+            Strain::NoTrump => "NT",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Strain> {
+        match s.to_uppercase().as_str() {
+            "C" => Some(Strain::Clubs),
+            "D" => Some(Strain::Diamonds),
+            "H" => Some(Strain::Hearts),
+            "S" => Some(Strain::Spades),
+            "NT" | "N" => Some(Strain::NoTrump),
+            _ => None,
+        }
+    }
+}
+
+/// 叫品 (e.g., 1C, 3NT, 7S)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Bid {
+    /// 線位 (1-7)，欄位順序決定比較大小: 先比線位再比 Strain
+    pub level: u8,
+    pub strain: Strain,
+}
+
+impl Bid {
+    pub fn new(level: u8, strain: Strain) -> Option<Bid> {
+        (1..=7).contains(&level).then_some(Bid { level, strain })
+    }
+
+    /// 從協議字串解析 (e.g., "1S", "3NT")
+    pub fn from_protocol_string(s: &str) -> Option<Bid> {
+        let s = s.trim();
+        let level = s.chars().next()?.to_digit(10)? as u8;
+        let strain = Strain::from_str(&s[1..])?;
+        Bid::new(level, strain)
+    }
+}
+
+impl fmt::Display for Bid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.level, self.strain.symbol())
+    }
+}
+
+/// 叫牌動作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Call {
+    Pass,
+    Bid(Bid),
+    Double,
+    Redouble,
+}
+
+impl Call {
+    /// 協議表示: "PASS" / "1S" / "X" / "XX"
+    pub fn to_protocol_string(self) -> String {
+        match self {
+            Call::Pass => "PASS".to_string(),
+            Call::Bid(bid) => bid.to_string(),
+            Call::Double => "X".to_string(),
+            Call::Redouble => "XX".to_string(),
+        }
+    }
+}
+
+/// 賭倍狀態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Doubling {
+    Undoubled,
+    Doubled,
+    Redoubled,
+}
+
+/// 最終合約
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contract {
+    pub level: u8,
+    pub strain: Strain,
+    pub doubling: Doubling,
+    /// 主打者 index
+    pub declarer: usize,
+}
+
+impl Contract {
+    /// 合約需要拿到的墩數 (6 + 線位)
+    pub fn tricks_required(&self) -> u32 {
+        6 + self.level as u32
+    }
+}
+
+impl fmt::Display for Contract {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suffix = match self.doubling {
+            Doubling::Undoubled => "",
+            Doubling::Doubled => "X",
+            Doubling::Redoubled => "XX",
+        };
+        write!(f, "{}{}{}", self.level, self.strain.symbol(), suffix)
+    }
+}
+
+/// 叫牌錯誤
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuctionError {
+    /// 不在遊戲中
+    NotInGame,
+    /// 目前不是叫牌階段
+    NotBidding,
+    /// 不是你的回合
+    NotYourTurn,
+    /// 叫品必須高於上一個叫品
+    InsufficientBid,
+    /// 只能賭倍對手的叫品
+    CannotDouble,
+    /// 只能再賭倍對手的賭倍
+    CannotRedouble,
+}
+
+/// 叫牌進度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuctionStatus {
+    /// 叫牌中
+    Open,
+    /// 叫牌結束，產生合約
+    Contract(Contract),
+    /// 四家都 PASS
+    PassedOut,
+}
+
+/// 叫牌過程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Auction {
+    /// 莊家 (第一個叫牌者) index
+    dealer: usize,
+    /// 各座位的隊伍 (用於判斷對手)
+    teams: Vec<Team>,
+    /// 依序的叫牌 (第 i 個由 (dealer + i) % n 叫出)
+    calls: Vec<Call>,
+}
+
+impl Auction {
+    pub fn new(dealer: usize, teams: Vec<Team>) -> Self {
+        Self {
+            dealer,
+            teams,
+            calls: Vec::new(),
+        }
+    }
+
+    /// 第 i 個叫牌的座位
+    pub fn seat_of(&self, call_idx: usize) -> usize {
+        (self.dealer + call_idx) % self.teams.len()
+    }
+
+    /// 目前輪到叫牌的座位
+    pub fn current_bidder(&self) -> usize {
+        self.seat_of(self.calls.len())
+    }
+
+    /// 所有叫牌 (seat, call)
+    pub fn calls(&self) -> Vec<(usize, Call)> {
+        self.calls
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (self.seat_of(i), *c))
+            .collect()
+    }
+
+    /// 兩個座位是否為同伴
+    pub fn is_partner(&self, a: usize, b: usize) -> bool {
+        self.teams[a] == self.teams[b]
+    }
+
+    /// 最後一個叫品 (seat, bid)
+    pub fn last_bid(&self) -> Option<(usize, Bid)> {
+        self.calls
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(i, c)| match c {
+                Call::Bid(bid) => Some((self.seat_of(i), *bid)),
+                _ => None,
+            })
+    }
+
+    /// 最後一個非 PASS 的叫牌 (seat, call)
+    fn last_action(&self) -> Option<(usize, Call)> {
+        self.calls
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, c)| **c != Call::Pass)
+            .map(|(i, c)| (self.seat_of(i), *c))
+    }
+
+    /// 驗證 seat 的叫牌是否合法
+    pub fn validate(&self, seat: usize, call: Call) -> Result<(), AuctionError> {
+        if self.status() != AuctionStatus::Open {
+            return Err(AuctionError::NotBidding);
+        }
+        if seat != self.current_bidder() {
+            return Err(AuctionError::NotYourTurn);
+        }
+
+        match call {
+            Call::Pass => Ok(()),
+            Call::Bid(bid) => match self.last_bid() {
+                Some((_, last)) if bid <= last => Err(AuctionError::InsufficientBid),
+                _ => Ok(()),
+            },
+            Call::Double => match self.last_action() {
+                Some((by, Call::Bid(_))) if !self.is_partner(by, seat) => Ok(()),
+                _ => Err(AuctionError::CannotDouble),
+            },
+            Call::Redouble => match self.last_action() {
+                Some((by, Call::Double)) if !self.is_partner(by, seat) => Ok(()),
+                _ => Err(AuctionError::CannotRedouble),
+            },
+        }
+    }
+
+    /// 執行叫牌
+    pub fn call(&mut self, seat: usize, call: Call) -> Result<AuctionStatus, AuctionError> {
+        self.validate(seat, call)?;
+        self.calls.push(call);
+        Ok(self.status())
+    }
+
+    /// 目前叫牌進度
+    pub fn status(&self) -> AuctionStatus {
+        let n = self.calls.len();
+        let trailing_passes = self.calls.iter().rev().take_while(|c| **c == Call::Pass).count();
+
+        match self.last_bid() {
+            None if n >= self.teams.len() => AuctionStatus::PassedOut,
+            Some(_) if trailing_passes >= self.teams.len() - 1 => {
+                AuctionStatus::Contract(self.contract().unwrap())
+            }
+            _ => AuctionStatus::Open,
+        }
+    }
+
+    /// 依目前叫牌得出的合約 (不論叫牌是否結束)
+    pub fn contract(&self) -> Option<Contract> {
+        let (bid_seat, bid) = self.last_bid()?;
+
+        let doubling = match self.last_action() {
+            Some((_, Call::Double)) => Doubling::Doubled,
+            Some((_, Call::Redouble)) => Doubling::Redoubled,
+            _ => Doubling::Undoubled,
+        };
+
+        // 主打者: 該方第一個叫出此 Strain 的人
+        let declarer = self
+            .calls()
+            .into_iter()
+            .find(|(seat, c)| {
+                self.is_partner(*seat, bid_seat)
+                    && matches!(c, Call::Bid(b) if b.strain == bid.strain)
+            })
+            .map(|(seat, _)| seat)
+            .unwrap_or(bid_seat);
+
+        Some(Contract {
+            level: bid.level,
+            strain: bid.strain,
+            doubling,
+            declarer,
+        })
+    }
+
+    /// 在指定 Strain 中目前可叫的最低叫品
+    pub fn lowest_bid_in(&self, strain: Strain) -> Option<Bid> {
+        match self.last_bid() {
+            None => Bid::new(1, strain),
+            Some((_, last)) => {
+                let same_level = Bid::new(last.level, strain)?;
+                if same_level > last {
+                    Some(same_level)
+                } else {
+                    Bid::new(last.level + 1, strain)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn teams() -> Vec<Team> {
+        vec![Team::Human, Team::Ai, Team::Human, Team::Ai]
+    }
+
+    fn bid(s: &str) -> Call {
+        Call::Bid(Bid::from_protocol_string(s).unwrap())
+    }
+
+    #[test]
+    fn test_bid_ordering_and_parsing() {
+        assert!(Bid::from_protocol_string("1NT").unwrap() > Bid::from_protocol_string("1S").unwrap());
+        assert!(Bid::from_protocol_string("2C").unwrap() > Bid::from_protocol_string("1NT").unwrap());
+        assert_eq!(Bid::from_protocol_string("3nt").unwrap().to_string(), "3NT");
+        assert!(Bid::from_protocol_string("8C").is_none());
+        assert!(Bid::from_protocol_string("0S").is_none());
+        assert!(Bid::from_protocol_string("2X").is_none());
+    }
+
+    #[test]
+    fn test_bid_must_be_higher() {
+        let mut auction = Auction::new(0, teams());
+        auction.call(0, bid("1S")).unwrap();
+
+        assert_eq!(auction.validate(1, bid("1H")), Err(AuctionError::InsufficientBid));
+        assert_eq!(auction.validate(1, bid("1S")), Err(AuctionError::InsufficientBid));
+        assert!(auction.validate(1, bid("1NT")).is_ok());
+        assert_eq!(auction.validate(2, Call::Pass), Err(AuctionError::NotYourTurn));
+    }
+
+    #[test]
+    fn test_double_only_against_opponents() {
+        let mut auction = Auction::new(0, teams());
+        // 沒有叫品不能賭倍
+        assert_eq!(auction.validate(0, Call::Double), Err(AuctionError::CannotDouble));
+
+        auction.call(0, bid("1S")).unwrap();
+        auction.call(1, Call::Pass).unwrap();
+        // 不能賭倍同伴
+        assert_eq!(auction.validate(2, Call::Double), Err(AuctionError::CannotDouble));
+        auction.call(2, Call::Pass).unwrap();
+        // 對手可以賭倍 (中間隔著 PASS 也可以)
+        auction.call(3, Call::Double).unwrap();
+        // 不能再賭倍自己的賭倍 / 同伴不能再次賭倍
+        assert_eq!(auction.validate(0, Call::Double), Err(AuctionError::CannotDouble));
+        auction.call(0, Call::Redouble).unwrap();
+        assert_eq!(auction.validate(1, Call::Redouble), Err(AuctionError::CannotRedouble));
+    }
+
+    #[test]
+    fn test_contract_and_declarer() {
+        let mut auction = Auction::new(1, teams());
+        auction.call(1, Call::Pass).unwrap();
+        auction.call(2, bid("1H")).unwrap();
+        auction.call(3, Call::Pass).unwrap();
+        auction.call(0, bid("4H")).unwrap();
+        auction.call(1, Call::Double).unwrap();
+        auction.call(2, Call::Pass).unwrap();
+        auction.call(3, Call::Pass).unwrap();
+        let status = auction.call(0, Call::Pass).unwrap();
+
+        // 同伴 (seat 2) 先叫出 Hearts，所以是主打者
+        let expected = Contract {
+            level: 4,
+            strain: Strain::Hearts,
+            doubling: Doubling::Doubled,
+            declarer: 2,
+        };
+        assert_eq!(status, AuctionStatus::Contract(expected));
+        assert_eq!(expected.to_string(), "4HX");
+        assert_eq!(expected.tricks_required(), 10);
+        assert_eq!(auction.validate(1, Call::Pass), Err(AuctionError::NotBidding));
+    }
+
+    #[test]
+    fn test_passed_out() {
+        let mut auction = Auction::new(2, teams());
+        for seat in [2, 3, 0] {
+            assert_eq!(auction.call(seat, Call::Pass).unwrap(), AuctionStatus::Open);
+        }
+        assert_eq!(auction.call(1, Call::Pass).unwrap(), AuctionStatus::PassedOut);
+    }
+
+    #[test]
+    fn test_lowest_bid_in() {
+        let mut auction = Auction::new(0, teams());
+        assert_eq!(auction.lowest_bid_in(Strain::Clubs).unwrap().to_string(), "1C");
+        auction.call(0, bid("1H")).unwrap();
+        assert_eq!(auction.lowest_bid_in(Strain::Spades).unwrap().to_string(), "1S");
+        assert_eq!(auction.lowest_bid_in(Strain::Diamonds).unwrap().to_string(), "2D");
+    }
+}
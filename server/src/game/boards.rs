@@ -0,0 +1,321 @@
+//! 牌組來源 (Board Source)
+//!
+//! 每副牌的手牌預設由 seed 洗牌 (`Deck::shuffle`)；房間也可以改用預先載入的牌組
+//! (PBN 檔或 JSON 牌型列表)，依牌號取出同一副牌，讓每一桌打相同的牌 (複式)，
+//! 或以 seed 產生符合條件的牌 (見 [`super::dealgen`])。
+//!
+//! JSON 牌組為陣列，每個元素是 52 張牌 (前 13 張給 N，其次 E、S、W)，牌號依序為 1, 2, ...；
+//! 也可以寫成 `{"board": 7, "cards": [...]}` 指定牌號。
+//! 莊家與身價一律依牌號決定 (與洗牌時相同)，PBN 的 `[Dealer]` 不影響發牌。
+
+use super::dealgen::{DealConstraints, DealGenError};
+use super::deck::CardData;
+use super::engine::GameEngine;
+use super::pbn::{self, PbnDeal, PbnError};
+use crate::net::ConnectionId;
+use crate::protocol::ServerMessage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// 牌組載入或發牌錯誤
+#[derive(Debug)]
+pub enum BoardError {
+    Io(io::Error),
+    Pbn(PbnError),
+    Json(serde_json::Error),
+    /// 第 n 個 (1-based) JSON 牌型不是 52 張不重複的牌
+    InvalidLayout(usize),
+    /// 同一個牌號出現兩次
+    DuplicateBoard(u32),
+    /// 牌組中沒有這個牌號
+    UnknownBoard(u32),
+    /// 條件發牌失敗
+    Constraints(DealGenError),
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::Io(e) => write!(f, "I/O error: {}", e),
+            BoardError::Pbn(e) => write!(f, "{}", e),
+            BoardError::Json(e) => write!(f, "invalid deal list: {}", e),
+            BoardError::InvalidLayout(n) => write!(f, "layout #{} is not 52 distinct cards", n),
+            BoardError::DuplicateBoard(b) => write!(f, "board {} appears twice", b),
+            BoardError::UnknownBoard(b) => write!(f, "deal set has no board {}", b),
+            BoardError::Constraints(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<io::Error> for BoardError {
+    fn from(e: io::Error) -> Self {
+        BoardError::Io(e)
+    }
+}
+
+impl From<PbnError> for BoardError {
+    fn from(e: PbnError) -> Self {
+        BoardError::Pbn(e)
+    }
+}
+
+impl From<DealGenError> for BoardError {
+    fn from(e: DealGenError) -> Self {
+        BoardError::Constraints(e)
+    }
+}
+
+impl From<serde_json::Error> for BoardError {
+    fn from(e: serde_json::Error) -> Self {
+        BoardError::Json(e)
+    }
+}
+
+/// JSON 牌組中的一個牌型
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonLayout {
+    Cards(Vec<CardData>),
+    Board { board: u32, cards: Vec<CardData> },
+}
+
+/// 預先載入的牌組
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DealSet {
+    /// 牌組名稱 (檔名，不含副檔名)
+    pub name: String,
+    /// 每副牌 (牌號皆已填入)
+    pub boards: Vec<PbnDeal>,
+}
+
+impl DealSet {
+    /// 建立牌組 (沒有 `[Board]` 的牌依出現順序編號)
+    fn new(name: &str, mut boards: Vec<PbnDeal>) -> Result<Self, BoardError> {
+        let mut seen = HashSet::new();
+        for (i, deal) in boards.iter_mut().enumerate() {
+            let board = *deal.board.get_or_insert(i as u32 + 1);
+            if !seen.insert(board) {
+                return Err(BoardError::DuplicateBoard(board));
+            }
+        }
+        Ok(Self {
+            name: name.to_string(),
+            boards,
+        })
+    }
+
+    /// 從 PBN 文字讀取
+    pub fn from_pbn(name: &str, text: &str) -> Result<Self, BoardError> {
+        Self::new(name, pbn::read_deals(text)?)
+    }
+
+    /// 從 JSON 牌型列表讀取
+    pub fn from_json(name: &str, text: &str) -> Result<Self, BoardError> {
+        let layouts: Vec<JsonLayout> = serde_json::from_str(text)?;
+        let mut boards = Vec::with_capacity(layouts.len());
+        for (i, layout) in layouts.into_iter().enumerate() {
+            let (board, cards) = match layout {
+                JsonLayout::Cards(cards) => (None, cards),
+                JsonLayout::Board { board, cards } => (Some(board), cards),
+            };
+            let distinct: HashSet<&CardData> = cards.iter().collect();
+            if cards.len() != 52 || distinct.len() != 52 {
+                return Err(BoardError::InvalidLayout(i + 1));
+            }
+            let mut hands: [Vec<CardData>; 4] = Default::default();
+            for (seat, hand) in cards.chunks(13).enumerate() {
+                hands[seat] = hand.to_vec();
+            }
+            boards.push(PbnDeal {
+                board,
+                dealer: None,
+                hands,
+            });
+        }
+        Self::new(name, boards)
+    }
+
+    /// 讀取牌組檔 (副檔名 `.json` 為 JSON 牌型列表，其餘視為 PBN)
+    pub fn load(path: &Path) -> Result<Self, BoardError> {
+        let text = fs::read_to_string(path)?;
+        let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            Self::from_json(&name, &text)
+        } else {
+            Self::from_pbn(&name, &text)
+        }
+    }
+
+    /// 依牌號取出一副牌
+    pub fn get(&self, board: u32) -> Option<&PbnDeal> {
+        self.boards.iter().find(|d| d.board == Some(board))
+    }
+}
+
+/// 每副牌的手牌來源
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BoardSource {
+    /// 以 seed 洗牌
+    #[default]
+    Shuffle,
+    /// 從預設牌組依牌號取牌
+    DealSet(DealSet),
+    /// 以 seed 產生符合條件的牌
+    Constrained(DealConstraints),
+}
+
+impl BoardSource {
+    /// 牌組名稱 (洗牌時為 None)
+    pub fn deal_set_name(&self) -> Option<&str> {
+        match self {
+            BoardSource::DealSet(set) => Some(&set.name),
+            _ => None,
+        }
+    }
+
+    /// 全 PASS 時是否換 seed 重發 (預設牌組與限制條件的牌號對應固定的牌，改記為流局)
+    pub fn redeals_passed_out(&self) -> bool {
+        matches!(self, BoardSource::Shuffle)
+    }
+
+    /// 為引擎目前的牌號與 seed 發牌
+    pub fn deal(&self, engine: &mut GameEngine) -> Result<Vec<(ConnectionId, ServerMessage)>, BoardError> {
+        match self {
+            BoardSource::Shuffle => {
+                engine.deal_set = None;
+                Ok(engine.deal())
+            }
+            BoardSource::DealSet(set) => {
+                let deal = set.get(engine.board).ok_or(BoardError::UnknownBoard(engine.board))?;
+                let messages = pbn::deal_from_pbn(engine, deal)?;
+                engine.deal_set = Some(set.name.clone());
+                Ok(messages)
+            }
+            BoardSource::Constrained(constraints) => {
+                let deal = constraints.generate(engine.seed)?;
+                engine.deal_set = None;
+                Ok(pbn::deal_from_pbn(engine, &deal)?)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::cardset::CardSet;
+    use crate::game::deck::Deck;
+    use crate::game::auction::Call;
+    use crate::game::match_state::MatchState;
+    use crate::game::testing;
+
+    fn new_engine(board: u32) -> GameEngine {
+        let mut engine = testing::new_engine(board as u64);
+        engine.board = board;
+        engine
+    }
+
+    /// 以 seed 洗好的 52 張牌
+    fn layout(seed: u64) -> Vec<String> {
+        let mut deck = Deck::new();
+        deck.shuffle(seed);
+        deck.deal(1, 52)[0].iter().map(|c| c.to_protocol_string()).collect()
+    }
+
+    fn layout_json(seed: u64) -> String {
+        serde_json::to_string(&layout(seed)).unwrap()
+    }
+
+    #[test]
+    fn test_json_deal_set() {
+        let text = format!("[{}, {{\"board\": 7, \"cards\": {}}}]", layout_json(1), layout_json(2));
+        let set = DealSet::from_json("club", &text).unwrap();
+        assert_eq!(set.boards.len(), 2);
+        assert!(set.get(1).is_some());
+        assert!(set.get(7).is_some());
+        assert!(set.get(2).is_none());
+
+        // 前 13 張給 N (P1)
+        let mut engine = new_engine(1);
+        let source = BoardSource::DealSet(set.clone());
+        source.deal(&mut engine).unwrap();
+        let north: CardSet = set.get(1).unwrap().hands[0].iter().collect();
+        assert_eq!(engine.players[0].hand, north);
+        assert_eq!(engine.deal_set.as_deref(), Some("club"));
+
+        let mut missing = new_engine(3);
+        assert!(matches!(source.deal(&mut missing), Err(BoardError::UnknownBoard(3))));
+    }
+
+    #[test]
+    fn test_invalid_json_layouts() {
+        assert!(matches!(DealSet::from_json("x", "[[\"AS\"]]"), Err(BoardError::InvalidLayout(1))));
+        let mut repeated = layout(2);
+        repeated[1] = repeated[0].clone();
+        let text = format!("[{}, {}]", layout_json(1), serde_json::to_string(&repeated).unwrap());
+        assert!(matches!(DealSet::from_json("x", &text), Err(BoardError::InvalidLayout(2))));
+
+        let twice = format!("[{}, {{\"board\": 1, \"cards\": {}}}]", layout_json(1), layout_json(2));
+        assert!(matches!(DealSet::from_json("x", &twice), Err(BoardError::DuplicateBoard(1))));
+        assert!(matches!(DealSet::from_json("x", "{}"), Err(BoardError::Json(_))));
+    }
+
+    #[test]
+    fn test_pbn_deal_set_numbers_boards() {
+        let deal = "N:AKQJ.T98.765.432 T98.765.432.AKQJ 765.432.AKQJ.T98 432.AKQJ.T98.765";
+        let text = format!("[Board \"5\"]\n[Deal \"{}\"]\n\n[Deal \"{}\"]\n", deal, deal);
+        let set = DealSet::from_pbn("night", &text).unwrap();
+        assert_eq!(set.boards[0].board, Some(5));
+        assert_eq!(set.boards[1].board, Some(2));
+
+        // 每桌拿到相同的牌
+        let source = BoardSource::DealSet(set);
+        let (mut a, mut b) = (new_engine(5), new_engine(5));
+        source.deal(&mut a).unwrap();
+        source.deal(&mut b).unwrap();
+        assert_eq!(a.dealt_hands, b.dealt_hands);
+        assert_eq!(source.deal_set_name(), Some("night"));
+
+        // 全 PASS 不重發，記為沒有合約的 0 分流局
+        assert!(!source.redeals_passed_out());
+        a.bidding = true;
+        source.deal(&mut a).unwrap();
+        for idx in 0..4 {
+            a.make_call((a.dealer_idx + idx) % 4, Call::Pass);
+        }
+        a.close_passed_out();
+        assert!(a.is_game_over());
+        let mut match_state = MatchState::new(2);
+        let result = match_state.record(&a);
+        assert_eq!((result.board, result.contract.as_deref(), result.result.as_deref()), (5, None, None));
+        assert_eq!((result.score.human, result.score.ai), (0, 0));
+        assert!(!match_state.is_over());
+    }
+
+    #[test]
+    fn test_constrained_source() {
+        let constraints = DealConstraints::parse("N:hcp=20+").unwrap();
+        let source = BoardSource::Constrained(constraints);
+        let (mut a, mut b) = (new_engine(1), new_engine(1));
+        source.deal(&mut a).unwrap();
+        source.deal(&mut b).unwrap();
+        assert_eq!(a.dealt_hands, b.dealt_hands);
+        assert!(constraints.seats[0].accepts(&a.players[0].hand.to_vec()));
+        assert_eq!(source.deal_set_name(), None);
+    }
+
+    #[test]
+    fn test_shuffle_source_uses_seed() {
+        let mut engine = new_engine(1);
+        BoardSource::Shuffle.deal(&mut engine).unwrap();
+        let mut expected = new_engine(1);
+        expected.deal();
+        assert_eq!(engine.dealt_hands, expected.dealt_hands);
+        assert_eq!(engine.deal_set, None);
+        assert!(BoardSource::Shuffle.redeals_passed_out());
+    }
+}
@@ -0,0 +1,253 @@
+//! 牌的位元集合 (Card Set)
+//!
+//! 52 張牌各佔 u64 的一個 bit：花色依 S, H, D, C 各佔連續 13 bits，同花色內由 2 到 A
+//! 從低位排到高位。聯集、交集、張數與取同花色的牌都只是位元運算，
+//! 引擎的手牌、合法出牌與 AI 策略都以此表示；對外 (協議、快照) 仍是牌的字串列表。
+
+use super::deck::{CardData, Rank, Suit};
+use crate::protocol::Card;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::{BitAnd, BitOr, BitOrAssign, Not, Sub};
+
+/// 牌的位元集合
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CardSet(pub u64);
+
+/// 單一花色的 13 bits
+const SUIT_BITS: u64 = (1 << 13) - 1;
+
+impl CardSet {
+    pub const EMPTY: CardSet = CardSet(0);
+    /// 整副 52 張
+    pub const FULL: CardSet = CardSet((1 << 52) - 1);
+
+    /// 牌對應的 bit 位置
+    pub fn index(card: CardData) -> u32 {
+        card.suit as u32 * 13 + (card.rank.0 as u32 - 2)
+    }
+
+    /// bit 位置對應的牌
+    pub fn card_at(index: u32) -> CardData {
+        CardData::new(Suit::all()[(index / 13) as usize], Rank((index % 13 + 2) as u8))
+    }
+
+    /// 單張牌的集合
+    pub fn single(card: CardData) -> CardSet {
+        CardSet(1 << Self::index(card))
+    }
+
+    /// 某花色全部 13 張
+    pub fn suit_mask(suit: Suit) -> CardSet {
+        CardSet(SUIT_BITS << (suit as u32 * 13))
+    }
+
+    pub fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, card: CardData) -> bool {
+        self.0 & (1 << Self::index(card)) != 0
+    }
+
+    pub fn insert(&mut self, card: CardData) {
+        self.0 |= 1 << Self::index(card);
+    }
+
+    pub fn remove(&mut self, card: CardData) {
+        self.0 &= !(1 << Self::index(card));
+    }
+
+    /// 集合中某花色的牌
+    pub fn of_suit(self, suit: Suit) -> CardSet {
+        self & Self::suit_mask(suit)
+    }
+
+    /// 集合中某花色的張數
+    pub fn suit_len(self, suit: Suit) -> u32 {
+        self.of_suit(suit).len()
+    }
+
+    /// 某花色最大的牌
+    pub fn highest_in(self, suit: Suit) -> Option<CardData> {
+        let bits = self.of_suit(suit).0;
+        (bits != 0).then(|| Self::card_at(63 - bits.leading_zeros()))
+    }
+
+    /// 某花色最小的牌
+    pub fn lowest_in(self, suit: Suit) -> Option<CardData> {
+        let bits = self.of_suit(suit).0;
+        (bits != 0).then(|| Self::card_at(bits.trailing_zeros()))
+    }
+
+    /// 點數最小的牌 (同點數時依 S, H, D, C 順序)
+    pub fn lowest(self) -> Option<CardData> {
+        self.iter().min_by_key(|c| c.rank.0)
+    }
+
+    /// 依 bit 順序的第一張牌 (S 的 2 最先，C 的 A 最後)
+    pub fn first(self) -> Option<CardData> {
+        (self.0 != 0).then(|| Self::card_at(self.0.trailing_zeros()))
+    }
+
+    /// 依 bit 順序逐張列出 (同花色由小到大，花色依 S, H, D, C)
+    pub fn iter(self) -> impl Iterator<Item = CardData> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            (bits != 0).then(|| {
+                let index = bits.trailing_zeros();
+                bits &= bits - 1;
+                Self::card_at(index)
+            })
+        })
+    }
+
+    pub fn to_vec(self) -> Vec<CardData> {
+        self.iter().collect()
+    }
+
+    /// 協議的牌字串列表 (e.g., ["2S", "AS", "10H"])
+    pub fn to_protocol_strings(self) -> Vec<Card> {
+        self.iter().map(|c| c.to_protocol_string()).collect()
+    }
+
+    /// 解析協議的牌字串列表 (有無法解析的牌時為 None；重複的牌只算一次)
+    pub fn from_protocol_strings<S: AsRef<str>>(cards: &[S]) -> Option<CardSet> {
+        cards.iter().map(|c| CardData::from_protocol_string(c.as_ref())).collect()
+    }
+}
+
+impl FromIterator<CardData> for CardSet {
+    fn from_iter<I: IntoIterator<Item = CardData>>(iter: I) -> Self {
+        let mut set = CardSet::EMPTY;
+        for card in iter {
+            set.insert(card);
+        }
+        set
+    }
+}
+
+impl<'a> FromIterator<&'a CardData> for CardSet {
+    fn from_iter<I: IntoIterator<Item = &'a CardData>>(iter: I) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = CardSet;
+    fn bitor(self, rhs: CardSet) -> CardSet {
+        CardSet(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for CardSet {
+    fn bitor_assign(&mut self, rhs: CardSet) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for CardSet {
+    type Output = CardSet;
+    fn bitand(self, rhs: CardSet) -> CardSet {
+        CardSet(self.0 & rhs.0)
+    }
+}
+
+impl Sub for CardSet {
+    type Output = CardSet;
+    fn sub(self, rhs: CardSet) -> CardSet {
+        CardSet(self.0 & !rhs.0)
+    }
+}
+
+impl Not for CardSet {
+    type Output = CardSet;
+    fn not(self) -> CardSet {
+        CardSet(!self.0 & Self::FULL.0)
+    }
+}
+
+/// 快照中與牌的列表相同 (e.g., ["2S", "AS"])
+impl Serialize for CardSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for CardSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<CardData>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::deck::Deck;
+
+    fn card(s: &str) -> CardData {
+        CardData::from_protocol_string(s).unwrap()
+    }
+
+    #[test]
+    fn test_indices_cover_the_deck() {
+        let deck = Deck::new();
+        let all: CardSet = deck.cards().iter().collect();
+        assert_eq!(all, CardSet::FULL);
+        assert_eq!(all.len(), 52);
+        assert_eq!(CardSet::index(CardData::new(Suit::Spades, Rank::TWO)), 0);
+        assert_eq!(CardSet::index(CardData::new(Suit::Spades, Rank::ACE)), 12);
+        assert_eq!(CardSet::index(CardData::new(Suit::Clubs, Rank::ACE)), 51);
+        assert_eq!(all.iter().count(), 52);
+        assert!(all.iter().all(|c| CardSet::card_at(CardSet::index(c)) == c));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let queen = CardData::new(Suit::Diamonds, Rank::QUEEN);
+        let two = CardData::new(Suit::Hearts, Rank::TWO);
+        let mut a = CardSet::EMPTY;
+        assert!(a.is_empty());
+        a.insert(queen);
+        assert!(a.contains(queen));
+        assert!(!a.contains(two));
+
+        let b: CardSet = [two].iter().collect();
+        assert_eq!((a | b).len(), 2);
+        assert!((a & b).is_empty());
+        assert_eq!((a | b) & a, a);
+        assert_eq!((a | b) - a, b);
+        assert_eq!((!a).len(), 51);
+
+        a.remove(queen);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn test_suit_queries() {
+        let hand = CardSet::from_protocol_strings(&["AS", "3S", "10S", "KH", "2C"]).unwrap();
+        assert_eq!(CardSet::suit_mask(Suit::Hearts).len(), 13);
+        assert_eq!(hand.suit_len(Suit::Spades), 3);
+        assert_eq!(hand.of_suit(Suit::Clubs), CardSet::single(card("2C")));
+        assert_eq!(hand.highest_in(Suit::Spades), Some(card("AS")));
+        assert_eq!(hand.lowest_in(Suit::Spades), Some(card("3S")));
+        assert_eq!(hand.highest_in(Suit::Diamonds), None);
+        assert_eq!(hand.lowest(), Some(card("2C")));
+        assert_eq!(hand.first(), Some(card("3S")));
+    }
+
+    #[test]
+    fn test_protocol_strings_and_serde() {
+        let hand = CardSet::from_protocol_strings(&["KH", "10S", "2C"]).unwrap();
+        assert_eq!(hand.to_protocol_strings(), vec!["10S", "KH", "2C"]);
+        assert_eq!(CardSet::from_protocol_strings(&["KH", "XX"]), None);
+
+        // 與 Vec<CardData> 相同的 JSON
+        let json = serde_json::to_string(&hand).unwrap();
+        assert_eq!(json, serde_json::to_string(&hand.to_vec()).unwrap());
+        assert_eq!(serde_json::from_str::<CardSet>(&json).unwrap(), hand);
+    }
+}
@@ -0,0 +1,261 @@
+//! 攤牌宣告的檢查 (Claim Verification)
+//!
+//! 內建 AI 是宣告的對手時，由 Server 替它決定是否接受。宣告者看不到其他家的牌，
+//! 不能逐一以雙明手解算每種分配 (那等於假設宣告者每次都猜對牌的位置)，
+//! 這裡只數穩贏的墩：不論其他牌怎麼分配、其他家怎麼出牌都一定贏的牌。
+//! 宣告者那一手以外還沒出的牌 (包含同伴的牌與沒發出的牌) 一律當成對手的牌，
+//! trick 進行中或由其他家領牌時，還沒出牌的每一家都可能出其中任何一張。
+
+use super::cardset::CardSet;
+use super::deck::{CardData, Suit};
+use super::engine::{Claim, GameEngine};
+use super::rules::trick_winner;
+
+/// 宣告的檢查結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// 穩贏的墩數足夠
+    Holds,
+    /// 穩贏的墩數 (少於宣告的墩數)
+    Short(u32),
+}
+
+impl Verdict {
+    /// AI 拒絕時 CLAIM_RESULT 的 `reason`
+    pub fn reason(self) -> Option<&'static str> {
+        match self {
+            Verdict::Holds => None,
+            Verdict::Short(_) => Some("NOT_ENOUGH_WINNERS"),
+        }
+    }
+}
+
+/// 檢查宣告者這隊是否穩拿宣告的墩數 (包含進行中的這墩)
+pub fn verify(engine: &GameEngine, claim: &Claim) -> Verdict {
+    let sure = match engine.current_player_idx() {
+        Some(current) => {
+            // 輪到宣告者代出的夢家時數夢家的牌，否則數宣告者自己的牌
+            let seat = if engine.controller_of(current) == claim.claimer { current } else { claim.claimer };
+            let hand = engine.players[seat].hand;
+            let table: CardSet = engine.table.iter().map(|(_, card)| card).collect();
+            let others = !(engine.played | table | hand);
+            if engine.table.is_empty() && seat == current {
+                sure_tricks(hand, others, engine.trump)
+            } else {
+                sure_after_trick(engine, seat, hand, others)
+            }
+        }
+        None => 0,
+    };
+    if sure >= claim.tricks { Verdict::Holds } else { Verdict::Short(sure) }
+}
+
+/// 進行中的這墩 (或由其他家領牌的下一墩) 宣告方一定贏下時，1 墩加上接著領牌穩贏的墩；
+/// 其他家領牌時每一種可能領出的花色都要贏得下來
+fn sure_after_trick(engine: &GameEngine, seat: usize, hand: CardSet, others: CardSet) -> u32 {
+    let trump = engine.trump;
+    let own = engine.table.iter().find(|(idx, _)| *idx == seat).map(|&(_, card)| card);
+    // 這墩還沒出牌的其他家
+    let pending = engine.players.len() - engine.table.len() - usize::from(own.is_none());
+    let holds = |table: &[(usize, CardData)], card: CardData, lead: Suit| {
+        trick_winner(table, trump) == seat && (pending == 0 || !can_beat(others, card, lead, trump))
+    };
+
+    if let Some(card) = own {
+        let holds = holds(&engine.table, card, engine.table[0].1.suit);
+        return if holds { 1 + sure_tricks(hand, others, trump) } else { 0 };
+    }
+
+    let best_reply = |lead: Suit| {
+        let follows = hand.suit_len(lead) > 0;
+        hand.iter()
+            .filter(|card| !follows || card.suit == lead)
+            .filter(|&card| {
+                let mut table = engine.table.clone();
+                table.push((seat, card));
+                holds(&table, card, lead)
+            })
+            .map(|card| {
+                let mut rest = hand;
+                rest.remove(card);
+                1 + sure_tricks(rest, others, trump)
+            })
+            .max()
+            .unwrap_or(0)
+    };
+    match engine.table.first() {
+        Some(&(_, led)) => best_reply(led.suit),
+        None => Suit::all()
+            .into_iter()
+            .filter(|&suit| others.suit_len(suit) > 0)
+            .map(best_reply)
+            .min()
+            .unwrap_or(0),
+    }
+}
+
+/// `others` 中是否有牌能在 `lead` 花色的這墩壓過 `card` (王牌都當成可以切)
+fn can_beat(others: CardSet, card: CardData, lead: Suit, trump: Option<Suit>) -> bool {
+    others.iter().any(|other| {
+        if other.suit == card.suit {
+            other.rank > card.rank
+        } else {
+            Some(other.suit) == trump || (other.suit == lead && Some(card.suit) != trump)
+        }
+    })
+}
+
+/// `hand` 連續領牌一定贏的墩數 (`others` 是可能在對手手上的牌)：
+/// 有王牌時先清王，對手可能還有王牌就不算旁門的贏墩
+fn sure_tricks(hand: CardSet, others: CardSet, trump: Option<Suit>) -> u32 {
+    let trumps = trump.map_or(0, |suit| cashing_run(hand, others, suit));
+    let drawn = trump.is_none_or(|suit| trumps >= others.suit_len(suit));
+    if !drawn {
+        return trumps;
+    }
+    trumps
+        + Suit::all()
+            .into_iter()
+            .filter(|&suit| Some(suit) != trump)
+            .map(|suit| cashing_run(hand, others, suit))
+            .sum::<u32>()
+}
+
+/// 由大到小連續領出一個花色能贏幾輪：比對手所有的牌都大，
+/// 或對手的牌已經跟完 (每輪至少跟掉一張，對手最大的牌留到最後)
+fn cashing_run(hand: CardSet, others: CardSet, suit: Suit) -> u32 {
+    let opposing = others.suit_len(suit) as usize;
+    let top = others.highest_in(suit).map(|c| c.rank);
+    let mut cards: Vec<CardData> = hand.of_suit(suit).iter().collect();
+    cards.sort_by_key(|c| std::cmp::Reverse(c.rank));
+    cards
+        .iter()
+        .enumerate()
+        .take_while(|&(round, card)| round >= opposing || Some(card.rank) > top)
+        .count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::engine::GamePhase;
+    use crate::game::solver::{self, Position};
+    use crate::game::testing;
+    use crate::protocol::Team;
+
+    fn cards(list: &[&str]) -> Vec<CardData> {
+        list.iter().map(|c| CardData::from_protocol_string(c).unwrap()).collect()
+    }
+
+    /// 只發指定手牌的殘局 (P1 首引)
+    fn endgame(trump: Option<Suit>, hands: [&[&str]; 4]) -> GameEngine {
+        let mut engine = testing::new_engine(7);
+        engine.trump = trump;
+        engine.hand_size = Some(hands[0].len());
+        engine.deal_hands(hands.iter().map(|h| cards(h)).collect());
+        engine
+    }
+
+    fn claim(tricks: u32, remaining: u32) -> Claim {
+        Claim {
+            claimer: 0,
+            tricks,
+            remaining,
+            accepted: Vec::new(),
+            cards: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_top_winners_hold() {
+        let engine = endgame(None, [&["AS", "KS"], &["2H", "3D"], &["QS", "JS"], &["2D", "4D"]]);
+        assert_eq!(verify(&engine, &claim(2, 2)), Verdict::Holds);
+    }
+
+    #[test]
+    fn test_side_winners_need_trumps_drawn() {
+        // 實際上 P3/P4 都沒有王牌，但宣告者看不到 2H 在哪一家
+        let engine = endgame(
+            Some(Suit::Hearts),
+            [&["AS", "KS"], &["2H", "3D"], &["QS", "JS"], &["2D", "4D"]],
+        );
+        let position = Position::from_engine(&engine).unwrap();
+        assert_eq!(solver::solve(&position, Team::Human), Ok(2));
+        assert_eq!(verify(&engine, &claim(2, 2)), Verdict::Short(0));
+
+        // 王牌由大到小領完、對手的王牌也跟完後旁門才算
+        let mut hand: CardSet = cards(&["AH", "KH", "QH", "JH", "AS"]).into_iter().collect();
+        let others = !hand;
+        assert_eq!(sure_tricks(hand, others, Some(Suit::Hearts)), 4);
+        hand = cards(&["AH", "KH", "QH", "JH", "10H", "9H", "8H", "7H", "6H", "5H", "4H", "3H", "2H"])
+            .into_iter()
+            .collect();
+        assert_eq!(sure_tricks(hand, !hand, Some(Suit::Hearts)), 13);
+    }
+
+    #[test]
+    fn test_two_way_guess_is_rejected() {
+        // 打完 AS 之後宣告者要猜 AH 還是 AD 在牌局中：這副牌 AH 在 P3 手上、AD 沒發出，
+        // 要領 KD；換成 AD 在牌局中時要領 KH。兩種分配各自以雙明手解算都拿得到 2 墩
+        let guess = |ace: &'static str, queen: &'static str| {
+            endgame(None, [&["AS", "KH", "KD"], &["2S", "2C", "3C"], &[ace, "3S", "4C"], &[queen, "4S", "5C"]])
+        };
+        for engine in [guess("AH", "QD"), guess("AD", "QH")] {
+            let position = Position::from_engine(&engine).unwrap();
+            assert_eq!(solver::solve(&position, Team::Human), Ok(2));
+            assert_eq!(verify(&engine, &claim(2, 3)), Verdict::Short(1));
+            assert_eq!(verify(&engine, &claim(1, 3)), Verdict::Holds);
+        }
+    }
+
+    #[test]
+    fn test_claim_mid_trick() {
+        // 領出 AS 之後宣告：AS 已經最大，接著領 KS
+        let mut engine = endgame(None, [&["AS", "KS"], &["2H", "3D"], &["QS", "JS"], &["2D", "4D"]]);
+        engine.play_card(0, CardData::from_protocol_string("AS").unwrap());
+        assert_eq!(verify(&engine, &claim(2, 2)), Verdict::Holds);
+
+        // 輪到宣告者跟牌：出 AS 一定贏這墩，出 KS 不一定
+        let mut engine = endgame(None, [&["AS", "KS"], &["2H", "3D"], &["QS", "JS"], &["2D", "4D"]]);
+        engine.phase = GamePhase::WaitingForPlay { current_player_idx: 2 };
+        engine.play_card(2, CardData::from_protocol_string("QS").unwrap());
+        engine.play_card(3, CardData::from_protocol_string("2D").unwrap());
+        assert_eq!(verify(&engine, &claim(2, 2)), Verdict::Holds);
+
+        // 已經出的牌可能被後面的王牌切掉
+        let mut engine = endgame(
+            Some(Suit::Hearts),
+            [&["AS", "KS"], &["2H", "3D"], &["QS", "JS"], &["2D", "4D"]],
+        );
+        engine.play_card(0, CardData::from_protocol_string("AS").unwrap());
+        assert_eq!(verify(&engine, &claim(1, 2)), Verdict::Short(0));
+    }
+
+    #[test]
+    fn test_claim_with_opponent_on_lead() {
+        // 13 張王牌在手：不論對手領哪個花色都切下來再領回去
+        let mut engine = endgame(Some(Suit::Hearts), [&["AS"], &["2H"], &["2D"], &["2C"]]);
+        engine.hand_size = None;
+        engine.deal();
+        engine.players[0].hand = CardSet::suit_mask(Suit::Hearts);
+        engine.phase = GamePhase::WaitingForPlay { current_player_idx: 2 };
+        assert_eq!(verify(&engine, &claim(13, 13)), Verdict::Holds);
+
+        // 對手可能領紅心，宣告者沒有紅心也沒有王牌
+        let mut engine = endgame(None, [&["AS", "KS"], &["2H", "3D"], &["QS", "JS"], &["2D", "4D"]]);
+        engine.phase = GamePhase::WaitingForPlay { current_player_idx: 2 };
+        assert_eq!(verify(&engine, &claim(2, 2)), Verdict::Short(0));
+        assert_eq!(Verdict::Short(0).reason(), Some("NOT_ENOUGH_WINNERS"));
+    }
+
+    #[test]
+    fn test_early_claim_is_counted() {
+        // 一開始就能宣告：13 張黑桃在手
+        let mut engine = endgame(None, [&["AS"], &["2H"], &["2D"], &["2C"]]);
+        engine.hand_size = None;
+        engine.deal();
+        engine.players[0].hand = CardSet::suit_mask(Suit::Spades);
+        assert_eq!(verify(&engine, &claim(13, 13)), Verdict::Holds);
+        assert_eq!(cashing_run(CardSet::suit_mask(Suit::Spades), CardSet::EMPTY, Suit::Spades), 13);
+    }
+}
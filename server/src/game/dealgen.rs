@@ -0,0 +1,348 @@
+//! 條件發牌 (Constrained Deal Generator)
+//!
+//! 依每個座位 (N/E/S/W) 的條件產生一副牌：大牌點 (HCP) 範圍、各花色張數範圍、
+//! 平均或非平均牌型。以 seed 決定的 SplitMix64 洗整副牌，四家都符合才接受
+//! (整副拒絕，不會偏向某些牌)，相同的條件與 seed 一定產生相同的牌。
+//!
+//! 條件字串: 座位之間以 `;` 分隔，每個座位為 `<座位>:<條件>,<條件>...`，e.g.
+//! `N:hcp=15-17,bal;E:S=6+`。條件有 `hcp=<範圍>`、`S/H/D/C=<範圍>`、`bal`、`unbal`，
+//! 範圍寫成 `15-17`、`6+` 或 `5`。
+
+use super::deck::{CardData, Deck, SplitMix64, Suit};
+use super::eval;
+use super::pbn::{PbnDeal, SEATS};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 13 張牌最多的大牌點 (4A 4K 4Q 1J)
+const MAX_HAND_HCP: u32 = 37;
+/// 放棄前最多抽幾副牌 (開局時在 game loop 上抽，抽不到時要在幾毫秒內放棄並回報 GaveUp)
+const MAX_DRAWS: u32 = 20_000;
+/// 設定時試抽的 seed 數
+const PROBE_SEEDS: u64 = 4;
+/// 設定時每個 seed 最多抽幾副牌
+const PROBE_DRAWS: u32 = MAX_DRAWS / 4;
+
+/// 條件發牌錯誤
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DealGenError {
+    /// 條件字串格式錯誤
+    InvalidSpec(String),
+    /// 條件互相矛盾，不可能成立
+    Impossible(String),
+    /// 抽牌次數用盡仍找不到符合的牌
+    GaveUp,
+    /// 條件太少見，設定時試抽找不到符合的牌
+    TooRare,
+}
+
+impl fmt::Display for DealGenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DealGenError::InvalidSpec(s) => write!(f, "invalid constraint: {}", s),
+            DealGenError::Impossible(s) => write!(f, "constraints cannot be met: {}", s),
+            DealGenError::GaveUp => write!(f, "no matching deal found after {} deals", MAX_DRAWS),
+            DealGenError::TooRare => write!(f, "constraints too rare: no matching deal within {} deals", PROBE_DRAWS),
+        }
+    }
+}
+
+/// 閉區間範圍
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Range {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl Range {
+    pub fn new(min: u32, max: u32) -> Self {
+        Self { min, max }
+    }
+
+    pub fn contains(&self, value: u32) -> bool {
+        (self.min..=self.max).contains(&value)
+    }
+
+    /// 解析 "15-17"、"6+" 或 "5" (上限為 limit)
+    fn parse(s: &str, limit: u32) -> Result<Self, DealGenError> {
+        let invalid = || DealGenError::InvalidSpec(s.to_string());
+        let num = |v: &str| v.trim().parse::<u32>().map_err(|_| invalid());
+        let range = if let Some(min) = s.strip_suffix('+') {
+            Range::new(num(min)?, limit)
+        } else if let Some((min, max)) = s.split_once('-') {
+            Range::new(num(min)?, num(max)?)
+        } else {
+            let n = num(s)?;
+            Range::new(n, n)
+        };
+        if range.min > range.max || range.max > limit {
+            return Err(invalid());
+        }
+        Ok(range)
+    }
+
+    fn display(&self, limit: u32) -> String {
+        if self.min == self.max {
+            self.min.to_string()
+        } else if self.max == limit {
+            format!("{}+", self.min)
+        } else {
+            format!("{}-{}", self.min, self.max)
+        }
+    }
+}
+
+/// 單一座位的條件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeatConstraint {
+    /// 大牌點範圍
+    pub hcp: Range,
+    /// 各花色張數範圍 (S, H, D, C)
+    pub suit_lengths: [Range; 4],
+    /// Some(true) = 平均牌型，Some(false) = 非平均牌型
+    pub balanced: Option<bool>,
+}
+
+impl Default for SeatConstraint {
+    fn default() -> Self {
+        Self {
+            hcp: Range::new(0, MAX_HAND_HCP),
+            suit_lengths: [Range::new(0, 13); 4],
+            balanced: None,
+        }
+    }
+}
+
+impl SeatConstraint {
+    /// 沒有任何條件
+    pub fn is_free(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 手牌是否符合條件
+    pub fn accepts(&self, hand: &[CardData]) -> bool {
+        let shape = eval::shape(hand);
+        self.hcp.contains(eval::high_card_points(hand))
+            && shape.0.iter().zip(&self.suit_lengths).all(|(&len, range)| range.contains(len))
+            && self.balanced.is_none_or(|b| b == shape.is_balanced())
+    }
+
+    fn parse(items: &str) -> Result<Self, DealGenError> {
+        let mut seat = Self::default();
+        for item in items.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match item.to_ascii_lowercase().as_str() {
+                "bal" | "balanced" => seat.balanced = Some(true),
+                "unbal" | "unbalanced" => seat.balanced = Some(false),
+                _ => {
+                    let (key, value) = item
+                        .split_once('=')
+                        .ok_or_else(|| DealGenError::InvalidSpec(item.to_string()))?;
+                    let key = key.trim();
+                    if key.eq_ignore_ascii_case("hcp") {
+                        seat.hcp = Range::parse(value, MAX_HAND_HCP)?;
+                    } else {
+                        let suit = Some(key)
+                            .filter(|k| k.len() == 1)
+                            .and_then(|k| k.chars().next())
+                            .and_then(Suit::from_char)
+                            .ok_or_else(|| DealGenError::InvalidSpec(item.to_string()))?;
+                        seat.suit_lengths[suit as usize] = Range::parse(value, 13)?;
+                    }
+                }
+            }
+        }
+        Ok(seat)
+    }
+}
+
+impl fmt::Display for SeatConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut items = Vec::new();
+        if self.hcp != Range::new(0, MAX_HAND_HCP) {
+            items.push(format!("hcp={}", self.hcp.display(MAX_HAND_HCP)));
+        }
+        match self.balanced {
+            Some(true) => items.push("bal".to_string()),
+            Some(false) => items.push("unbal".to_string()),
+            None => {}
+        }
+        for (suit, range) in Suit::all().iter().zip(&self.suit_lengths) {
+            if *range != Range::new(0, 13) {
+                items.push(format!("{}={}", suit.symbol(), range.display(13)));
+            }
+        }
+        write!(f, "{}", items.join(","))
+    }
+}
+
+/// 四個座位 (N, E, S, W) 的發牌條件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DealConstraints {
+    pub seats: [SeatConstraint; 4],
+}
+
+impl DealConstraints {
+    /// 解析條件字串 (e.g., "N:hcp=15-17,bal;E:S=6+")
+    pub fn parse(spec: &str) -> Result<Self, DealGenError> {
+        let mut constraints = Self::default();
+        for clause in spec.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+            let (seat, items) = clause
+                .split_once(':')
+                .ok_or_else(|| DealGenError::InvalidSpec(clause.to_string()))?;
+            let idx = Some(seat.trim())
+                .filter(|s| s.len() == 1)
+                .and_then(|s| s.chars().next())
+                .and_then(|c| SEATS.iter().position(|&s| s == c.to_ascii_uppercase()))
+                .ok_or_else(|| DealGenError::InvalidSpec(clause.to_string()))?;
+            constraints.seats[idx] = SeatConstraint::parse(items)?;
+        }
+        constraints.check()?;
+        Ok(constraints)
+    }
+
+    /// 排除明顯不可能的條件 (張數或大牌點總和不合)
+    fn check(&self) -> Result<(), DealGenError> {
+        for (seat, c) in SEATS.iter().zip(&self.seats) {
+            let min: u32 = c.suit_lengths.iter().map(|r| r.min).sum();
+            let max: u32 = c.suit_lengths.iter().map(|r| r.max).sum();
+            if min > 13 || max < 13 {
+                return Err(DealGenError::Impossible(format!("{} suit lengths cannot total 13", seat)));
+            }
+        }
+        for suit in Suit::all() {
+            let min: u32 = self.seats.iter().map(|c| c.suit_lengths[suit as usize].min).sum();
+            if min > 13 {
+                return Err(DealGenError::Impossible(format!("more than 13 {} cards needed", suit.symbol())));
+            }
+        }
+        let (min, max) = self
+            .seats
+            .iter()
+            .fold((0, 0), |(min, max), c| (min + c.hcp.min, max + c.hcp.max));
+        if min > 40 || max < 40 {
+            return Err(DealGenError::Impossible("HCP ranges cannot total 40".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 以 seed 產生符合條件的一副牌 (N, E, S, W 的手牌)
+    pub fn generate(&self, seed: u64) -> Result<PbnDeal, DealGenError> {
+        self.generate_within(seed, MAX_DRAWS)
+    }
+
+    /// 設定時先試抽：條件太少見 (PROBE_SEEDS 個 seed 在 PROBE_DRAWS 副內有任一個抽不到) 時拒絕，
+    /// 確保開局時 MAX_DRAWS 副內幾乎一定抽得到
+    pub fn probe(&self) -> Result<(), DealGenError> {
+        for seed in 0..PROBE_SEEDS {
+            match self.generate_within(seed, PROBE_DRAWS) {
+                Ok(_) => {}
+                Err(DealGenError::GaveUp) => return Err(DealGenError::TooRare),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// 每次洗整副牌，四家都符合條件才接受 (整副拒絕，符合條件的牌出現機率均等)
+    fn generate_within(&self, seed: u64, max_draws: u32) -> Result<PbnDeal, DealGenError> {
+        let mut rng = SplitMix64::new(seed);
+        // 條件多的座位先檢查，不符合就提早換下一副
+        let mut order = [0, 1, 2, 3];
+        order.sort_by_key(|&i| self.seats[i].is_free());
+        let mut cards = Deck::new().cards().to_vec();
+
+        for _ in 0..max_draws {
+            for i in (1..cards.len()).rev() {
+                let j = (rng.next() % (i as u64 + 1)) as usize;
+                cards.swap(i, j);
+            }
+            let hand = |seat: usize| &cards[seat * 13..(seat + 1) * 13];
+            if order.iter().all(|&seat| self.seats[seat].accepts(hand(seat))) {
+                let mut hands: [Vec<CardData>; 4] = Default::default();
+                for (seat, h) in hands.iter_mut().enumerate() {
+                    *h = hand(seat).to_vec();
+                    h.sort_by_key(|c| (c.suit as u8, c.rank.0));
+                }
+                return Ok(PbnDeal {
+                    board: None,
+                    dealer: None,
+                    hands,
+                });
+            }
+        }
+        Err(DealGenError::GaveUp)
+    }
+}
+
+impl fmt::Display for DealConstraints {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let clauses: Vec<String> = SEATS
+            .iter()
+            .zip(&self.seats)
+            .filter(|(_, c)| !c.is_free())
+            .map(|(seat, c)| format!("{}:{}", seat, c))
+            .collect();
+        write!(f, "{}", clauses.join(";"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_parse_and_display() {
+        let c = DealConstraints::parse("n:hcp=15-17,bal; E:s=6+").unwrap();
+        assert_eq!(c.seats[0].hcp, Range::new(15, 17));
+        assert_eq!(c.seats[0].balanced, Some(true));
+        assert_eq!(c.seats[1].suit_lengths[Suit::Spades as usize], Range::new(6, 13));
+        assert!(c.seats[2].is_free());
+        assert_eq!(c.to_string(), "N:hcp=15-17,bal;E:S=6+");
+        assert_eq!(DealConstraints::parse(&c.to_string()).unwrap(), c);
+
+        assert!(matches!(DealConstraints::parse("X:hcp=1"), Err(DealGenError::InvalidSpec(_))));
+        assert!(matches!(DealConstraints::parse("N:hcp=17-15"), Err(DealGenError::InvalidSpec(_))));
+        assert!(matches!(DealConstraints::parse("N:Z=4"), Err(DealGenError::InvalidSpec(_))));
+        assert!(matches!(DealConstraints::parse("N:S=7+;S:S=7+"), Err(DealGenError::Impossible(_))));
+        assert!(matches!(DealConstraints::parse("N:hcp=21+;S:hcp=20+"), Err(DealGenError::Impossible(_))));
+    }
+
+    #[test]
+    fn test_generate_meets_constraints() {
+        let c = DealConstraints::parse("N:hcp=15-17,bal;E:S=6+;W:unbal,hcp=0-5").unwrap();
+        let deal = c.generate(7).unwrap();
+
+        let mut all: Vec<CardData> = deal.hands.concat();
+        assert_eq!(all.len(), 52);
+        all.sort_by_key(|c| (c.suit as u8, c.rank.0));
+        all.dedup();
+        assert_eq!(all.len(), 52);
+
+        for (seat, hand) in deal.hands.iter().enumerate() {
+            assert_eq!(hand.len(), 13);
+            assert!(c.seats[seat].accepts(hand));
+        }
+        let hcp = eval::high_card_points(&deal.hands[0]);
+        assert!((15..=17).contains(&hcp));
+        assert!(eval::is_balanced(&deal.hands[0]));
+        assert!(eval::suit_length(&deal.hands[1], Suit::Spades) >= 6);
+    }
+
+    #[test]
+    fn test_probe_rejects_rare_constraints() {
+        assert_eq!(DealConstraints::parse("N:hcp=15-17,bal;E:S=6+").unwrap().probe(), Ok(()));
+        // 試抽與放棄都在 game loop 上，抽滿也不能卡住 (未最佳化的 debug build 也一樣)
+        let started = Instant::now();
+        assert_eq!(DealConstraints::parse("N:S=13").unwrap().probe(), Err(DealGenError::TooRare));
+        assert_eq!(DealConstraints::parse("N:S=13").unwrap().generate(1), Err(DealGenError::GaveUp));
+        assert!(started.elapsed() < Duration::from_secs(2), "took {:?}", started.elapsed());
+    }
+
+    #[test]
+    fn test_generate_is_reproducible() {
+        let c = DealConstraints::parse("S:H=5+,hcp=12+").unwrap();
+        assert_eq!(c.generate(99).unwrap(), c.generate(99).unwrap());
+        assert_ne!(c.generate(99).unwrap(), c.generate(100).unwrap());
+    }
+}
@@ -1,7 +1,8 @@
 use crate::protocol::Card;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// 花色
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Suit {
     Spades,   // ♠
     Hearts,   // ♥
@@ -35,7 +36,7 @@ impl Suit {
 }
 
 /// 點數 (2-14, 其中 11=J, 12=Q, 13=K, 14=A)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Rank(pub u8);
 
 impl Rank {
@@ -82,6 +83,14 @@ impl Rank {
         }
     }
 
+    /// PBN 的單字元表示 (10 為 'T')
+    pub fn pbn_char(&self) -> char {
+        match self.0 {
+            10 => 'T',
+            _ => self.symbol().chars().next().unwrap_or('?'),
+        }
+    }
+
     pub fn from_str(s: &str) -> Option<Rank> {
         match s.to_uppercase().as_str() {
             "2" => Some(Rank::TWO),
@@ -92,7 +101,8 @@ impl Rank {
             "7" => Some(Rank::SEVEN),
             "8" => Some(Rank::EIGHT),
             "9" => Some(Rank::NINE),
-            "10" => Some(Rank::TEN),
+            // PBN 以 "T" 表示 10
+            "10" | "T" => Some(Rank::TEN),
             "J" => Some(Rank::JACK),
             "Q" => Some(Rank::QUEEN),
             "K" => Some(Rank::KING),
@@ -115,11 +125,11 @@ impl CardData {
     }
 
     /// 轉換為協議字串格式 (e.g., "AS", "10H", "KC")
-    pub fn to_protocol_string(&self) -> Card {
+    pub fn to_protocol_string(self) -> Card {
         format!("{}{}", self.rank.symbol(), self.suit.symbol())
     }
 
-    /// 從協議字串解析 (e.g., "AS", "10H", "KC")
+    /// 從協議字串解析 (e.g., "AS", "10H", "KC"；也接受 PBN 的 "TH")
     pub fn from_protocol_string(s: &str) -> Option<CardData> {
         if s.len() < 2 {
             return None;
@@ -135,6 +145,21 @@ impl CardData {
     }
 }
 
+/// 快照中的牌以協議字串表示 (e.g., "AS")
+impl Serialize for CardData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_protocol_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for CardData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        CardData::from_protocol_string(&s)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid card: {}", s)))
+    }
+}
+
 /// 牌組
 pub struct Deck {
     cards: Vec<CardData>,
@@ -152,13 +177,13 @@ impl Deck {
         Self { cards }
     }
 
-    /// 使用 seed 洗牌 (Fisher-Yates shuffle with LCG PRNG)
+    /// 使用 seed 洗牌 (Fisher-Yates shuffle with SplitMix64 PRNG)
     pub fn shuffle(&mut self, seed: u64) {
-        let mut rng = SimpleLcg::new(seed);
+        let mut rng = SplitMix64::new(seed);
         let n = self.cards.len();
 
         for i in (1..n).rev() {
-            let j = (rng.next() as usize) % (i + 1);
+            let j = (rng.next() % (i as u64 + 1)) as usize;
             self.cards.swap(i, j);
         }
     }
@@ -176,7 +201,6 @@ impl Deck {
     }
 
     /// 取得所有牌
-    #[allow(dead_code)]
     pub fn cards(&self) -> &[CardData] {
         &self.cards
     }
@@ -188,25 +212,24 @@ impl Default for Deck {
     }
 }
 
-/// 簡易 LCG 隨機數產生器 (Linear Congruential Generator)
-/// 用於確定性洗牌
-struct SimpleLcg {
+/// SplitMix64 隨機數產生器
+/// 用於確定性洗牌；完整 64-bit 狀態，無法從手牌反推 seed
+/// (Client 驗證發牌時需實作相同演算法，見 protocol.md)
+pub(crate) struct SplitMix64 {
     state: u64,
 }
 
-impl SimpleLcg {
-    fn new(seed: u64) -> Self {
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
         Self { state: seed }
     }
 
-    fn next(&mut self) -> u64 {
-        // LCG parameters (same as glibc)
-        const A: u64 = 1103515245;
-        const C: u64 = 12345;
-        const M: u64 = 1 << 31;
-
-        self.state = (A.wrapping_mul(self.state).wrapping_add(C)) % M;
-        self.state
+    pub(crate) fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
     }
 }
 
@@ -300,5 +323,10 @@ mod tests {
         let card = CardData::from_protocol_string("KC").unwrap();
         assert_eq!(card.suit, Suit::Clubs);
         assert_eq!(card.rank, Rank::KING);
+
+        // PBN 的 T 與 10 相同
+        assert_eq!(CardData::from_protocol_string("TH"), CardData::from_protocol_string("10H"));
+        assert_eq!(Rank::TEN.pbn_char(), 'T');
+        assert_eq!(Rank::ACE.pbn_char(), 'A');
     }
 }
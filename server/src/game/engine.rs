@@ -1,16 +1,30 @@
-use super::deck::{CardData, Deck};
+use super::auction::{Auction, AuctionError, AuctionStatus, Call, Contract};
+use super::cardset::CardSet;
+use super::deck::{CardData, Deck, Suit};
+use super::fairness::DealSeed;
+use super::rules::{PassDirection, RuleKind, RuleSet, TieBreak, TrickContext, UndoPolicy};
+use super::scoring::{HandScore, Vulnerability};
 use crate::net::ConnectionId;
-use crate::protocol::{Card, PlayerId, Score, ServerMessage, TablePlay, Team, TrickHistory};
+use crate::protocol::{
+    AuctionCall, Card, ClaimSummary, Outcome, PlayerId, PlayerScore, Score, SeatState, ServerMessage,
+    SignedScore, TablePlay, Team, TrickHistory,
+};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 
-const CARDS_PER_PLAYER: usize = 13;
-const TOTAL_TRICKS: usize = 13;
-const TIMEOUT_MS: u32 = 30000; // 30 秒
+const DEFAULT_TIMEOUT_MS: u32 = 30000; // 30 秒
 
 /// 遊戲階段
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GamePhase {
     /// 等待發牌
     WaitingToDeal,
+    /// 傳牌中 (所有玩家同時選牌)
+    Passing,
+    /// 叫牌中
+    Bidding {
+        current_player_idx: usize,
+    },
     /// 等待玩家出牌
     WaitingForPlay {
         current_player_idx: usize,
@@ -22,18 +36,33 @@ pub enum GamePhase {
 }
 
 /// 玩家在遊戲中的狀態
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GamePlayer {
     pub conn_id: ConnectionId,
     pub player_id: PlayerId,
     pub team: Team,
-    pub hand: Vec<CardData>,
+    pub hand: CardSet,
+    /// 連續超時次數 (玩家自行出牌後歸零)
+    pub timeouts: u32,
+    /// 本副牌贏得的 trick 數
+    #[serde(default)]
+    pub tricks: u32,
+    /// 是否在線 (斷線等待續連時為 false)
+    #[serde(default = "default_connected")]
+    pub connected: bool,
+}
+
+fn default_connected() -> bool {
+    true
 }
 
 /// 遊戲引擎
+#[derive(Serialize, Deserialize)]
 pub struct GameEngine {
     /// 遊戲種子
     pub seed: u64,
+    /// 這副牌的 server secret (與 seed 一起在 GAME_OVER 揭露)
+    pub secret: String,
     /// 玩家列表 (順序固定: P1, P2, P3, P4)
     pub players: Vec<GamePlayer>,
     /// 當前遊戲階段
@@ -48,6 +77,83 @@ pub struct GameEngine {
     pub history: Vec<TrickHistory>,
     /// 上一 trick 的贏家 index (用於決定下一 trick 誰先出)
     pub last_trick_winner: Option<usize>,
+    /// YOUR_TURN 的出牌時限 (毫秒)
+    pub turn_timeout_ms: u32,
+    /// 棄權的玩家 index (因連續超時)
+    pub forfeited_by: Option<usize>,
+    /// 王牌花色 (None = 無王，只有領牌花色能贏)
+    pub trump: Option<Suit>,
+    /// 莊家 index (叫牌由莊家開始；不叫牌時由莊家首引)
+    pub dealer_idx: usize,
+    /// 是否在出牌前進行叫牌
+    pub bidding: bool,
+    /// 叫牌過程
+    pub auction: Option<Auction>,
+    /// 叫牌產生的合約
+    pub contract: Option<Contract>,
+    /// 夢家 index (主打者的同伴，由主打者代為出牌)
+    pub dummy_idx: Option<usize>,
+    /// 夢家手牌是否已攤出
+    pub dummy_revealed: bool,
+    /// 牌號 (1-based，決定身價)
+    pub board: u32,
+    /// 出牌規則 (合法出牌、trick 贏家、計分、結束條件)
+    #[serde(with = "rules_serde")]
+    pub rules: Box<dyn RuleSet>,
+    /// 之前 tricks 已出過的牌
+    pub played: CardSet,
+    /// 每位玩家吃到的 trick 分數
+    pub player_points: Vec<u32>,
+    /// 本副的傳牌方向 (None = 不傳牌)
+    pub pass_direction: Option<PassDirection>,
+    /// 每位玩家選好要傳出的牌
+    pub passes: Vec<Option<CardSet>>,
+    /// 開始出牌時的手牌 (傳牌後；用於匯出牌局記錄)
+    #[serde(default)]
+    pub dealt_hands: Vec<CardSet>,
+    /// 手牌來自的預設牌組名稱 (None = 以 seed 洗牌)
+    #[serde(default)]
+    pub deal_set: Option<String>,
+    /// 分數平手時的勝負判定
+    #[serde(default)]
+    pub tie_break: TieBreak,
+    /// 每人手牌張數 (None = 依規則發完整副牌；e.g., 5 張的練習牌局)
+    #[serde(default)]
+    pub hand_size: Option<usize>,
+    /// 等待對手回覆的攤牌宣告
+    #[serde(default)]
+    pub claim: Option<Claim>,
+    /// 被接受的攤牌宣告 (這副牌依此結束)
+    #[serde(default)]
+    pub claimed: Option<Claim>,
+    /// 悔牌範圍
+    #[serde(default)]
+    pub undo_policy: UndoPolicy,
+    /// 等待對手同意的悔牌請求
+    #[serde(default)]
+    pub undo: Option<Undo>,
+}
+
+/// 悔牌請求：收回 requester 最後出的牌 (含之後其他座位出的牌)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Undo {
+    pub requester: usize,
+    /// 已同意的對手
+    pub accepted: Vec<usize>,
+}
+
+/// 攤牌宣告：宣告者這隊拿剩下 tricks 中的 `tricks` 墩，其餘歸對手
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Claim {
+    pub claimer: usize,
+    pub tricks: u32,
+    /// 宣告時剩下的 tricks (含進行中的 trick)
+    pub remaining: u32,
+    /// 已接受的對手
+    pub accepted: Vec<usize>,
+    /// 結算時每個座位還沒出的牌 (含桌上這墩已出的牌)，供 HIGH_CARDS 判定平手
+    #[serde(default)]
+    pub cards: Vec<CardSet>,
 }
 
 impl GameEngine {
@@ -59,12 +165,16 @@ impl GameEngine {
                 conn_id,
                 player_id,
                 team,
-                hand: Vec::new(),
+                hand: CardSet::EMPTY,
+                timeouts: 0,
+                tricks: 0,
+                connected: true,
             })
             .collect();
 
         Self {
             seed,
+            secret: DealSeed::generate().secret,
             players: game_players,
             phase: GamePhase::WaitingToDeal,
             current_trick: 0,
@@ -72,41 +182,412 @@ impl GameEngine {
             score: Score::default(),
             history: Vec::new(),
             last_trick_winner: None,
+            turn_timeout_ms: DEFAULT_TIMEOUT_MS,
+            forfeited_by: None,
+            trump: None,
+            dealer_idx: 0,
+            bidding: false,
+            auction: None,
+            contract: None,
+            dummy_idx: None,
+            dummy_revealed: false,
+            board: 1,
+            rules: RuleKind::default().build(),
+            played: CardSet::EMPTY,
+            player_points: Vec::new(),
+            pass_direction: None,
+            passes: Vec::new(),
+            dealt_hands: Vec::new(),
+            deal_set: None,
+            tie_break: TieBreak::default(),
+            hand_size: None,
+            claim: None,
+            claimed: None,
+            undo_policy: UndoPolicy::default(),
+            undo: None,
         }
     }
 
+    /// 每人發幾張牌 (不超過整副牌平分的張數)
+    pub fn cards_per_player(&self) -> usize {
+        let num_players = self.players.len();
+        let full = self.rules.cards_per_player(num_players);
+        self.hand_size.map_or(full, |n| n.clamp(1, 52 / num_players))
+    }
+
     /// 發牌
     pub fn deal(&mut self) -> Vec<(ConnectionId, ServerMessage)> {
         let mut deck = Deck::new();
         deck.shuffle(self.seed);
 
-        let hands = deck.deal(4, CARDS_PER_PLAYER);
+        let hands = deck.deal(self.players.len(), self.cards_per_player());
+        self.deal_hands(hands)
+    }
 
+    /// 以指定的手牌發牌 (不洗牌；e.g., 從 PBN 載入的牌)
+    pub fn deal_hands(&mut self, hands: Vec<Vec<CardData>>) -> Vec<(ConnectionId, ServerMessage)> {
         // 分配手牌給玩家
         for (i, hand) in hands.into_iter().enumerate() {
-            self.players[i].hand = hand;
-            // 排序手牌 (方便玩家閱讀)
-            self.players[i].hand.sort_by_key(|c| (c.suit as u8, c.rank.0));
+            self.players[i].hand = hand.into_iter().collect();
+            self.players[i].tricks = 0;
         }
+        self.dealt_hands = self.players.iter().map(|p| p.hand).collect();
 
         // 設定遊戲狀態
+        let num_players = self.players.len();
         self.current_trick = 1;
+        self.played = CardSet::EMPTY;
+        self.player_points = vec![0; num_players];
+        self.passes = vec![None; num_players];
+        self.dummy_idx = None;
+        self.dummy_revealed = false;
+        self.claim = None;
+        self.claimed = None;
+        self.undo = None;
+        self.pass_direction = (self.rules.pass_count() > 0)
+            .then(|| PassDirection::for_hand(self.board))
+            .filter(|d| *d != PassDirection::Hold);
+        if self.pass_direction.is_some() {
+            // 先傳牌，全部傳完才開始出牌
+            self.phase = GamePhase::Passing;
+        } else if self.bidding {
+            // 從莊家開始叫牌
+            let teams = self.players.iter().map(|p| p.team).collect();
+            self.auction = Some(Auction::new(self.dealer_idx, teams));
+            self.contract = None;
+            self.phase = GamePhase::Bidding {
+                current_player_idx: self.dealer_idx,
+            };
+        } else {
+            self.phase = GamePhase::WaitingForPlay {
+                current_player_idx: self.opening_leader(),
+            };
+        }
+
+        // 產生 DEAL 訊息
+        (0..self.players.len())
+            .map(|idx| (self.players[idx].conn_id, self.deal_message(idx)))
+            .collect()
+    }
+
+    /// 第一個 trick 的首引者 (規則未指定時為莊家)
+    fn opening_leader(&self) -> usize {
+        let hands: Vec<CardSet> = self.players.iter().map(|p| p.hand).collect();
+        self.rules.opening_leader(&hands).unwrap_or(self.dealer_idx)
+    }
+
+    /// 尚未傳牌的玩家 index
+    pub fn pending_passers(&self) -> Vec<usize> {
+        if self.phase != GamePhase::Passing {
+            return Vec::new();
+        }
+        (0..self.players.len()).filter(|&idx| self.passes[idx].is_none()).collect()
+    }
+
+    /// 產生 PASS_REQUEST 訊息
+    pub fn pass_request_message(&self, player_idx: usize) -> Option<ServerMessage> {
+        let direction = self.pass_direction?;
+        let to = direction.target(player_idx, self.players.len());
+        Some(ServerMessage::PassRequest {
+            direction: direction.as_str().to_string(),
+            to: self.players[to].player_id.clone(),
+            count: self.rules.pass_count() as u32,
+            timeout_ms: self.turn_timeout_ms,
+        })
+    }
+
+    /// 驗證傳牌是否合法
+    pub fn validate_pass(&self, conn_id: ConnectionId, cards: &[Card]) -> Result<(usize, CardSet), PassError> {
+        let player_idx = self.find_player_idx(conn_id).ok_or(PassError::NotInGame)?;
+        if self.phase != GamePhase::Passing {
+            return Err(PassError::NotPassing);
+        }
+        if self.passes[player_idx].is_some() {
+            return Err(PassError::AlreadyPassed);
+        }
+        if cards.len() != self.rules.pass_count() {
+            return Err(PassError::WrongCount);
+        }
+
+        let parsed = CardSet::from_protocol_strings(cards).ok_or(PassError::InvalidCard)?;
+        // 重複的牌只算一張，張數會變少
+        if parsed.len() as usize != cards.len() || !(parsed - self.players[player_idx].hand).is_empty() {
+            return Err(PassError::NotInHand);
+        }
+        Ok((player_idx, parsed))
+    }
+
+    /// 記錄傳牌 (需先通過 validate_pass)；所有人都選好後交換手牌並開始出牌
+    pub fn submit_pass(&mut self, player_idx: usize, cards: CardSet) -> PassResult {
+        self.passes[player_idx] = Some(cards);
+        if !self.pending_passers().is_empty() {
+            return PassResult::Waiting;
+        }
+
+        let direction = self.pass_direction.expect("passing without direction");
+        let n = self.players.len();
+        let passes: Vec<CardSet> = self.passes.iter_mut().map(|p| p.take().unwrap_or_default()).collect();
+
+        for (from, &cards) in passes.iter().enumerate() {
+            self.players[from].hand = self.players[from].hand - cards;
+        }
+        for (from, &cards) in passes.iter().enumerate() {
+            let to = direction.target(from, n);
+            self.players[to].hand |= cards;
+        }
+        self.dealt_hands = self.players.iter().map(|p| p.hand).collect();
+
+        let messages = (0..n)
+            .map(|to| {
+                let from = (0..n).find(|&f| direction.target(f, n) == to).unwrap_or(to);
+                let msg = ServerMessage::CardsPassed {
+                    direction: direction.as_str().to_string(),
+                    from: self.players[from].player_id.clone(),
+                    received: passes[from].to_protocol_strings(),
+                    hand: self.players[to].hand.to_protocol_strings(),
+                };
+                (self.players[to].conn_id, msg)
+            })
+            .collect();
+
+        let leader_idx = self.opening_leader();
         self.phase = GamePhase::WaitingForPlay {
-            current_player_idx: 0, // P1 先出
+            current_player_idx: leader_idx,
         };
+        PassResult::Exchanged(messages, leader_idx)
+    }
 
-        // 產生 DEAL 訊息
-        self.players
-            .iter()
-            .map(|p| {
-                let hand: Vec<Card> = p.hand.iter().map(|c| c.to_protocol_string()).collect();
-                let msg = ServerMessage::Deal {
-                    hand,
-                    total_tricks: TOTAL_TRICKS as u32,
+    /// 產生玩家目前手牌的 DEAL 訊息 (發牌與續連共用)
+    pub fn deal_message(&self, player_idx: usize) -> ServerMessage {
+        let hand = self.players[player_idx].hand.to_protocol_strings();
+        ServerMessage::Deal {
+            hand,
+            total_tricks: self.cards_per_player() as u32,
+            seed_commitment: self.deal_seed().commitment(),
+        }
+    }
+
+    /// 這副牌的 seed 與 secret
+    pub fn deal_seed(&self) -> DealSeed {
+        DealSeed {
+            seed: self.seed,
+            secret: self.secret.clone(),
+        }
+    }
+
+    /// 產生 TABLE_STATE 訊息 (當前 trick、桌面與分數)
+    pub fn table_state_message(&self) -> ServerMessage {
+        ServerMessage::TableState {
+            trick: self.current_trick,
+            table: self.table_plays(),
+            score: self.score.clone(),
+        }
+    }
+
+    /// 桌面出牌轉為協議格式
+    fn table_plays(&self) -> Vec<TablePlay> {
+        self.table.iter().map(|&(idx, card)| self.table_play(idx, card)).collect()
+    }
+
+    /// 產生 HISTORY 訊息 (回覆 GET_HISTORY)
+    pub fn history_message(&self) -> ServerMessage {
+        ServerMessage::History {
+            tricks: self.history.clone(),
+            current_trick: self.current_trick,
+            table: self.table_plays(),
+            score: self.score.clone(),
+        }
+    }
+
+    /// 產生座位的 STATE_SNAPSHOT 訊息 (回覆 GET_STATE 與續連後重送)
+    pub fn state_snapshot_message(&self, player_idx: usize) -> ServerMessage {
+        // 輪到的座位由這條連線控制時 (含主打者替夢家出牌) 才附合法牌
+        let turn = self
+            .current_player_idx()
+            .filter(|&idx| self.controller_of(idx) == player_idx);
+        ServerMessage::StateSnapshot {
+            player_id: self.players[player_idx].player_id.clone(),
+            board: self.board,
+            trump: self.trump_symbol(),
+            hand: self.players[player_idx].hand.to_protocol_strings(),
+            legal: turn
+                .map(|idx| self.get_legal_moves(idx).to_protocol_strings())
+                .unwrap_or_default(),
+            seat: turn
+                .filter(|&idx| idx != player_idx)
+                .map(|idx| self.players[idx].player_id.clone()),
+            trick: self.current_trick,
+            table: self.table_plays(),
+            score: self.score.clone(),
+            history: self.history.clone(),
+            seats: self.seat_states(),
+        }
+    }
+
+    /// 將座位的 conn_id 換成新的連線 (續連)
+    pub fn rebind_conn(&mut self, player_id: &str, new_conn_id: ConnectionId) -> bool {
+        match self.players.iter_mut().find(|p| p.player_id == player_id) {
+            Some(player) => {
+                player.conn_id = new_conn_id;
+                player.connected = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 設定座位的在線狀態
+    pub fn set_connected(&mut self, player_id: &str, connected: bool) {
+        if let Some(player) = self.players.iter_mut().find(|p| p.player_id == player_id) {
+            player.connected = connected;
+        }
+    }
+
+    /// 換新的 seed 準備重新發牌 (四家都 PASS 時，之後由牌組來源重新發牌)
+    pub fn reseed(&mut self, deal_seed: DealSeed) {
+        self.seed = deal_seed.seed;
+        self.secret = deal_seed.secret;
+        self.table.clear();
+    }
+
+    /// 取得當前應該叫牌的玩家 index
+    pub fn current_bidder_idx(&self) -> Option<usize> {
+        match &self.phase {
+            GamePhase::Bidding { current_player_idx } => Some(*current_player_idx),
+            _ => None,
+        }
+    }
+
+    /// 取得當前應該行動 (叫牌或出牌) 的玩家 index
+    pub fn current_turn_idx(&self) -> Option<usize> {
+        self.current_bidder_idx().or_else(|| self.current_player_idx())
+    }
+
+    /// 驗證叫牌是否合法
+    pub fn validate_call(&self, conn_id: ConnectionId, call: Call) -> Result<usize, AuctionError> {
+        let player_idx = self.find_player_idx(conn_id).ok_or(AuctionError::NotInGame)?;
+        let auction = self.auction.as_ref().ok_or(AuctionError::NotBidding)?;
+        if self.current_bidder_idx().is_none() {
+            return Err(AuctionError::NotBidding);
+        }
+
+        auction.validate(player_idx, call)?;
+        Ok(player_idx)
+    }
+
+    /// 執行叫牌 (需先通過 validate_call)
+    pub fn make_call(&mut self, player_idx: usize, call: Call) -> AuctionResult {
+        let auction = self.auction.as_mut().expect("auction not started");
+        let status = auction.call(player_idx, call).expect("call not validated");
+        let next_idx = auction.current_bidder();
+
+        match status {
+            AuctionStatus::Open => {
+                self.phase = GamePhase::Bidding {
+                    current_player_idx: next_idx,
                 };
-                (p.conn_id, msg)
+                AuctionResult::Continue(self.auction_update_message(), next_idx)
+            }
+            AuctionStatus::Contract(contract) => {
+                // 合約決定王牌，由主打者左手邊第一位對手首引
+                self.contract = Some(contract);
+                self.trump = contract.strain.trump();
+                let n = self.players.len();
+                let declarer_team = self.players[contract.declarer].team;
+                let leader_idx = (1..n)
+                    .map(|i| (contract.declarer + i) % n)
+                    .find(|&idx| self.players[idx].team != declarer_team)
+                    .unwrap_or((contract.declarer + 1) % n);
+                // 主打者的同伴成為夢家
+                self.dummy_idx = (1..n)
+                    .map(|i| (contract.declarer + i) % n)
+                    .find(|&idx| self.players[idx].team == declarer_team);
+                self.phase = GamePhase::WaitingForPlay {
+                    current_player_idx: leader_idx,
+                };
+                AuctionResult::Contract(self.auction_update_message(), leader_idx)
+            }
+            AuctionStatus::PassedOut => {
+                self.phase = GamePhase::WaitingToDeal;
+                AuctionResult::PassedOut(self.auction_update_message())
+            }
+        }
+    }
+
+    /// 產生 AUCTION_UPDATE 訊息
+    pub fn auction_update_message(&self) -> ServerMessage {
+        let calls = self
+            .auction
+            .as_ref()
+            .map(|a| a.calls())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(idx, call)| AuctionCall {
+                player_id: self.players[idx].player_id.clone(),
+                call: call.to_protocol_string(),
             })
-            .collect()
+            .collect();
+
+        let passed_out = self
+            .auction
+            .as_ref()
+            .is_some_and(|a| a.status() == AuctionStatus::PassedOut);
+
+        ServerMessage::AuctionUpdate {
+            dealer: self.players[self.dealer_idx].player_id.clone(),
+            calls,
+            next: self
+                .current_bidder_idx()
+                .map(|idx| self.players[idx].player_id.clone()),
+            contract: self.contract.map(|c| c.to_string()),
+            declarer: self
+                .contract
+                .map(|c| self.players[c.declarer].player_id.clone()),
+            passed_out,
+        }
+    }
+
+    /// 實際替座位出牌的玩家 index (夢家由主打者控制)
+    pub fn controller_of(&self, seat_idx: usize) -> usize {
+        match (self.dummy_idx, self.contract) {
+            (Some(dummy), Some(contract)) if dummy == seat_idx => contract.declarer,
+            _ => seat_idx,
+        }
+    }
+
+    /// 首引之後攤出夢家手牌 (只回傳一次)
+    pub fn reveal_dummy(&mut self) -> Option<ServerMessage> {
+        let opening_lead_made = self.current_trick > 1 || !self.table.is_empty();
+        if self.dummy_revealed || !opening_lead_made {
+            return None;
+        }
+        self.dummy_idx?;
+        self.dummy_revealed = true;
+        self.dummy_reveal_message()
+    }
+
+    /// 產生 DUMMY_REVEAL 訊息 (夢家目前手牌；尚未攤牌時為 None)
+    pub fn dummy_reveal_message(&self) -> Option<ServerMessage> {
+        if !self.dummy_revealed {
+            return None;
+        }
+        let dummy_idx = self.dummy_idx?;
+        let contract = self.contract?;
+        Some(ServerMessage::DummyReveal {
+            dummy: self.players[dummy_idx].player_id.clone(),
+            declarer: self.players[contract.declarer].player_id.clone(),
+            hand: self.players[dummy_idx].hand.to_protocol_strings(),
+        })
+    }
+
+    /// 全 PASS 且不重發時直接結束這副牌 (沒有合約，雙方墩數為 0)
+    pub fn close_passed_out(&mut self) {
+        self.phase = GamePhase::GameOver;
+    }
+
+    /// 遊戲是否已結束
+    pub fn is_game_over(&self) -> bool {
+        self.phase == GamePhase::GameOver
     }
 
     /// 取得當前應該出牌的玩家 index
@@ -127,73 +608,80 @@ impl GameEngine {
     pub fn your_turn_message(&self, player_idx: usize) -> ServerMessage {
         let legal = self.get_legal_moves(player_idx);
 
-        let table: Vec<TablePlay> = self
-            .table
-            .iter()
-            .map(|(idx, card)| TablePlay {
-                player_id: self.players[*idx].player_id.clone(),
-                card: card.to_protocol_string(),
-            })
-            .collect();
-
         ServerMessage::YourTurn {
             trick: self.current_trick,
-            table,
-            legal: legal.iter().map(|c| c.to_protocol_string()).collect(),
-            timeout_ms: TIMEOUT_MS,
+            table: self.table_plays(),
+            legal: legal.to_protocol_strings(),
+            timeout_ms: self.turn_timeout_ms,
+            seat: (self.controller_of(player_idx) != player_idx)
+                .then(|| self.players[player_idx].player_id.clone()),
+            seats: self.seat_states(),
         }
     }
 
-    /// 取得合法出牌
-    pub fn get_legal_moves(&self, player_idx: usize) -> Vec<CardData> {
-        let hand = &self.players[player_idx].hand;
-
-        if self.table.is_empty() {
-            // 第一個出牌的人可以出任何牌
-            return hand.clone();
-        }
-
-        // 取得領牌花色
-        let lead_suit = self.table[0].1.suit;
+    /// 各座位的 trick 數、剩餘張數、在線與是否輪到出牌
+    pub fn seat_states(&self) -> Vec<SeatState> {
+        let to_play = self.current_player_idx();
+        self.players
+            .iter()
+            .enumerate()
+            .map(|(idx, p)| SeatState {
+                player_id: p.player_id.clone(),
+                tricks: p.tricks,
+                cards_left: p.hand.len(),
+                connected: p.connected,
+                to_play: to_play == Some(idx),
+            })
+            .collect()
+    }
 
-        // 檢查手中是否有同花色的牌
-        let same_suit: Vec<CardData> = hand.iter().filter(|c| c.suit == lead_suit).copied().collect();
+    /// 王牌花色的協議表示 (None = 無王)
+    pub fn trump_symbol(&self) -> Option<String> {
+        self.trump.map(|s| s.symbol().to_string())
+    }
 
-        if same_suit.is_empty() {
-            // 沒有同花色，可以出任何牌
-            hand.clone()
-        } else {
-            // 必須跟牌
-            same_suit
+    /// 目前 trick 的規則判斷狀態
+    pub fn trick_context(&self) -> TrickContext<'_> {
+        TrickContext {
+            trick: self.current_trick,
+            table: &self.table,
+            played: self.played,
         }
     }
 
+    /// 取得合法出牌 (由規則決定)
+    pub fn get_legal_moves(&self, player_idx: usize) -> CardSet {
+        self.rules.legal_moves(self.players[player_idx].hand, &self.trick_context())
+    }
+
     /// 驗證出牌是否合法
+    /// 回傳出牌的座位 (主打者替夢家出牌時為夢家座位)
     pub fn validate_play(&self, conn_id: ConnectionId, card_str: &str) -> Result<(usize, CardData), PlayError> {
         // 找到玩家
-        let player_idx = self
+        let conn_idx = self
             .players
             .iter()
             .position(|p| p.conn_id == conn_id)
             .ok_or(PlayError::NotInGame)?;
 
-        // 檢查是否輪到該玩家
+        // 檢查是否輪到該玩家控制的座位
         let current_idx = self.current_player_idx().ok_or(PlayError::NotYourTurn)?;
-        if player_idx != current_idx {
+        if self.controller_of(current_idx) != conn_idx {
             return Err(PlayError::NotYourTurn);
         }
+        let player_idx = current_idx;
 
         // 解析卡牌
         let card = CardData::from_protocol_string(card_str).ok_or(PlayError::InvalidCard)?;
 
         // 檢查牌是否在手牌中
-        if !self.players[player_idx].hand.contains(&card) {
+        if !self.players[player_idx].hand.contains(card) {
             return Err(PlayError::NotInHand);
         }
 
         // 檢查是否符合跟牌規則
         let legal_moves = self.get_legal_moves(player_idx);
-        if !legal_moves.contains(&card) {
+        if !legal_moves.contains(card) {
             return Err(PlayError::NotLegal);
         }
 
@@ -203,9 +691,7 @@ impl GameEngine {
     /// 執行出牌
     pub fn play_card(&mut self, player_idx: usize, card: CardData) -> PlayResult {
         // 從手牌移除
-        let hand = &mut self.players[player_idx].hand;
-        let pos = hand.iter().position(|c| *c == card).unwrap();
-        hand.remove(pos);
+        self.players[player_idx].hand.remove(card);
 
         // 加入桌面
         self.table.push((player_idx, card));
@@ -217,13 +703,14 @@ impl GameEngine {
             trick: self.current_trick,
         };
 
-        // 檢查是否 4 人都出完
-        if self.table.len() == 4 {
+        // 檢查是否所有人都出完
+        let num_players = self.players.len();
+        if self.table.len() == num_players {
             self.phase = GamePhase::TrickComplete;
             PlayResult::TrickComplete(broadcast)
         } else {
             // 下一位玩家
-            let next_idx = (player_idx + 1) % 4;
+            let next_idx = (player_idx + 1) % num_players;
             self.phase = GamePhase::WaitingForPlay {
                 current_player_idx: next_idx,
             };
@@ -234,165 +721,783 @@ impl GameEngine {
     /// 結算 Trick
     pub fn resolve_trick(&mut self) -> TrickResolution {
         // 判定 winner
-        let lead_suit = self.table[0].1.suit;
-        let winner_idx = self
-            .table
-            .iter()
-            .filter(|(_, card)| card.suit == lead_suit)
-            .max_by_key(|(_, card)| card.rank.0)
-            .map(|(idx, _)| *idx)
-            .unwrap();
+        let winner_idx = self.rules.trick_winner(&self.table, self.trump);
 
         let winner_team = self.players[winner_idx].team;
 
-        // 更新分數 (每 trick 1 分)
+        // 更新分數 (由規則決定每 trick 的分數)
+        let cards: Vec<CardData> = self.table.iter().map(|(_, c)| *c).collect();
+        let points = self.rules.trick_points(&cards);
+        self.player_points[winner_idx] += points;
         match winner_team {
-            Team::Human => self.score.human += 1,
-            Team::Ai => self.score.ai += 1,
+            Team::Human => self.score.human += points,
+            Team::Ai => self.score.ai += points,
         }
 
         // 記錄歷史
+        let (leader_idx, lead) = self.table[0];
         let trick_history = TrickHistory {
             trick: self.current_trick,
             winner: self.players[winner_idx].player_id.clone(),
             cards: self.table.iter().map(|(_, c)| c.to_protocol_string()).collect(),
+            leader: self.players[leader_idx].player_id.clone(),
+            led_suit: lead.suit.symbol().to_string(),
+            plays: self.table_plays(),
+            score: self.score.clone(),
         };
         self.history.push(trick_history);
 
-        // 產生 TRICK_RESULT 訊息
+        // 清除桌面
+        let plays = self.table_plays();
+        self.table.clear();
+        self.played |= cards.iter().collect();
+        self.last_trick_winner = Some(winner_idx);
+        self.players[winner_idx].tricks += 1;
+
+        // 檢查是否遊戲結束
+        let hands: Vec<CardSet> = self.players.iter().map(|p| p.hand).collect();
+        let game_over = self.rules.is_hand_over(self.current_trick, &hands);
+        self.phase = if game_over {
+            GamePhase::GameOver
+        } else {
+            GamePhase::WaitingForPlay {
+                current_player_idx: winner_idx,
+            }
+        };
+
+        // 產生 TRICK_RESULT 訊息 (座位狀態為結算後)
         let result_msg = ServerMessage::TrickResult {
             trick: self.current_trick,
-            plays: self
-                .table
-                .iter()
-                .map(|(idx, card)| TablePlay {
-                    player_id: self.players[*idx].player_id.clone(),
-                    card: card.to_protocol_string(),
-                })
-                .collect(),
+            plays,
             winner: self.players[winner_idx].player_id.clone(),
             score: self.score.clone(),
+            trump: self.trump_symbol(),
+            seats: self.seat_states(),
         };
 
-        // 清除桌面
-        self.table.clear();
-        self.last_trick_winner = Some(winner_idx);
-
-        // 檢查是否遊戲結束
-        if self.current_trick >= TOTAL_TRICKS as u32 {
-            self.phase = GamePhase::GameOver;
+        if game_over {
             TrickResolution::GameOver(result_msg)
         } else {
             // 下一 trick
             self.current_trick += 1;
-            self.phase = GamePhase::WaitingForPlay {
-                current_player_idx: winner_idx,
-            };
             TrickResolution::NextTrick(result_msg, winner_idx)
         }
     }
 
-    /// 產生 GAME_OVER 訊息
-    pub fn game_over_message(&self) -> ServerMessage {
-        let winner = if self.score.human > self.score.ai {
-            Team::Human
-        } else if self.score.ai > self.score.human {
-            Team::Ai
-        } else {
-            // 平手時，預設 HUMAN 贏 (或可自訂規則)
-            Team::Human
-        };
-
-        ServerMessage::GameOver {
-            final_score: self.score.clone(),
-            winner,
-            history: self.history.clone(),
-        }
+    /// 記錄一次超時，回傳該玩家的連續超時次數
+    pub fn record_timeout(&mut self, player_idx: usize) -> u32 {
+        let player = &mut self.players[player_idx];
+        player.timeouts += 1;
+        player.timeouts
     }
 
-    /// 取得所有玩家的 conn_id - 預留供未來擴充
-    #[allow(dead_code)]
-    pub fn all_conn_ids(&self) -> Vec<ConnectionId> {
-        self.players.iter().map(|p| p.conn_id).collect()
+    /// 玩家自行出牌，連續超時次數歸零
+    pub fn reset_timeouts(&mut self, player_idx: usize) {
+        self.players[player_idx].timeouts = 0;
     }
 
-    /// 透過 conn_id 找玩家 index
-    #[allow(dead_code)]
-    pub fn find_player_idx(&self, conn_id: ConnectionId) -> Option<usize> {
-        self.players.iter().position(|p| p.conn_id == conn_id)
+    /// 玩家棄權，遊戲直接結束 (對手隊伍獲勝)
+    pub fn forfeit(&mut self, player_idx: usize) {
+        self.forfeited_by = Some(player_idx);
+        self.table.clear();
+        self.phase = GamePhase::GameOver;
     }
-}
 
-/// 出牌錯誤
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum PlayError {
-    NotInGame,
-    NotYourTurn,
-    InvalidCard,
-    NotInHand,
-    NotLegal,
-}
+    /// 隊伍拿到的墩數 (含攤牌宣告分到的墩數)
+    pub fn tricks_for(&self, team: Team) -> u32 {
+        let claimed = self.claimed.as_ref().map_or(0, |c| {
+            if self.players[c.claimer].team == team { c.tricks } else { c.remaining - c.tricks }
+        });
+        self.history
+            .iter()
+            .filter(|h| self.players.iter().any(|p| p.player_id == h.winner && p.team == team))
+            .count() as u32
+            + claimed
+    }
 
-/// 出牌結果
-pub enum PlayResult {
-    /// 繼續遊戲，下一位玩家出牌
-    Continue(ServerMessage, usize), // (broadcast_msg, next_player_idx)
-    /// Trick 完成
-    TrickComplete(ServerMessage),
-}
+    /// 還沒結算的 tricks (含進行中的 trick)
+    pub fn tricks_remaining(&self) -> u32 {
+        (self.cards_per_player() as u32).saturating_sub(self.history.len() as u32)
+    }
 
-/// Trick 結算結果
-pub enum TrickResolution {
-    /// 下一 trick
-    NextTrick(ServerMessage, usize), // (trick_result_msg, next_player_idx)
-    /// 遊戲結束
-    GameOver(ServerMessage),
-}
+    /// 驗證 CLAIM / CONCEDE：搭檔制規則的出牌階段，且沒有其他宣告等待回覆
+    pub fn validate_claim(&self, conn_id: ConnectionId, tricks: u32) -> Result<usize, ClaimError> {
+        let player_idx = self.find_player_idx(conn_id).ok_or(ClaimError::NotInGame)?;
+        if self.current_player_idx().is_none() {
+            return Err(ClaimError::NotPlaying);
+        }
+        if !self.rules.partnership() {
+            return Err(ClaimError::NotPartnership);
+        }
+        if self.claim.is_some() || self.undo.is_some() {
+            return Err(ClaimError::ClaimPending);
+        }
+        if tricks > self.tricks_remaining() {
+            return Err(ClaimError::TooManyTricks);
+        }
+        Ok(player_idx)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use super::super::deck::{Rank, Suit};
+    /// 提出攤牌宣告 (需先通過 validate_claim)，回傳給所有真人的 CLAIM
+    pub fn start_claim(&mut self, player_idx: usize, tricks: u32) -> ServerMessage {
+        let claim = Claim {
+            claimer: player_idx,
+            tricks,
+            remaining: self.tricks_remaining(),
+            accepted: Vec::new(),
+            cards: Vec::new(),
+        };
+        let msg = self.claim_offer(&claim);
+        self.claim = Some(claim);
+        msg
+    }
 
-    fn create_test_engine() -> GameEngine {
-        let players = vec![
-            (1, "P1".to_string(), Team::Human),
-            (2, "P2".to_string(), Team::Human),
-            (3, "P3".to_string(), Team::Ai),
-            (4, "P4".to_string(), Team::Ai),
-        ];
-        GameEngine::new(12345, players)
+    /// 等待回覆的宣告的 CLAIM 訊息 (續連時重送)
+    pub fn claim_message(&self) -> Option<ServerMessage> {
+        self.claim.as_ref().map(|claim| self.claim_offer(claim))
     }
 
-    #[test]
-    fn test_deal_cards() {
-        let mut engine = create_test_engine();
-        let messages = engine.deal();
+    fn claim_offer(&self, claim: &Claim) -> ServerMessage {
+        let claimer = &self.players[claim.claimer];
+        ServerMessage::Claim {
+            player_id: claimer.player_id.clone(),
+            tricks: claim.tricks,
+            remaining: claim.remaining,
+            hand: claimer.hand.to_protocol_strings(),
+        }
+    }
 
-        assert_eq!(messages.len(), 4);
-        for player in &engine.players {
-            assert_eq!(player.hand.len(), 13);
+    /// 還沒回覆宣告的對手
+    pub fn claim_responders(&self) -> Vec<usize> {
+        match &self.claim {
+            Some(claim) => {
+                let team = self.players[claim.claimer].team;
+                (0..self.players.len())
+                    .filter(|&idx| self.players[idx].team != team && !claim.accepted.contains(&idx))
+                    .collect()
+            }
+            None => Vec::new(),
         }
     }
 
-    #[test]
-    fn test_legal_moves_first_play() {
-        let mut engine = create_test_engine();
-        engine.deal();
+    /// 驗證 CLAIM_RESPONSE：有等待中的宣告，且是還沒回覆的對手
+    pub fn validate_claim_response(&self, conn_id: ConnectionId) -> Result<usize, ClaimError> {
+        let player_idx = self.find_player_idx(conn_id).ok_or(ClaimError::NotInGame)?;
+        if self.claim.is_none() {
+            return Err(ClaimError::NoClaim);
+        }
+        if !self.claim_responders().contains(&player_idx) {
+            return Err(ClaimError::NotOpponent);
+        }
+        Ok(player_idx)
+    }
 
-        // 第一個出牌的人可以出任何牌
-        let legal = engine.get_legal_moves(0);
-        assert_eq!(legal.len(), 13);
+    /// 對手回覆宣告：有人拒絕就取消，對手全部接受時依宣告結束這副牌
+    /// (`reason` 是 AI 拒絕的原因，見 claim::Verdict)
+    pub fn respond_claim(&mut self, player_idx: usize, accept: bool, reason: Option<&str>) -> ClaimOutcome {
+        let claim = self.claim.as_mut().expect("claim response not validated");
+        if !accept {
+            let claim = self.claim.take().unwrap();
+            return ClaimOutcome::Rejected(self.claim_result_message(&claim, false, Some(player_idx), reason));
+        }
+        claim.accepted.push(player_idx);
+        if !self.claim_responders().is_empty() {
+            return ClaimOutcome::Pending;
+        }
+        let claim = self.claim.take().unwrap();
+        let msg = self.claim_result_message(&claim, true, Some(player_idx), None);
+        self.settle_claim(claim);
+        ClaimOutcome::Accepted(msg)
     }
 
-    #[test]
-    fn test_follow_suit_rule() {
-        let mut engine = create_test_engine();
-        engine.deal();
+    /// 撤回等待中的宣告 (回覆期限已過)，回傳 CLAIM_RESULT
+    pub fn withdraw_claim(&mut self) -> Option<ServerMessage> {
+        let claim = self.claim.take()?;
+        Some(self.claim_result_message(&claim, false, None, None))
+    }
 
-        // 模擬 P1 出了一張黑桃
-        let p1_hand = &engine.players[0].hand;
-        let spade_card = p1_hand.iter().find(|c| c.suit == Suit::Spades).copied();
+    /// 認輸 (需先以 0 墩通過 validate_claim)：剩下的 tricks 全歸對手並結束這副牌
+    pub fn concede(&mut self, player_idx: usize) -> ServerMessage {
+        let claim = Claim {
+            claimer: player_idx,
+            tricks: 0,
+            remaining: self.tricks_remaining(),
+            accepted: Vec::new(),
+            cards: Vec::new(),
+        };
+        let msg = self.claim_result_message(&claim, true, None, None);
+        self.settle_claim(claim);
+        msg
+    }
+
+    fn claim_result_message(
+        &self,
+        claim: &Claim,
+        accepted: bool,
+        by: Option<usize>,
+        reason: Option<&str>,
+    ) -> ServerMessage {
+        ServerMessage::ClaimResult {
+            player_id: self.players[claim.claimer].player_id.clone(),
+            tricks: claim.tricks,
+            accepted,
+            by: by.map(|idx| self.players[idx].player_id.clone()),
+            reason: reason.map(str::to_string),
+        }
+    }
+
+    /// 驗證 UNDO_REQUEST：房間允許悔牌的出牌階段、沒有其他請求等待回覆，
+    /// 且要收回的牌在允許的範圍內 (UndoPolicy::Trick 不能跨過已結算的 trick)
+    pub fn validate_undo(&self, conn_id: ConnectionId) -> Result<usize, UndoError> {
+        let player_idx = self.find_player_idx(conn_id).ok_or(UndoError::NotInGame)?;
+        if self.undo_policy == UndoPolicy::Off {
+            return Err(UndoError::Disabled);
+        }
+        if self.current_player_idx().is_none() {
+            return Err(UndoError::NotPlaying);
+        }
+        if self.claim.is_some() || self.undo.is_some() {
+            return Err(UndoError::RequestPending);
+        }
+        let count = self.undo_count(player_idx).ok_or(UndoError::NothingToUndo)?;
+        if count > self.table.len() && self.undo_policy != UndoPolicy::Board {
+            return Err(UndoError::CrossesTrick);
+        }
+        Ok(player_idx)
+    }
+
+    /// 提出悔牌請求 (需先通過 validate_undo)，回傳給所有真人的 UNDO_REQUEST
+    pub fn start_undo(&mut self, player_idx: usize) -> ServerMessage {
+        let undo = Undo {
+            requester: player_idx,
+            accepted: Vec::new(),
+        };
+        let msg = self.undo_request(&undo);
+        self.undo = Some(undo);
+        msg
+    }
+
+    /// 等待回覆的悔牌請求的 UNDO_REQUEST 訊息 (續連時重送)
+    pub fn undo_message(&self) -> Option<ServerMessage> {
+        self.undo.as_ref().map(|undo| self.undo_request(undo))
+    }
+
+    fn undo_request(&self, undo: &Undo) -> ServerMessage {
+        let plays = self.board_plays();
+        let count = self.undo_count(undo.requester).unwrap_or(0);
+        ServerMessage::UndoRequest {
+            player_id: self.players[undo.requester].player_id.clone(),
+            cards: plays.iter().rev().take(count).map(|&(idx, card)| self.table_play(idx, card)).collect(),
+        }
+    }
+
+    /// 悔牌要徵求同意的對手：搭檔制是另一隊，個人計分規則是其他所有座位
+    pub fn undo_opponents(&self, requester: usize) -> Vec<usize> {
+        let team = self.players[requester].team;
+        let partnership = self.rules.partnership();
+        (0..self.players.len())
+            .filter(|&idx| idx != requester && (!partnership || self.players[idx].team != team))
+            .collect()
+    }
+
+    /// 還沒同意悔牌的對手
+    pub fn undo_responders(&self) -> Vec<usize> {
+        match &self.undo {
+            Some(undo) => self
+                .undo_opponents(undo.requester)
+                .into_iter()
+                .filter(|idx| !undo.accepted.contains(idx))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// 驗證 UNDO_RESPONSE：有等待中的悔牌請求，且是還沒回覆的對手
+    pub fn validate_undo_response(&self, conn_id: ConnectionId) -> Result<usize, UndoError> {
+        let player_idx = self.find_player_idx(conn_id).ok_or(UndoError::NotInGame)?;
+        if self.undo.is_none() {
+            return Err(UndoError::NoRequest);
+        }
+        if !self.undo_responders().contains(&player_idx) {
+            return Err(UndoError::NotOpponent);
+        }
+        Ok(player_idx)
+    }
+
+    /// 對手回覆悔牌：有人拒絕就取消，對手全部同意時收回牌
+    pub fn respond_undo(&mut self, player_idx: usize, accept: bool) -> UndoOutcome {
+        let undo = self.undo.as_mut().expect("undo response not validated");
+        if !accept {
+            let undo = self.undo.take().unwrap();
+            return UndoOutcome::Rejected(self.undo_result_message(&undo, false, Some(player_idx)));
+        }
+        undo.accepted.push(player_idx);
+        if !self.undo_responders().is_empty() {
+            return UndoOutcome::Pending;
+        }
+        let undo = self.undo.take().unwrap();
+        let result_msg = self.undo_result_message(&undo, true, Some(player_idx));
+        let cards = self.take_back(undo.requester);
+        let cancel_msg = ServerMessage::PlayCancel {
+            player_id: self.players[undo.requester].player_id.clone(),
+            cards,
+            trick: self.current_trick,
+            table: self.table_plays(),
+            score: self.score.clone(),
+            seats: self.seat_states(),
+        };
+        UndoOutcome::Accepted(result_msg, cancel_msg)
+    }
+
+    /// 撤回等待中的悔牌請求 (回覆期限已過)，回傳 UNDO_RESULT
+    pub fn withdraw_undo(&mut self) -> Option<ServerMessage> {
+        let undo = self.undo.take()?;
+        Some(self.undo_result_message(&undo, false, None))
+    }
+
+    fn undo_result_message(&self, undo: &Undo, accepted: bool, by: Option<usize>) -> ServerMessage {
+        ServerMessage::UndoResult {
+            player_id: self.players[undo.requester].player_id.clone(),
+            accepted,
+            by: by.map(|idx| self.players[idx].player_id.clone()),
+        }
+    }
+
+    /// 這副牌依序出過的牌 (已結算的 tricks 加上桌面)
+    fn board_plays(&self) -> Vec<(usize, CardData)> {
+        self.history
+            .iter()
+            .flat_map(|trick| self.trick_plays(trick))
+            .chain(self.table.iter().copied())
+            .collect()
+    }
+
+    /// trick 歷史中的出牌 (player_idx, card)
+    fn trick_plays(&self, trick: &TrickHistory) -> Vec<(usize, CardData)> {
+        trick
+            .plays
+            .iter()
+            .filter_map(|p| Some((self.seat_of(&p.player_id)?, CardData::from_protocol_string(&p.card)?)))
+            .collect()
+    }
+
+    fn seat_of(&self, player_id: &str) -> Option<usize> {
+        self.players.iter().position(|p| p.player_id == player_id)
+    }
+
+    fn table_play(&self, idx: usize, card: CardData) -> TablePlay {
+        TablePlay {
+            player_id: self.players[idx].player_id.clone(),
+            card: card.to_protocol_string(),
+        }
+    }
+
+    /// 收回 player_idx 控制的座位 (含主打者代出的夢家) 最後一張牌時，連同之後出的牌共要收回幾張
+    fn undo_count(&self, player_idx: usize) -> Option<usize> {
+        let plays = self.board_plays();
+        plays
+            .iter()
+            .rposition(|&(seat, _)| self.controller_of(seat) == player_idx)
+            .map(|i| plays.len() - i)
+    }
+
+    /// 收回 player_idx 最後一張牌及之後的牌，回傳收回的牌 (由最後出的往前)
+    fn take_back(&mut self, player_idx: usize) -> Vec<TablePlay> {
+        let count = self.undo_count(player_idx).unwrap_or(0);
+        let mut taken = Vec::with_capacity(count);
+        for _ in 0..count {
+            if self.table.is_empty() {
+                self.reopen_last_trick();
+            }
+            if let Some((idx, card)) = self.table.pop() {
+                self.players[idx].hand.insert(card);
+                self.phase = GamePhase::WaitingForPlay { current_player_idx: idx };
+                taken.push(self.table_play(idx, card));
+            }
+        }
+        // 收回首引時夢家重新蓋牌，再次首引後才攤出
+        if self.history.is_empty() && self.table.is_empty() {
+            self.dummy_revealed = false;
+        }
+        taken
+    }
+
+    /// 把最後一個已結算的 trick 放回桌面 (還原分數、墩數與出過的牌)
+    fn reopen_last_trick(&mut self) {
+        let trick = match self.history.pop() {
+            Some(t) => t,
+            None => return,
+        };
+        let table = self.trick_plays(&trick);
+        let cards: Vec<CardData> = table.iter().map(|(_, c)| *c).collect();
+        if let Some(winner_idx) = self.seat_of(&trick.winner) {
+            let points = self.rules.trick_points(&cards);
+            self.player_points[winner_idx] = self.player_points[winner_idx].saturating_sub(points);
+            match self.players[winner_idx].team {
+                Team::Human => self.score.human = self.score.human.saturating_sub(points),
+                Team::Ai => self.score.ai = self.score.ai.saturating_sub(points),
+            }
+            self.players[winner_idx].tricks = self.players[winner_idx].tricks.saturating_sub(1);
+        }
+        self.played = self.played - cards.iter().collect();
+        self.last_trick_winner = self.history.last().and_then(|t| self.seat_of(&t.winner));
+        self.current_trick = trick.trick;
+        self.table = table;
+    }
+
+    /// 依宣告分配剩下的 tricks (搭檔制每墩 1 分) 並結束這副牌
+    fn settle_claim(&mut self, mut claim: Claim) {
+        claim.cards = self.players.iter().map(|p| p.hand).collect();
+        for &(idx, card) in &self.table {
+            claim.cards[idx].insert(card);
+        }
+        let own = claim.tricks;
+        let other = claim.remaining - claim.tricks;
+        let (human, ai) = match self.players[claim.claimer].team {
+            Team::Human => (own, other),
+            Team::Ai => (other, own),
+        };
+        self.score.human += human;
+        self.score.ai += ai;
+        self.table.clear();
+        self.phase = GamePhase::GameOver;
+        self.claimed = Some(claim);
+    }
+
+    /// 合約的複式計分 (沒有合約或有人棄權時為 None)
+    pub fn hand_score(&self) -> Option<HandScore> {
+        if self.forfeited_by.is_some() {
+            return None;
+        }
+        let contract = self.contract?;
+        let declarer_team = self.players[contract.declarer].team;
+        Some(HandScore::new(
+            contract,
+            declarer_team,
+            self.tricks_for(declarer_team),
+            Vulnerability::for_board(self.board),
+        ))
+    }
+
+    /// 個人計分 (搭檔制規則為 None)
+    pub fn player_scores(&self) -> Option<Vec<PlayerScore>> {
+        if self.rules.partnership() {
+            return None;
+        }
+        let points = self.rules.final_points(&self.player_points);
+        Some(
+            self.players
+                .iter()
+                .enumerate()
+                .map(|(idx, p)| PlayerScore {
+                    player_id: p.player_id.clone(),
+                    taken: self.player_points[idx],
+                    points: points[idx],
+                })
+                .collect(),
+        )
+    }
+
+    /// 產生 GAME_OVER 訊息
+    pub fn game_over_message(&self) -> ServerMessage {
+        let hand_score = self.hand_score();
+        let player_scores = self.player_scores();
+
+        let mut tie_broken = false;
+        // 個人計分時罰分最低的座位獲勝 (棄權者除外，分數越低越好)
+        let winning_seat = player_scores.as_ref().and_then(|scores| {
+            let seats: Vec<usize> = (0..scores.len()).filter(|&idx| Some(idx) != self.forfeited_by).collect();
+            let best = seats.iter().map(|&idx| scores[idx].points).min()?;
+            let tied: Vec<usize> = seats.into_iter().filter(|&idx| scores[idx].points == best).collect();
+            match tied[..] {
+                [idx] => Some(idx),
+                _ => {
+                    let seat = self.break_tie(|idx| idx, &tied);
+                    tie_broken = seat.is_some();
+                    seat
+                }
+            }
+        });
+        let winner = if player_scores.is_some() {
+            winning_seat.map_or(Outcome::Draw, |idx| self.players[idx].team.into())
+        } else if let Some(idx) = self.forfeited_by {
+            match self.players[idx].team {
+                Team::Human => Outcome::Ai,
+                Team::Ai => Outcome::Human,
+            }
+        } else if let Some(hs) = &hand_score {
+            // 有合約時由複式分數決定 (不會是 0)
+            if hs.score_for(Team::Human) > 0 { Outcome::Human } else { Outcome::Ai }
+        } else {
+            match self.score.human.cmp(&self.score.ai) {
+                Ordering::Greater => Outcome::Human,
+                Ordering::Less => Outcome::Ai,
+                Ordering::Equal => {
+                    let team = self.break_tie(|idx| self.players[idx].team, &[Team::Human, Team::Ai]);
+                    tie_broken = team.is_some();
+                    team.map_or(Outcome::Draw, Outcome::from)
+                }
+            }
+        };
+        let tie_break = tie_broken.then(|| self.tie_break.name().to_string());
+
+        ServerMessage::GameOver {
+            final_score: self.score.clone(),
+            winner,
+            winning_seat: winning_seat.map(|idx| self.players[idx].player_id.clone()),
+            tie_break,
+            history: self.history.clone(),
+            seed: self.seed,
+            secret: self.secret.clone(),
+            trump: self.trump_symbol(),
+            forfeit: self.forfeited_by.map(|idx| self.players[idx].player_id.clone()),
+            contract: hand_score.as_ref().map(|hs| hs.contract.to_string()),
+            result: hand_score.as_ref().map(|hs| hs.result.clone()),
+            duplicate_score: hand_score.as_ref().map(|hs| SignedScore {
+                human: hs.score_for(Team::Human),
+                ai: hs.score_for(Team::Ai),
+            }),
+            player_scores,
+            claim: self.claimed.as_ref().map(|c| ClaimSummary {
+                player_id: self.players[c.claimer].player_id.clone(),
+                tricks: c.tricks,
+                remaining: c.remaining,
+            }),
+        }
+    }
+
+    /// 依房間的 tie-break 規則在平手的幾方 (`side_of` 把座位對應到隊伍或座位本身) 中判定勝方；
+    /// 攤牌宣告分到的墩也算在內
+    fn break_tie<K: Copy + Eq>(&self, side_of: impl Fn(usize) -> K, tied: &[K]) -> Option<K> {
+        let claim = self.claimed.as_ref();
+        let claimer = claim.map(|c| side_of(c.claimer));
+        let opponents = claimer.and_then(|own| (0..self.players.len()).map(&side_of).find(|&k| k != own));
+        match self.tie_break {
+            TieBreak::Draw => None,
+            TieBreak::LastTrick => {
+                // 宣告全部剩下的墩時最後一墩歸宣告方，否則歸對手
+                let claimed_last = claim.map(|c| if c.tricks == c.remaining { claimer } else { opponents });
+                claimed_last
+                    .into_iter()
+                    .flatten()
+                    .chain(self.history.iter().rev().filter_map(|h| self.seat_of(&h.winner).map(&side_of)))
+                    .find(|side| tied.contains(side))
+            }
+            TieBreak::HighCards => {
+                let high = |cards: &mut dyn Iterator<Item = CardData>| cards.filter(|c| c.rank.0 >= 10).count();
+                let mut counts = vec![0; tied.len()];
+                let mut credit = |side: Option<K>, n: usize| {
+                    if let Some(i) = side.and_then(|k| tied.iter().position(|&t| t == k)) {
+                        counts[i] += n;
+                    }
+                };
+                for trick in &self.history {
+                    let cards = &mut trick.cards.iter().filter_map(|c| CardData::from_protocol_string(c));
+                    credit(self.seat_of(&trick.winner).map(&side_of), high(cards));
+                }
+                // 全拿 (或全讓) 時剩下的大牌都歸拿墩的一方，部分宣告時各方算自己手上的大牌
+                if let Some(c) = claim {
+                    for (idx, cards) in c.cards.iter().enumerate() {
+                        let side = match c.tricks {
+                            t if t == c.remaining => claimer,
+                            0 => opponents,
+                            _ => Some(side_of(idx)),
+                        };
+                        credit(side, high(&mut cards.iter()));
+                    }
+                }
+                let best = *counts.iter().max()?;
+                match counts.iter().filter(|&&n| n == best).count() {
+                    1 => counts.iter().position(|&n| n == best).map(|i| tied[i]),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// 取得所有玩家的 conn_id - 預留供未來擴充
+    #[allow(dead_code)]
+    pub fn all_conn_ids(&self) -> Vec<ConnectionId> {
+        self.players.iter().map(|p| p.conn_id).collect()
+    }
+
+    /// 透過 conn_id 找玩家 index
+    pub fn find_player_idx(&self, conn_id: ConnectionId) -> Option<usize> {
+        self.players.iter().position(|p| p.conn_id == conn_id)
+    }
+}
+
+/// 規則集以 RuleKind 序列化 (快照用)
+mod rules_serde {
+    use super::{RuleKind, RuleSet};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[allow(clippy::borrowed_box)]
+    pub fn serialize<S: Serializer>(rules: &Box<dyn RuleSet>, serializer: S) -> Result<S::Ok, S::Error> {
+        rules.kind().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Box<dyn RuleSet>, D::Error> {
+        Ok(RuleKind::deserialize(deserializer)?.build())
+    }
+}
+
+/// 出牌錯誤
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayError {
+    NotInGame,
+    NotYourTurn,
+    InvalidCard,
+    NotInHand,
+    NotLegal,
+}
+
+/// 傳牌錯誤
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PassError {
+    NotInGame,
+    NotPassing,
+    AlreadyPassed,
+    WrongCount,
+    InvalidCard,
+    NotInHand,
+}
+
+/// 攤牌宣告錯誤
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaimError {
+    NotInGame,
+    /// 不在出牌階段
+    NotPlaying,
+    /// 個人計分的規則 (e.g., Hearts) 不能宣告
+    NotPartnership,
+    /// 已有宣告等待回覆
+    ClaimPending,
+    /// 超過剩下的 tricks
+    TooManyTricks,
+    /// 沒有等待回覆的宣告
+    NoClaim,
+    /// 不是 (還沒回覆的) 對手
+    NotOpponent,
+}
+
+/// 攤牌宣告的回覆結果
+pub enum ClaimOutcome {
+    /// 還有對手沒回覆
+    Pending,
+    /// 對手全部接受，這副牌結束
+    Accepted(ServerMessage), // claim_result_msg
+    /// 有對手拒絕，繼續出牌
+    Rejected(ServerMessage), // claim_result_msg
+}
+
+/// 悔牌錯誤
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UndoError {
+    NotInGame,
+    /// 房間不允許悔牌
+    Disabled,
+    /// 不在出牌階段
+    NotPlaying,
+    /// 已有宣告或悔牌請求等待回覆
+    RequestPending,
+    /// 這副牌還沒出過牌
+    NothingToUndo,
+    /// 要跨過已結算的 trick，但房間只允許收回進行中 trick 的牌
+    CrossesTrick,
+    /// 練習房以外，對手裡沒有可以同意的真人
+    NoHumanOpponent,
+    /// 沒有等待回覆的悔牌請求
+    NoRequest,
+    /// 不是 (還沒回覆的) 對手
+    NotOpponent,
+}
+
+/// 悔牌請求的回覆結果
+#[allow(clippy::large_enum_variant)]
+pub enum UndoOutcome {
+    /// 還有對手沒回覆
+    Pending,
+    /// 對手全部同意，牌已收回
+    Accepted(ServerMessage, ServerMessage), // (undo_result_msg, play_cancel_msg)
+    /// 有對手拒絕
+    Rejected(ServerMessage), // undo_result_msg
+}
+
+/// 傳牌結果
+pub enum PassResult {
+    /// 還有玩家沒選好
+    Waiting,
+    /// 交換完成，開始出牌
+    Exchanged(Vec<(ConnectionId, ServerMessage)>, usize), // (cards_passed_msgs, opening_leader_idx)
+}
+
+/// 出牌結果
+pub enum PlayResult {
+    /// 繼續遊戲，下一位玩家出牌
+    Continue(ServerMessage, usize), // (broadcast_msg, next_player_idx)
+    /// Trick 完成
+    TrickComplete(ServerMessage),
+}
+
+/// 叫牌結果
+pub enum AuctionResult {
+    /// 繼續叫牌
+    Continue(ServerMessage, usize), // (auction_update_msg, next_bidder_idx)
+    /// 叫牌結束，進入出牌階段
+    Contract(ServerMessage, usize), // (auction_update_msg, opening_leader_idx)
+    /// 四家都 PASS，需要重新發牌
+    PassedOut(ServerMessage),
+}
+
+/// Trick 結算結果
+pub enum TrickResolution {
+    /// 下一 trick
+    NextTrick(ServerMessage, usize), // (trick_result_msg, next_player_idx)
+    /// 遊戲結束
+    GameOver(ServerMessage),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::auction::Bid;
+    use super::super::deck::{Rank, Suit};
+    use super::super::rules::{Hearts, trick_winner};
+    use super::super::testing::{self, play_first_legal, play_out};
+
+    fn create_test_engine() -> GameEngine {
+        testing::new_engine(12345)
+    }
+
+    #[test]
+    fn test_deal_cards() {
+        let mut engine = create_test_engine();
+        let messages = engine.deal();
+
+        assert_eq!(messages.len(), 4);
+        for player in &engine.players {
+            assert_eq!(player.hand.len(), 13);
+        }
+    }
+
+    #[test]
+    fn test_legal_moves_first_play() {
+        let mut engine = create_test_engine();
+        engine.deal();
+
+        // 第一個出牌的人可以出任何牌
+        let legal = engine.get_legal_moves(0);
+        assert_eq!(legal.len(), 13);
+    }
+
+    #[test]
+    fn test_follow_suit_rule() {
+        let mut engine = create_test_engine();
+        engine.deal();
+
+        // 模擬 P1 出了一張黑桃
+        let spade_card = engine.players[0].hand.lowest_in(Suit::Spades);
 
         if let Some(card) = spade_card {
             engine.table.push((0, card));
@@ -434,4 +1539,812 @@ mod tests {
             _ => panic!("Expected NextTrick"),
         }
     }
+
+    #[test]
+    fn test_trump_beats_led_suit() {
+        let mut engine = create_test_engine();
+        engine.deal();
+        engine.trump = Some(Suit::Hearts);
+
+        // P3 王吃 (2H)，P4 超吃 (5H)
+        engine.table = vec![
+            (0, CardData::new(Suit::Spades, Rank::ACE)),
+            (1, CardData::new(Suit::Spades, Rank::KING)),
+            (2, CardData::new(Suit::Hearts, Rank::TWO)),
+            (3, CardData::new(Suit::Hearts, Rank::FIVE)),
+        ];
+        engine.phase = GamePhase::TrickComplete;
+
+        match engine.resolve_trick() {
+            TrickResolution::NextTrick(ServerMessage::TrickResult { winner, trump, .. }, winner_idx) => {
+                assert_eq!(winner_idx, 3);
+                assert_eq!(winner, "P4");
+                assert_eq!(trump.as_deref(), Some("H"));
+            }
+            _ => panic!("Expected NextTrick"),
+        }
+    }
+
+    #[test]
+    fn test_history_records_leader_and_plays() {
+        let mut engine = create_test_engine();
+        engine.deal();
+        engine.current_trick = 1;
+
+        // P2 首引 ♦，P4 墊 ♠A 也贏不了
+        engine.table = vec![
+            (1, CardData::new(Suit::Diamonds, Rank::FIVE)),
+            (2, CardData::new(Suit::Diamonds, Rank::KING)),
+            (3, CardData::new(Suit::Spades, Rank::ACE)),
+            (0, CardData::new(Suit::Diamonds, Rank::TWO)),
+        ];
+        engine.phase = GamePhase::TrickComplete;
+        engine.resolve_trick();
+
+        let trick = &engine.history[0];
+        assert_eq!(trick.leader, "P2");
+        assert_eq!(trick.led_suit, "D");
+        assert_eq!(trick.winner, "P3");
+        let plays: Vec<_> = trick.plays.iter().map(|p| format!("{}:{}", p.player_id, p.card)).collect();
+        assert_eq!(plays, ["P2:5D", "P3:KD", "P4:AS", "P1:2D"]);
+        assert_eq!(trick.cards, ["5D", "KD", "AS", "2D"]);
+        assert_eq!((trick.score.human, trick.score.ai), (0, 1));
+
+        // 第二個 trick 出了一張牌時索取歷史
+        let idx = engine.current_player_idx().unwrap();
+        let card = engine.get_legal_moves(idx).first().unwrap();
+        engine.play_card(idx, card);
+        match engine.history_message() {
+            ServerMessage::History { tricks, current_trick, table, score } => {
+                assert_eq!(tricks.len(), 1);
+                assert_eq!(current_trick, 2);
+                assert_eq!(table, vec![TablePlay { player_id: "P3".to_string(), card: card.to_protocol_string() }]);
+                assert_eq!(score.ai, 1);
+            }
+            _ => panic!("Expected History"),
+        }
+    }
+
+    #[test]
+    fn test_seat_states_in_turn_and_result() {
+        let mut engine = create_test_engine();
+        engine.deal();
+        engine.set_connected("P2", false);
+
+        let leader = engine.current_player_idx().unwrap();
+        match engine.your_turn_message(leader) {
+            ServerMessage::YourTurn { seats, .. } => {
+                assert_eq!(seats.len(), 4);
+                assert!(seats.iter().all(|s| s.cards_left == 13 && s.tricks == 0));
+                assert!(seats[leader].to_play);
+                assert_eq!(seats.iter().filter(|s| s.to_play).count(), 1);
+                assert!(!seats[1].connected && seats[0].connected);
+            }
+            _ => panic!("Expected YourTurn"),
+        }
+
+        for _ in 0..4 {
+            let idx = engine.current_player_idx().unwrap();
+            let card = engine.get_legal_moves(idx).first().unwrap();
+            engine.play_card(idx, card);
+        }
+        match engine.resolve_trick() {
+            TrickResolution::NextTrick(ServerMessage::TrickResult { seats, .. }, winner_idx) => {
+                assert_eq!(seats[winner_idx].tricks, 1);
+                assert!(seats[winner_idx].to_play);
+                assert_eq!(seats.iter().map(|s| s.tricks).sum::<u32>(), 1);
+                assert!(seats.iter().all(|s| s.cards_left == 12));
+            }
+            _ => panic!("Expected NextTrick"),
+        }
+
+        // 續連後恢復在線
+        engine.rebind_conn("P2", 42);
+        assert!(engine.seat_states()[1].connected);
+    }
+
+    #[test]
+    fn test_no_trump_discard_cannot_win() {
+        // 無王時，墊出的其他花色即使點數大也不能贏
+        let table = vec![
+            (0, CardData::new(Suit::Clubs, Rank::FOUR)),
+            (1, CardData::new(Suit::Hearts, Rank::ACE)),
+            (2, CardData::new(Suit::Clubs, Rank::THREE)),
+            (3, CardData::new(Suit::Diamonds, Rank::KING)),
+        ];
+        assert_eq!(trick_winner(&table, None), 0);
+        // 指定王牌但沒人出王牌時，仍由領牌花色決定
+        assert_eq!(trick_winner(&table, Some(Suit::Spades)), 0);
+        assert_eq!(trick_winner(&table, Some(Suit::Diamonds)), 3);
+    }
+
+    #[test]
+    fn test_bidding_sets_contract_and_leader() {
+        let mut engine = create_test_engine();
+        engine.bidding = true;
+        engine.dealer_idx = 1;
+        engine.deal();
+
+        assert_eq!(engine.current_bidder_idx(), Some(1));
+        assert!(engine.current_player_idx().is_none());
+
+        // P2 開叫 1S，其餘 PASS
+        let bid = Call::Bid(Bid::from_protocol_string("1S").unwrap());
+        assert_eq!(engine.validate_call(2, bid), Ok(1));
+        assert_eq!(engine.validate_call(1, Call::Pass), Err(AuctionError::NotYourTurn));
+        assert!(matches!(engine.make_call(1, bid), AuctionResult::Continue(_, 2)));
+        assert!(matches!(engine.make_call(2, Call::Pass), AuctionResult::Continue(_, 3)));
+        assert!(matches!(engine.make_call(3, Call::Pass), AuctionResult::Continue(_, 0)));
+
+        match engine.make_call(0, Call::Pass) {
+            AuctionResult::Contract(ServerMessage::AuctionUpdate { contract, declarer, next, .. }, leader) => {
+                assert_eq!(contract.as_deref(), Some("1S"));
+                assert_eq!(declarer.as_deref(), Some("P2"));
+                assert!(next.is_none());
+                // 主打者左手邊第一位對手首引
+                assert_eq!(leader, 2);
+            }
+            _ => panic!("Expected Contract"),
+        }
+        assert_eq!(engine.trump, Some(Suit::Spades));
+        assert_eq!(engine.current_player_idx(), Some(2));
+
+        // P1 主打時，同隊的 P2 不首引
+        let engine = create_contract_engine();
+        assert_eq!(engine.current_player_idx(), Some(2));
+    }
+
+    #[test]
+    fn test_passed_out_redeal() {
+        let mut engine = create_test_engine();
+        engine.bidding = true;
+        engine.deal();
+        let first_hand = engine.players[0].hand;
+
+        for idx in 0..3 {
+            assert!(matches!(engine.make_call(idx, Call::Pass), AuctionResult::Continue(..)));
+        }
+        assert!(matches!(engine.make_call(3, Call::Pass), AuctionResult::PassedOut(_)));
+        assert!(engine.current_turn_idx().is_none());
+
+        engine.reseed(DealSeed { seed: 54321, secret: "ab".repeat(16) });
+        let messages = engine.deal();
+        assert_eq!(messages.len(), 4);
+        assert_ne!(engine.players[0].hand, first_hand);
+        assert_eq!(engine.current_bidder_idx(), Some(0));
+    }
+
+    /// P1 開叫 1S 後其餘 PASS：P1 主打，P2 為夢家，P3 首引
+    fn create_contract_engine() -> GameEngine {
+        let mut engine = create_test_engine();
+        engine.bidding = true;
+        engine.deal();
+        engine.make_call(0, Call::Bid(Bid::from_protocol_string("1S").unwrap()));
+        for idx in [1, 2, 3] {
+            engine.make_call(idx, Call::Pass);
+        }
+        engine
+    }
+
+    #[test]
+    fn test_dummy_revealed_after_opening_lead() {
+        let mut engine = create_contract_engine();
+        assert_eq!(engine.dummy_idx, Some(1));
+        assert_eq!(engine.controller_of(1), 0);
+        assert_eq!(engine.controller_of(2), 2);
+
+        // 首引前不攤牌
+        assert!(engine.reveal_dummy().is_none());
+
+        let lead = engine.get_legal_moves(2).first().unwrap();
+        engine.play_card(2, lead);
+        match engine.reveal_dummy() {
+            Some(ServerMessage::DummyReveal { dummy, declarer, hand }) => {
+                assert_eq!(dummy, "P2");
+                assert_eq!(declarer, "P1");
+                assert_eq!(hand.len(), 13);
+            }
+            _ => panic!("Expected DummyReveal"),
+        }
+        // 只攤一次
+        assert!(engine.reveal_dummy().is_none());
+        assert!(engine.dummy_reveal_message().is_some());
+    }
+
+    #[test]
+    fn test_declarer_plays_for_dummy() {
+        let mut engine = create_contract_engine();
+        let lead = engine.get_legal_moves(2).first().unwrap();
+        engine.play_card(2, lead);
+        let p4_card = engine.get_legal_moves(3).first().unwrap();
+        engine.play_card(3, p4_card);
+        let p1_card = engine.get_legal_moves(0).first().unwrap();
+        engine.play_card(0, p1_card);
+
+        // 輪到夢家 P2：只有主打者 P1 (conn 1) 能出
+        assert_eq!(engine.current_player_idx(), Some(1));
+        let card = engine.get_legal_moves(1).first().unwrap().to_protocol_string();
+        assert_eq!(engine.validate_play(2, &card), Err(PlayError::NotYourTurn));
+        let (seat, _) = engine.validate_play(1, &card).unwrap();
+        assert_eq!(seat, 1);
+
+        match engine.your_turn_message(1) {
+            ServerMessage::YourTurn { seat, .. } => assert_eq!(seat.as_deref(), Some("P2")),
+            _ => panic!("Expected YourTurn"),
+        }
+    }
+
+    #[test]
+    fn test_state_snapshot_for_declarer_and_defender() {
+        let mut engine = create_contract_engine();
+        let lead = engine.get_legal_moves(2).first().unwrap();
+        engine.play_card(2, lead);
+        let p4_card = engine.get_legal_moves(3).first().unwrap();
+        engine.play_card(3, p4_card);
+
+        // 輪到主打者 P1 自己出牌
+        match engine.state_snapshot_message(0) {
+            ServerMessage::StateSnapshot { player_id, hand, legal, seat, trick, table, history, seats, .. } => {
+                assert_eq!(player_id, "P1");
+                assert_eq!(hand.len(), 13);
+                assert_eq!(legal, engine.get_legal_moves(0).to_protocol_strings());
+                assert_eq!(seat, None);
+                assert_eq!(trick, 1);
+                assert_eq!(table.len(), 2);
+                assert!(history.is_empty());
+                assert!(seats[0].to_play);
+            }
+            _ => panic!("Expected StateSnapshot"),
+        }
+        // 防家 P3 不在回合中：沒有合法牌
+        match engine.state_snapshot_message(2) {
+            ServerMessage::StateSnapshot { hand, legal, .. } => {
+                assert_eq!(hand.len(), 12);
+                assert!(legal.is_empty());
+            }
+            _ => panic!("Expected StateSnapshot"),
+        }
+
+        // 輪到夢家 P2：主打者的快照附夢家的合法牌
+        let p1_card = engine.get_legal_moves(0).first().unwrap();
+        engine.play_card(0, p1_card);
+        match engine.state_snapshot_message(0) {
+            ServerMessage::StateSnapshot { legal, seat, .. } => {
+                assert_eq!(legal, engine.get_legal_moves(1).to_protocol_strings());
+                assert_eq!(seat.as_deref(), Some("P2"));
+            }
+            _ => panic!("Expected StateSnapshot"),
+        }
+    }
+
+    #[test]
+    fn test_human_declarer_controls_ai_dummy() {
+        // 單一真人 (P1) 與內建 AI (P3) 同隊
+        let players = vec![
+            (1, "P1".to_string(), Team::Human),
+            (2, "P2".to_string(), Team::Ai),
+            (ConnectionId::MAX, "P3".to_string(), Team::Human),
+            (4, "P4".to_string(), Team::Ai),
+        ];
+        let mut engine = GameEngine::new(12345, players);
+        engine.bidding = true;
+        engine.deal();
+        engine.make_call(0, Call::Bid(Bid::from_protocol_string("2H").unwrap()));
+        for idx in [1, 2, 3] {
+            engine.make_call(idx, Call::Pass);
+        }
+
+        assert_eq!(engine.dummy_idx, Some(2));
+        assert_eq!(engine.current_player_idx(), Some(1));
+        let lead = engine.get_legal_moves(1).first().unwrap();
+        engine.play_card(1, lead);
+
+        // 輪到 AI 夢家時由 P1 的連線出牌
+        let card = engine.get_legal_moves(2).first().unwrap().to_protocol_string();
+        assert_eq!(engine.validate_play(ConnectionId::MAX, &card), Err(PlayError::NotYourTurn));
+        assert_eq!(engine.validate_play(1, &card).map(|(seat, _)| seat), Ok(2));
+    }
+
+    /// 直接填入兩隊各自贏得的 tricks (P1 代表 HUMAN，P3 代表 AI)
+    fn set_tricks(engine: &mut GameEngine, human: u32, ai: u32) {
+        engine.history = (0..human + ai)
+            .map(|i| TrickHistory {
+                trick: i + 1,
+                winner: if i < human { "P1" } else { "P3" }.to_string(),
+                ..TrickHistory::default()
+            })
+            .collect();
+        engine.score = Score { human, ai };
+    }
+
+    #[test]
+    fn test_game_over_carries_duplicate_score() {
+        // P1 主打 1S，HUMAN 隊拿 8 墩 (1S+1)，牌號 1 無身價
+        let mut engine = create_contract_engine();
+        set_tricks(&mut engine, 8, 5);
+        engine.phase = GamePhase::GameOver;
+
+        match engine.game_over_message() {
+            ServerMessage::GameOver { contract, result, duplicate_score, winner, .. } => {
+                assert_eq!(contract.as_deref(), Some("1S"));
+                assert_eq!(result.as_deref(), Some("1S+1"));
+                assert_eq!(duplicate_score, Some(SignedScore { human: 110, ai: -110 }));
+                assert_eq!(winner, Outcome::Human);
+            }
+            _ => panic!("Expected GameOver"),
+        }
+
+        // 倒約時防守方獲勝 (牌號 2: HUMAN 有身價)
+        engine.board = 2;
+        set_tricks(&mut engine, 5, 8);
+        match engine.game_over_message() {
+            ServerMessage::GameOver { result, duplicate_score, winner, .. } => {
+                assert_eq!(result.as_deref(), Some("1S-2"));
+                assert_eq!(duplicate_score, Some(SignedScore { human: -200, ai: 200 }));
+                assert_eq!(winner, Outcome::Ai);
+            }
+            _ => panic!("Expected GameOver"),
+        }
+    }
+
+    #[test]
+    fn test_three_seats_short_hand() {
+        // 3 人、每人 5 張的練習牌局
+        let players = vec![
+            (1, "P1".to_string(), Team::Human),
+            (2, "P2".to_string(), Team::Human),
+            (3, "P3".to_string(), Team::Ai),
+        ];
+        let mut engine = GameEngine::new(7, players);
+        engine.hand_size = Some(5);
+        let messages = engine.deal();
+        assert_eq!(messages.len(), 3);
+        match &messages[0].1 {
+            ServerMessage::Deal { hand, total_tricks, .. } => {
+                assert_eq!(hand.len(), 5);
+                assert_eq!(*total_tricks, 5);
+            }
+            _ => panic!("Expected Deal"),
+        }
+
+        let mut tricks = 0;
+        loop {
+            for _ in 0..3 {
+                let idx = engine.current_player_idx().unwrap();
+                let card = engine.get_legal_moves(idx).first().unwrap();
+                engine.play_card(idx, card);
+            }
+            tricks += 1;
+            if let TrickResolution::GameOver(_) = engine.resolve_trick() {
+                break;
+            }
+        }
+        assert_eq!(tricks, 5);
+        assert_eq!(engine.score.human + engine.score.ai, 5);
+
+        // 超過整副牌平分的張數時以平分為上限
+        engine.hand_size = Some(20);
+        assert_eq!(engine.cards_per_player(), 17);
+    }
+
+    #[test]
+    fn test_forfeit_ends_game() {
+        let mut engine = create_test_engine();
+        engine.deal();
+
+        assert_eq!(engine.record_timeout(0), 1);
+        assert_eq!(engine.record_timeout(0), 2);
+        engine.forfeit(0);
+
+        assert_eq!(engine.phase, GamePhase::GameOver);
+        assert!(engine.current_player_idx().is_none());
+        match engine.game_over_message() {
+            ServerMessage::GameOver { winner, forfeit, .. } => {
+                // P1 (HUMAN) 棄權，AI 獲勝
+                assert_eq!(winner, Outcome::Ai);
+                assert_eq!(forfeit.as_deref(), Some("P1"));
+            }
+            _ => panic!("Expected GameOver"),
+        }
+    }
+
+    #[test]
+    fn test_claim_accepted_ends_game() {
+        let mut engine = create_test_engine();
+        engine.deal();
+        play_first_legal(&mut engine, 4);
+        let human = engine.tricks_for(Team::Human);
+        assert_eq!(engine.tricks_remaining(), 12);
+        assert_eq!(engine.validate_claim(1, 13), Err(ClaimError::TooManyTricks));
+
+        match engine.start_claim(0, 10) {
+            ServerMessage::Claim { player_id, tricks, remaining, hand } => {
+                assert_eq!(player_id, "P1");
+                assert_eq!((tricks, remaining), (10, 12));
+                assert_eq!(hand.len(), 12);
+            }
+            _ => panic!("Expected Claim"),
+        }
+        assert_eq!(engine.validate_claim(2, 1), Err(ClaimError::ClaimPending));
+        // 同伴 P2 不回覆
+        assert_eq!(engine.validate_claim_response(2), Err(ClaimError::NotOpponent));
+        assert_eq!(engine.validate_claim_response(3), Ok(2));
+
+        assert!(matches!(engine.respond_claim(2, true, None), ClaimOutcome::Pending));
+        assert_eq!(engine.claim_responders(), vec![3]);
+        assert_eq!(engine.validate_claim_response(3), Err(ClaimError::NotOpponent));
+        match engine.respond_claim(3, true, None) {
+            ClaimOutcome::Accepted(ServerMessage::ClaimResult { player_id, tricks, accepted, by, .. }) => {
+                assert_eq!(player_id, "P1");
+                assert_eq!(tricks, 10);
+                assert!(accepted);
+                assert_eq!(by.as_deref(), Some("P4"));
+            }
+            _ => panic!("Expected Accepted"),
+        }
+
+        assert!(engine.is_game_over());
+        assert!(engine.claim.is_none());
+        assert_eq!(engine.score.human + engine.score.ai, 13);
+        assert_eq!(engine.tricks_for(Team::Human), human + 10);
+        assert_eq!(engine.tricks_for(Team::Ai), 3 - human);
+        match engine.game_over_message() {
+            ServerMessage::GameOver { claim, winner, .. } => {
+                let expected = ClaimSummary { player_id: "P1".to_string(), tricks: 10, remaining: 12 };
+                assert_eq!(claim, Some(expected));
+                assert_eq!(winner, Outcome::Human);
+            }
+            _ => panic!("Expected GameOver"),
+        }
+    }
+
+    #[test]
+    fn test_claim_rejected_and_concede() {
+        let mut engine = create_test_engine();
+        engine.deal();
+        engine.start_claim(0, 13);
+        match engine.respond_claim(2, false, None) {
+            ClaimOutcome::Rejected(ServerMessage::ClaimResult { accepted, by, .. }) => {
+                assert!(!accepted);
+                assert_eq!(by.as_deref(), Some("P3"));
+            }
+            _ => panic!("Expected Rejected"),
+        }
+        // 拒絕後繼續出牌
+        assert!(engine.claim.is_none());
+        assert!(engine.current_player_idx().is_some());
+        assert_eq!(engine.validate_claim_response(3), Err(ClaimError::NoClaim));
+        assert!(engine.withdraw_claim().is_none());
+
+        // AI 隊的 P3 認輸：13 墩全歸 HUMAN
+        assert_eq!(engine.validate_claim(3, 0), Ok(2));
+        engine.concede(2);
+        assert!(engine.is_game_over());
+        assert_eq!((engine.score.human, engine.score.ai), (13, 0));
+        match engine.game_over_message() {
+            ServerMessage::GameOver { claim, winner, .. } => {
+                assert_eq!(claim.map(|c| (c.player_id, c.tricks)), Some(("P3".to_string(), 0)));
+                assert_eq!(winner, Outcome::Human);
+            }
+            _ => panic!("Expected GameOver"),
+        }
+        assert_eq!(engine.validate_claim(1, 0), Err(ClaimError::NotPlaying));
+
+        // 個人計分不能宣告
+        let hearts = create_hearts_engine(4);
+        assert_eq!(hearts.validate_claim(1, 0), Err(ClaimError::NotPartnership));
+    }
+
+    #[test]
+    fn test_undo_within_trick() {
+        let mut engine = create_test_engine();
+        engine.deal();
+        let leader = engine.current_player_idx().unwrap();
+        let conn = engine.players[leader].conn_id;
+        assert_eq!(engine.validate_undo(conn), Err(UndoError::Disabled));
+        engine.undo_policy = UndoPolicy::Trick;
+        assert_eq!(engine.validate_undo(conn), Err(UndoError::NothingToUndo));
+
+        // 首引者之後再出兩張：三張一起收回
+        play_first_legal(&mut engine, 3);
+        let table = engine.table.clone();
+        assert_eq!(engine.validate_undo(conn), Ok(leader));
+        match engine.start_undo(leader) {
+            ServerMessage::UndoRequest { player_id, cards } => {
+                assert_eq!(player_id, engine.players[leader].player_id);
+                let expected: Vec<_> = table.iter().rev().map(|&(i, c)| engine.table_play(i, c)).collect();
+                assert_eq!(cards, expected);
+            }
+            _ => panic!("Expected UndoRequest"),
+        }
+        assert_eq!(engine.validate_claim(conn, 0), Err(ClaimError::ClaimPending));
+
+        let responders = engine.undo_responders();
+        assert_eq!(responders.len(), 2);
+        assert!(matches!(engine.respond_undo(responders[0], true), UndoOutcome::Pending));
+        match engine.respond_undo(responders[1], true) {
+            UndoOutcome::Accepted(
+                ServerMessage::UndoResult { accepted, .. },
+                ServerMessage::PlayCancel { cards, table, seats, .. },
+            ) => {
+                assert!(accepted);
+                assert_eq!(cards.len(), 3);
+                assert!(table.is_empty());
+                assert!(seats.iter().all(|s| s.cards_left == 13));
+            }
+            _ => panic!("Expected Accepted"),
+        }
+        assert!(engine.undo.is_none());
+        assert!(engine.table.is_empty());
+        assert_eq!(engine.current_player_idx(), Some(leader));
+        assert!(engine.players.iter().all(|p| p.hand.len() == 13));
+    }
+
+    #[test]
+    fn test_undo_across_trick_needs_board_policy() {
+        let mut engine = create_test_engine();
+        engine.deal();
+        let hands: Vec<CardSet> = engine.players.iter().map(|p| p.hand).collect();
+        engine.undo_policy = UndoPolicy::Trick;
+        play_first_legal(&mut engine, 5);
+        assert_eq!(engine.history.len(), 1);
+
+        // P1 最後一張牌在已結算的 trick 1
+        assert!(engine.table.iter().all(|&(idx, _)| idx != 0));
+        let p1_conn = engine.players[0].conn_id;
+        assert_eq!(engine.validate_undo(p1_conn), Err(UndoError::CrossesTrick));
+
+        engine.undo_policy = UndoPolicy::Board;
+        engine.start_undo(0);
+        // 拒絕時不變
+        assert!(matches!(engine.respond_undo(2, false), UndoOutcome::Rejected(_)));
+        assert_eq!(engine.history.len(), 1);
+
+        engine.start_undo(0);
+        engine.respond_undo(2, true);
+        assert!(matches!(engine.respond_undo(3, true), UndoOutcome::Accepted(..)));
+        assert!(engine.history.is_empty());
+        assert_eq!((engine.score.human, engine.score.ai), (0, 0));
+        assert!(engine.players.iter().all(|p| p.tricks == 0));
+        assert_eq!(engine.current_trick, 1);
+        assert!(engine.played.is_empty());
+        assert_eq!(engine.last_trick_winner, None);
+        assert_eq!(engine.current_player_idx(), Some(0));
+        // 收回後 P1 之前出的牌留在桌面，其餘回到手中
+        for (idx, hand) in hands.iter().enumerate() {
+            let on_table = engine.table.iter().any(|&(i, _)| i == idx) as u32;
+            assert_eq!(engine.players[idx].hand.len() + on_table, hand.len());
+        }
+    }
+
+    #[test]
+    fn test_undo_opening_lead_hides_dummy() {
+        let mut engine = create_contract_engine();
+        engine.undo_policy = UndoPolicy::Trick;
+        let lead = engine.get_legal_moves(2).first().unwrap();
+        engine.play_card(2, lead);
+        assert!(engine.reveal_dummy().is_some());
+
+        engine.start_undo(2);
+        assert_eq!(engine.undo_responders(), vec![0, 1]);
+        engine.respond_undo(0, true);
+        assert!(matches!(engine.respond_undo(1, true), UndoOutcome::Accepted(..)));
+        assert!(!engine.dummy_revealed);
+        assert!(engine.dummy_reveal_message().is_none());
+
+        // 再次首引後重新攤牌
+        engine.play_card(2, lead);
+        assert!(engine.reveal_dummy().is_some());
+    }
+
+    #[test]
+    fn test_hearts_undo_asks_every_other_seat() {
+        let mut engine = create_hearts_engine(4);
+        engine.undo_policy = UndoPolicy::Trick;
+        play_first_legal(&mut engine, 2);
+        let requester = engine.table[1].0;
+        assert_eq!(engine.validate_undo(engine.players[requester].conn_id), Ok(requester));
+        engine.start_undo(requester);
+        let responders = engine.undo_responders();
+        assert_eq!(responders.len(), 3);
+        assert!(!responders.contains(&requester));
+        assert_eq!(engine.undo_opponents(requester), responders);
+    }
+
+    #[test]
+    fn test_tied_game_uses_tie_break() {
+        // 2:2 平手；HUMAN 的 trick 有 A, K, 10，AI 的有 Q, J；最後一個 trick 由 AI 拿下
+        let mut engine = create_test_engine();
+        let tricks = [
+            ("P1", ["AS", "2S", "3S", "4S"]),
+            ("P2", ["KH", "10H", "2H", "3H"]),
+            ("P3", ["QD", "2D", "3D", "4D"]),
+            ("P4", ["JC", "2C", "3C", "4C"]),
+        ];
+        engine.history = tricks
+            .iter()
+            .enumerate()
+            .map(|(i, (winner, cards))| TrickHistory {
+                trick: i as u32 + 1,
+                winner: winner.to_string(),
+                cards: cards.iter().map(|c| c.to_string()).collect(),
+                ..TrickHistory::default()
+            })
+            .collect();
+        engine.score = Score { human: 2, ai: 2 };
+        engine.phase = GamePhase::GameOver;
+
+        let outcome = |engine: &GameEngine| match engine.game_over_message() {
+            ServerMessage::GameOver { winner, tie_break, .. } => (winner, tie_break),
+            _ => panic!("Expected GameOver"),
+        };
+        assert_eq!(outcome(&engine), (Outcome::Draw, None));
+        engine.tie_break = TieBreak::LastTrick;
+        assert_eq!(outcome(&engine), (Outcome::Ai, Some("LAST_TRICK".to_string())));
+        engine.tie_break = TieBreak::HighCards;
+        assert_eq!(outcome(&engine), (Outcome::Human, Some("HIGH_CARDS".to_string())));
+
+        // 大牌張數也相同時仍判和
+        engine.history[0].cards = vec!["2S".into(), "3S".into(), "4S".into(), "5S".into()];
+        assert_eq!(outcome(&engine), (Outcome::Draw, None));
+        assert_eq!(TieBreak::from_str("last-trick"), Some(TieBreak::LastTrick));
+    }
+
+    #[test]
+    fn test_tie_break_counts_claimed_tricks() {
+        // 各拿 1 墩後 P1 宣告剩下 2 墩中的 1 墩：P1 手上 AS KS，P3 手上 QD
+        let mut engine = create_test_engine();
+        engine.history = vec![
+            TrickHistory { trick: 1, winner: "P1".into(), ..TrickHistory::default() },
+            TrickHistory { trick: 2, winner: "P3".into(), ..TrickHistory::default() },
+        ];
+        let card = |c: &str| CardData::from_protocol_string(c).unwrap();
+        let mut cards = vec![CardSet::EMPTY; 4];
+        cards[0] = [card("AS"), card("KS")].into_iter().collect();
+        cards[2] = CardSet::single(card("QD"));
+        engine.claimed = Some(Claim { claimer: 0, tricks: 1, remaining: 2, accepted: vec![2, 3], cards });
+        engine.score = Score { human: 2, ai: 2 };
+        engine.phase = GamePhase::GameOver;
+
+        let winner = |engine: &GameEngine| match engine.game_over_message() {
+            ServerMessage::GameOver { winner, .. } => winner,
+            _ => panic!("Expected GameOver"),
+        };
+        // 只宣告部分的墩時最後一墩歸對手；大牌各算自己手上的
+        engine.tie_break = TieBreak::LastTrick;
+        assert_eq!(winner(&engine), Outcome::Ai);
+        engine.tie_break = TieBreak::HighCards;
+        assert_eq!(winner(&engine), Outcome::Human);
+
+        // 全部讓給對手 (1:3) 不會平手；改成 P1 全拿且分數相同時最後一墩歸宣告方
+        let claim = engine.claimed.as_mut().unwrap();
+        claim.tricks = 0;
+        engine.score = Score { human: 1, ai: 3 };
+        assert_eq!(winner(&engine), Outcome::Ai);
+        engine.claimed.as_mut().unwrap().tricks = 2;
+        engine.history[0].winner = "P3".into();
+        engine.score = Score { human: 2, ai: 2 };
+        engine.tie_break = TieBreak::LastTrick;
+        assert_eq!(winner(&engine), Outcome::Human);
+    }
+
+    #[test]
+    fn test_hearts_reports_winning_seat() {
+        let mut engine = create_hearts_engine(4);
+        engine.phase = GamePhase::GameOver;
+        engine.history = vec![TrickHistory { trick: 1, winner: "P3".into(), ..TrickHistory::default() }];
+        let result = |engine: &GameEngine| match engine.game_over_message() {
+            ServerMessage::GameOver { winner, winning_seat, tie_break, .. } => (winner, winning_seat, tie_break),
+            _ => panic!("Expected GameOver"),
+        };
+
+        engine.player_points = vec![5, 0, 13, 8];
+        assert_eq!(result(&engine), (Outcome::Human, Some("P2".to_string()), None));
+
+        // P3 與 P4 同分最低：判和，或由拿到最後一墩的 P3 獲勝
+        engine.player_points = vec![13, 13, 0, 0];
+        assert_eq!(result(&engine), (Outcome::Draw, None, None));
+        engine.tie_break = TieBreak::LastTrick;
+        assert_eq!(result(&engine), (Outcome::Ai, Some("P3".to_string()), Some("LAST_TRICK".to_string())));
+
+        // 棄權者不會獲勝
+        engine.player_points = vec![0, 18, 4, 4];
+        engine.forfeit(0);
+        assert_eq!(result(&engine), (Outcome::Ai, Some("P3".to_string()), Some("LAST_TRICK".to_string())));
+    }
+
+    #[test]
+    fn test_rebind_conn() {
+        let mut engine = create_test_engine();
+        engine.deal();
+
+        assert!(engine.rebind_conn("P1", 42));
+        assert_eq!(engine.find_player_idx(42), Some(0));
+        assert!(engine.find_player_idx(1).is_none());
+        assert!(!engine.rebind_conn("P9", 43));
+
+        match engine.deal_message(0) {
+            ServerMessage::Deal { hand, .. } => assert_eq!(hand.len(), 13),
+            _ => panic!("Expected Deal"),
+        }
+    }
+
+    fn create_hearts_engine(board: u32) -> GameEngine {
+        let mut engine = create_test_engine();
+        engine.rules = RuleKind::Hearts.build();
+        engine.board = board;
+        engine.deal();
+        engine
+    }
+
+    #[test]
+    fn test_hearts_pass_exchange() {
+        let mut engine = create_hearts_engine(1);
+        assert_eq!(engine.phase, GamePhase::Passing);
+        assert_eq!(engine.pass_direction, Some(PassDirection::Left));
+        assert!(engine.current_turn_idx().is_none());
+
+        let p1_hand: Vec<Card> = engine.players[0].hand.iter().map(|c| c.to_protocol_string()).collect();
+        assert_eq!(engine.validate_pass(1, &p1_hand[..2]), Err(PassError::WrongCount));
+        let dup = vec![p1_hand[0].clone(), p1_hand[0].clone(), p1_hand[1].clone()];
+        assert_eq!(engine.validate_pass(1, &dup), Err(PassError::NotInHand));
+
+        let mut passed = Vec::new();
+        for idx in 0..4 {
+            let cards: Vec<Card> = engine.players[idx].hand.to_protocol_strings()[..3].to_vec();
+            let (seat, parsed) = engine.validate_pass(engine.players[idx].conn_id, &cards).unwrap();
+            assert_eq!(seat, idx);
+            passed.push(parsed);
+            let result = engine.submit_pass(idx, parsed);
+            if idx < 3 {
+                assert!(matches!(result, PassResult::Waiting));
+                assert_eq!(engine.validate_pass(engine.players[idx].conn_id, &cards), Err(PassError::AlreadyPassed));
+            } else {
+                match result {
+                    PassResult::Exchanged(messages, leader) => {
+                        assert_eq!(messages.len(), 4);
+                        assert!(engine.players[leader].hand.contains(Hearts::OPENING_CARD));
+                        assert_eq!(engine.current_player_idx(), Some(leader));
+                    }
+                    PassResult::Waiting => panic!("Expected exchange"),
+                }
+            }
+        }
+
+        // 向左傳：P1 的牌到了 P2 手上
+        for player in &engine.players {
+            assert_eq!(player.hand.len(), 13);
+        }
+        assert!(passed[0].iter().all(|c| engine.players[1].hand.contains(c)));
+        assert!(passed[3].iter().all(|c| engine.players[0].hand.contains(c)));
+    }
+
+    #[test]
+    fn test_hearts_hold_hand_plays_out() {
+        // 第 4 副不傳牌，直接由 ♣2 持有者首引
+        let mut engine = create_hearts_engine(4);
+        assert!(engine.pass_direction.is_none());
+        let leader = engine.current_player_idx().unwrap();
+        assert_eq!(engine.get_legal_moves(leader), CardSet::single(Hearts::OPENING_CARD));
+
+        play_out(&mut engine);
+        assert!(engine.is_game_over());
+        assert_eq!(engine.player_points.iter().sum::<u32>(), Hearts::TOTAL_POINTS);
+
+        match engine.game_over_message() {
+            ServerMessage::GameOver { player_scores: Some(scores), .. } => {
+                assert_eq!(scores.len(), 4);
+                assert!(scores.iter().all(|s| s.points <= Hearts::TOTAL_POINTS));
+            }
+            _ => panic!("Expected GameOver with player scores"),
+        }
+    }
+
+    #[test]
+    fn test_reset_timeouts() {
+        let mut engine = create_test_engine();
+        engine.record_timeout(1);
+        engine.reset_timeouts(1);
+        assert_eq!(engine.record_timeout(1), 1);
+    }
 }
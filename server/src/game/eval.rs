@@ -0,0 +1,240 @@
+//! 手牌評估 (Hand Evaluation)
+//!
+//! 以一手牌 (`&[CardData]`) 計算大牌點、牌型點、牌型、是否平均、快速贏墩與輸墩數 (LTC)，
+//! 供 AI 叫牌、條件發牌與管理介面共用。
+
+use super::deck::{CardData, Rank, Suit};
+use std::fmt;
+
+/// 大牌點 (A=4, K=3, Q=2, J=1)
+pub fn high_card_points(hand: &[CardData]) -> u32 {
+    hand.iter()
+        .map(|c| match c.rank {
+            Rank::ACE => 4,
+            Rank::KING => 3,
+            Rank::QUEEN => 2,
+            Rank::JACK => 1,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// 花色張數
+pub fn suit_length(hand: &[CardData], suit: Suit) -> u32 {
+    hand.iter().filter(|c| c.suit == suit).count() as u32
+}
+
+/// 各花色張數
+pub fn shape(hand: &[CardData]) -> Shape {
+    Shape(Suit::all().map(|suit| suit_length(hand, suit)))
+}
+
+/// 平均牌型 (沒有缺門、單張，最多一個雙張：4-3-3-3、4-4-3-2、5-3-3-2)
+pub fn is_balanced(hand: &[CardData]) -> bool {
+    shape(hand).is_balanced()
+}
+
+/// 牌型點 (缺門 3、單張 2、雙張 1)
+pub fn distribution_points(hand: &[CardData]) -> u32 {
+    shape(hand)
+        .0
+        .iter()
+        .map(|&len| match len {
+            0 => 3,
+            1 => 2,
+            2 => 1,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// 一個花色中持有的 A、K、Q
+fn honors(hand: &[CardData], suit: Suit) -> (bool, bool, bool) {
+    let has = |rank: Rank| hand.iter().any(|c| c.suit == suit && c.rank == rank);
+    (has(Rank::ACE), has(Rank::KING), has(Rank::QUEEN))
+}
+
+/// 快速贏墩 (每個花色 AK=2、AQ=1.5、A=1、KQ=1、Kx=0.5)
+pub fn quick_tricks(hand: &[CardData]) -> f32 {
+    Suit::all()
+        .iter()
+        .map(|&suit| match (honors(hand, suit), suit_length(hand, suit)) {
+            ((true, true, _), _) => 2.0,
+            ((true, false, true), _) => 1.5,
+            ((true, false, false), _) => 1.0,
+            ((false, true, true), _) => 1.0,
+            ((false, true, false), len) if len >= 2 => 0.5,
+            _ => 0.0,
+        })
+        .sum()
+}
+
+/// 輸墩數 (Losing Trick Count)：每個花色只看前三張，
+/// 單張缺 A、雙張缺 A 或 K、三張以上缺 A、K 或 Q 各算一個輸墩
+pub fn losing_trick_count(hand: &[CardData]) -> u32 {
+    Suit::all()
+        .iter()
+        .map(|&suit| {
+            let (ace, king, queen) = honors(hand, suit);
+            let len = suit_length(hand, suit);
+            let winners = match len {
+                0 => 0,
+                1 => ace as u32,
+                2 => ace as u32 + king as u32,
+                _ => ace as u32 + king as u32 + queen as u32,
+            };
+            len.min(3) - winners
+        })
+        .sum()
+}
+
+/// 牌型 (依 S, H, D, C 的張數)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shape(pub [u32; 4]);
+
+impl Shape {
+    /// 由長到短排列的張數 (e.g., [5, 3, 3, 2])
+    pub fn pattern(&self) -> [u32; 4] {
+        let mut pattern = self.0;
+        pattern.sort_by(|a, b| b.cmp(a));
+        pattern
+    }
+
+    pub fn is_balanced(&self) -> bool {
+        self.0.iter().all(|&l| l >= 2) && self.0.iter().filter(|&&l| l == 2).count() <= 1
+    }
+}
+
+/// 以 "5-3-3-2" 表示
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d] = self.pattern();
+        write!(f, "{}-{}-{}-{}", a, b, c, d)
+    }
+}
+
+/// 一手牌的完整評估
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HandEvaluation {
+    pub hcp: u32,
+    pub distribution_points: u32,
+    pub shape: Shape,
+    pub balanced: bool,
+    pub quick_tricks: f32,
+    pub losers: u32,
+}
+
+impl HandEvaluation {
+    pub fn of(hand: &[CardData]) -> Self {
+        let shape = shape(hand);
+        Self {
+            hcp: high_card_points(hand),
+            distribution_points: distribution_points(hand),
+            shape,
+            balanced: shape.is_balanced(),
+            quick_tricks: quick_tricks(hand),
+            losers: losing_trick_count(hand),
+        }
+    }
+}
+
+/// 管理介面用的一行摘要 (e.g., "15 HCP +1 dist, 5-3-3-2 bal, QT 2.5, LTC 7")
+impl fmt::Display for HandEvaluation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} HCP +{} dist, {} {}, QT {}, LTC {}",
+            self.hcp,
+            self.distribution_points,
+            self.shape,
+            if self.balanced { "bal" } else { "unbal" },
+            self.quick_tricks,
+            self.losers
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 以 PBN 寫法 (S.H.D.C) 建立一手牌
+    fn hand(pbn: &str) -> Vec<CardData> {
+        Suit::all()
+            .into_iter()
+            .zip(pbn.split('.'))
+            .flat_map(|(suit, ranks)| {
+                ranks
+                    .chars()
+                    .map(move |c| CardData::new(suit, Rank::from_str(&c.to_string()).unwrap()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_balanced_19_count() {
+        let h = hand("AKQ2.KJ3.Q54.A32");
+        let eval = HandEvaluation::of(&h);
+        assert_eq!(eval.hcp, 19);
+        assert_eq!(eval.distribution_points, 0);
+        assert_eq!(eval.shape, Shape([4, 3, 3, 3]));
+        assert!(eval.balanced);
+        assert_eq!(eval.quick_tricks, 3.5);
+        assert_eq!(eval.losers, 6);
+        assert_eq!(eval.to_string(), "19 HCP +0 dist, 4-3-3-3 bal, QT 3.5, LTC 6");
+    }
+
+    #[test]
+    fn test_long_suit_hand() {
+        let h = hand("AKJ9876.5.KQ4.32");
+        assert_eq!(high_card_points(&h), 13);
+        assert_eq!(shape(&h).to_string(), "7-3-2-1");
+        assert_eq!(shape(&h).0, [7, 1, 3, 2]);
+        assert!(!is_balanced(&h));
+        assert_eq!(distribution_points(&h), 3);
+        assert_eq!(quick_tricks(&h), 3.0);
+        assert_eq!(losing_trick_count(&h), 5);
+    }
+
+    #[test]
+    fn test_two_suiter_with_void() {
+        let h = hand("AQ5432.KQJ98.32.");
+        assert_eq!(high_card_points(&h), 12);
+        assert_eq!(shape(&h).pattern(), [6, 5, 2, 0]);
+        assert_eq!(suit_length(&h, Suit::Clubs), 0);
+        assert_eq!(distribution_points(&h), 4);
+        assert_eq!(quick_tricks(&h), 2.5);
+        assert_eq!(losing_trick_count(&h), 4);
+    }
+
+    #[test]
+    fn test_short_honors() {
+        // AQ 雙張 1.5 快速贏墩 1 輸墩；Kx 0.5；單張 K 沒有快速贏墩但算 1 輸墩
+        let h = hand("AQ.K5.K.J9876543");
+        assert_eq!(high_card_points(&h), 13);
+        assert_eq!(quick_tricks(&h), 2.0);
+        assert_eq!(losing_trick_count(&h), 6);
+        let h = hand("Q2.K.Q.AKJ987654");
+        assert_eq!(quick_tricks(&h), 2.0);
+        assert_eq!(losing_trick_count(&h), 5);
+    }
+
+    #[test]
+    fn test_yarborough() {
+        let h = hand("5432.5432.5432.2");
+        let eval = HandEvaluation::of(&h);
+        assert_eq!(eval.hcp, 0);
+        assert_eq!(eval.quick_tricks, 0.0);
+        assert_eq!(eval.losers, 10);
+        assert!(!eval.balanced);
+    }
+
+    #[test]
+    fn test_balanced_patterns() {
+        assert!(Shape([5, 3, 3, 2]).is_balanced());
+        assert!(Shape([3, 4, 2, 4]).is_balanced());
+        assert!(!Shape([4, 4, 4, 1]).is_balanced());
+        assert!(!Shape([5, 4, 2, 2]).is_balanced());
+        assert!(!Shape([6, 3, 2, 2]).is_balanced());
+    }
+}
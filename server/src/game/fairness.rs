@@ -0,0 +1,240 @@
+//! 發牌公平性 (Commit–Reveal)
+//!
+//! 開局時只公布 `SHA-256("<seed>:<secret>")` 的承諾值，seed 與每副牌的
+//! server secret 到 GAME_OVER 才揭露；Client 可驗證承諾值並重建整副牌
+//! (見 clients/common/fairness.py)，確認發牌沒有在開局後被更換。
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// secret 的位元組數 (hex 後 32 字元)
+const SECRET_BYTES: usize = 16;
+
+/// 一副牌的 seed 與 server secret
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DealSeed {
+    pub seed: u64,
+    /// 小寫 hex 字串
+    pub secret: String,
+}
+
+impl DealSeed {
+    /// 以作業系統的隨機來源產生新的 seed 與 secret
+    pub fn generate() -> Self {
+        let mut seed = [0u8; 8];
+        random_bytes(&mut seed);
+        Self {
+            seed: u64::from_le_bytes(seed),
+            secret: random_hex(SECRET_BYTES),
+        }
+    }
+
+    /// 承諾值：`SHA-256("<seed>:<secret>")` 的小寫 hex
+    pub fn commitment(&self) -> String {
+        to_hex(&sha256(format!("{}:{}", self.seed, self.secret).as_bytes()))
+    }
+}
+
+/// n 個隨機位元組的小寫 hex (續連 token 等)
+pub(crate) fn random_hex(n: usize) -> String {
+    let mut bytes = vec![0u8; n];
+    random_bytes(&mut bytes);
+    to_hex(&bytes)
+}
+
+/// 作業系統的隨機來源
+const RANDOM_SOURCE: &str = "/dev/urandom";
+
+/// 確認讀得到作業系統的隨機來源 (啟動時檢查，讀不到就拒絕啟動)
+pub fn check_random_source() -> io::Result<()> {
+    read_random(&mut [0u8; 8])
+}
+
+fn read_random(buf: &mut [u8]) -> io::Result<()> {
+    std::fs::File::open(RANDOM_SOURCE)?.read_exact(buf)
+}
+
+/// 填入密碼學強度的隨機位元組；seed 與續連 token 都靠它，讀不到隨機來源時直接 panic，
+/// 不改用非密碼學強度的替代品
+fn random_bytes(buf: &mut [u8]) {
+    if let Err(e) = read_random(buf) {
+        panic!("cannot read {}: {}", RANDOM_SOURCE, e);
+    }
+}
+
+/// 位元組轉小寫 hex
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SHA-256 (FIPS 180-4)，不依賴外部 crate
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+        0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+        0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+        0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+        0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+        0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+        0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+    ];
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+
+    // 補位: 0x80、0x00...、原始長度 (bits, big-endian)
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64).wrapping_mul(8)).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    for (i, word) in h.iter().enumerate() {
+        digest[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::deck::{CardData, Deck};
+
+    impl DealSeed {
+        /// 揭露的 seed / secret 是否符合承諾值 (Client 端驗證步驟的對照)
+        fn verify(&self, commitment: &str) -> bool {
+            self.commitment().eq_ignore_ascii_case(commitment)
+        }
+    }
+
+    /// 以 seed 重建發牌結果 (與 GameEngine::deal 相同，每手依花色、點數排序)
+    fn recompute_deal(seed: u64, num_players: usize, cards_per_player: usize) -> Vec<Vec<CardData>> {
+        let mut deck = Deck::new();
+        deck.shuffle(seed);
+        let mut hands = deck.deal(num_players, cards_per_player);
+        for hand in &mut hands {
+            hand.sort_by_key(|c| (c.suit as u8, c.rank.0));
+        }
+        hands
+    }
+
+    /// 驗證揭露的 seed 符合承諾值，且重建出的第 seat 手牌與收到的 DEAL 相同
+    /// (Python 版本見 clients/common/fairness.py)
+    fn verify_deal(
+        commitment: &str,
+        revealed: &DealSeed,
+        seat: usize,
+        hand: &[CardData],
+        num_players: usize,
+        cards_per_player: usize,
+    ) -> bool {
+        if !revealed.verify(commitment) {
+            return false;
+        }
+        let hands = recompute_deal(revealed.seed, num_players, cards_per_player);
+        let mut received = hand.to_vec();
+        received.sort_by_key(|c| (c.suit as u8, c.rank.0));
+        hands.get(seat).is_some_and(|h| *h == received)
+    }
+
+    fn fixed_seed() -> DealSeed {
+        DealSeed {
+            seed: 42,
+            secret: "000102030405060708090a0b0c0d0e0f".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sha256_known_vectors() {
+        assert_eq!(
+            to_hex(&sha256(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            to_hex(&sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // 跨越兩個 block
+        assert_eq!(
+            to_hex(&sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn test_commitment_and_verify() {
+        let deal_seed = fixed_seed();
+        let commitment = deal_seed.commitment();
+        assert_eq!(commitment, "a8a20deef2917c1aae27edf566dd48eb919390678c37ac5d7353d1a28c0a7405");
+        assert!(deal_seed.verify(&commitment.to_uppercase()));
+
+        let tampered = DealSeed { seed: 43, ..deal_seed };
+        assert!(!tampered.verify(&commitment));
+    }
+
+    #[test]
+    fn test_generate_is_unpredictable() {
+        assert!(check_random_source().is_ok());
+        let a = DealSeed::generate();
+        let b = DealSeed::generate();
+        assert_eq!(a.secret.len(), SECRET_BYTES * 2);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_verify_deal() {
+        let deal_seed = fixed_seed();
+        let commitment = deal_seed.commitment();
+        let hands = recompute_deal(deal_seed.seed, 4, 13);
+        assert_eq!(hands.iter().map(|h| h.len()).sum::<usize>(), 52);
+
+        // 與 clients/common/fairness.py 的測試向量相同
+        let seat0: Vec<String> = hands[0].iter().map(|c| c.to_protocol_string()).collect();
+        assert_eq!(seat0.join(" "), "5S 8S 3H 5H 8H 9H JH QH 3D 5D 6D KD 3C");
+
+        let mut received = hands[2].clone();
+        received.reverse();
+        assert!(verify_deal(&commitment, &deal_seed, 2, &received, 4, 13));
+        assert!(!verify_deal(&commitment, &deal_seed, 1, &received, 4, 13));
+
+        let other = DealSeed { seed: 7, ..deal_seed };
+        assert!(!verify_deal(&commitment, &other, 2, &received, 4, 13));
+    }
+}
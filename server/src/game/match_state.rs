@@ -0,0 +1,252 @@
+//! 多副牌比賽 (Match)
+//!
+//! 一個房間連續進行 N 副牌：每副牌結束後輪換莊家、換新的 seed，
+//! 並累計比賽分數 (叫牌模式為複式分數，個人計分規則為負的罰分，否則為墩數)。
+
+use super::engine::GameEngine;
+use crate::protocol::{BoardResult, Outcome, PlayerScore, ServerMessage, SignedScore, Team};
+use serde::{Deserialize, Serialize};
+
+/// 比賽進度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchState {
+    /// 總副數
+    pub total_boards: u32,
+    /// 已完成的每副牌結果
+    pub results: Vec<BoardResult>,
+    /// 累計比賽分數
+    pub score: SignedScore,
+    /// 因棄權提前結束
+    pub abandoned: bool,
+    /// 累計個人分數 (非搭檔制規則)
+    pub player_totals: Option<Vec<PlayerScore>>,
+    /// 第一副的牌號 (從預設牌組中段開始時大於 1)
+    #[serde(default = "default_first_board")]
+    pub first_board: u32,
+}
+
+/// 比賽預設從第 1 副開始 (`RoomConfig` 也用這個預設值)
+pub(crate) fn default_first_board() -> u32 {
+    1
+}
+
+impl MatchState {
+    pub fn new(total_boards: u32) -> Self {
+        Self {
+            total_boards: total_boards.max(1),
+            results: Vec::new(),
+            score: SignedScore::default(),
+            abandoned: false,
+            player_totals: None,
+            first_board: default_first_board(),
+        }
+    }
+
+    /// 目前進行中 (或下一副) 的牌號 (1-based)
+    pub fn current_board(&self) -> u32 {
+        self.first_board + self.results.len() as u32
+    }
+
+    /// 牌號對應的莊家 index (依座位輪流)
+    pub fn dealer_for(board: u32, num_players: usize) -> usize {
+        (board.max(1) as usize - 1) % num_players
+    }
+
+    /// 比賽是否結束
+    pub fn is_over(&self) -> bool {
+        self.abandoned || self.results.len() as u32 >= self.total_boards
+    }
+
+    /// 記錄一副已結束的牌，回傳該副結果
+    pub fn record(&mut self, engine: &GameEngine) -> &BoardResult {
+        let hand_score = engine.hand_score();
+        let player_scores = engine.player_scores();
+        let points = match (&hand_score, &player_scores) {
+            (Some(hs), _) => SignedScore {
+                human: hs.score_for(Team::Human),
+                ai: hs.score_for(Team::Ai),
+            },
+            // 個人計分：隊伍分數為隊員罰分總和取負
+            (None, Some(scores)) => {
+                let penalty = |team: Team| {
+                    scores
+                        .iter()
+                        .zip(&engine.players)
+                        .filter(|(_, p)| p.team == team)
+                        .map(|(s, _)| s.points as i32)
+                        .sum::<i32>()
+                };
+                SignedScore {
+                    human: -penalty(Team::Human),
+                    ai: -penalty(Team::Ai),
+                }
+            }
+            (None, None) => SignedScore {
+                human: engine.score.human as i32,
+                ai: engine.score.ai as i32,
+            },
+        };
+
+        if let Some(scores) = &player_scores {
+            let totals = self.player_totals.get_or_insert_with(|| {
+                scores
+                    .iter()
+                    .map(|s| PlayerScore { player_id: s.player_id.clone(), taken: 0, points: 0 })
+                    .collect()
+            });
+            for (total, score) in totals.iter_mut().zip(scores) {
+                total.taken += score.taken;
+                total.points += score.points;
+            }
+        }
+
+        self.score.human += points.human;
+        self.score.ai += points.ai;
+        if engine.forfeited_by.is_some() {
+            self.abandoned = true;
+        }
+
+        self.results.push(BoardResult {
+            board: engine.board,
+            dealer: engine.players[engine.dealer_idx].player_id.clone(),
+            contract: hand_score.as_ref().map(|hs| hs.contract.to_string()),
+            result: hand_score.as_ref().map(|hs| hs.result.clone()),
+            tricks: engine.score.clone(),
+            score: points,
+            player_scores,
+        });
+        self.results.last().unwrap()
+    }
+
+    /// 比賽勝負 (累計分數相同時判和)
+    pub fn winner(&self) -> Outcome {
+        if self.score.human > self.score.ai {
+            Outcome::Human
+        } else if self.score.ai > self.score.human {
+            Outcome::Ai
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// 產生 MATCH_STATUS 訊息 (兩副牌之間)
+    pub fn status_message(&self, next_dealer: &str) -> ServerMessage {
+        ServerMessage::MatchStatus {
+            board: self.results.last().map_or(0, |r| r.board),
+            total_boards: self.total_boards,
+            last: self.results.last().cloned(),
+            next_dealer: next_dealer.to_string(),
+            score: self.score,
+            player_scores: self.player_totals.clone(),
+        }
+    }
+
+    /// 產生 MATCH_OVER 訊息
+    pub fn over_message(&self) -> ServerMessage {
+        ServerMessage::MatchOver {
+            boards: self.results.clone(),
+            score: self.score,
+            winner: self.winner(),
+            player_scores: self.player_totals.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::testing;
+    use crate::protocol::Score;
+
+    fn finished_engine(board: u32, human: u32, ai: u32) -> GameEngine {
+        let mut engine = testing::new_engine(board as u64);
+        engine.board = board;
+        engine.dealer_idx = MatchState::dealer_for(board, 4);
+        engine.deal();
+        engine.score = Score { human, ai };
+        engine
+    }
+
+    #[test]
+    fn test_dealer_rotation() {
+        assert_eq!(MatchState::dealer_for(1, 4), 0);
+        assert_eq!(MatchState::dealer_for(2, 4), 1);
+        assert_eq!(MatchState::dealer_for(5, 4), 0);
+    }
+
+    #[test]
+    fn test_match_accumulates_boards() {
+        let mut state = MatchState::new(2);
+        assert_eq!(state.current_board(), 1);
+
+        let result = state.record(&finished_engine(1, 8, 5));
+        assert_eq!(result.dealer, "P1");
+        assert!(!state.is_over());
+        assert_eq!(state.current_board(), 2);
+
+        let result = state.record(&finished_engine(2, 4, 9));
+        assert_eq!(result.dealer, "P2");
+        assert!(state.is_over());
+        assert_eq!(state.score, SignedScore { human: 12, ai: 14 });
+
+        match state.over_message() {
+            ServerMessage::MatchOver { boards, winner, .. } => {
+                assert_eq!(boards.len(), 2);
+                assert_eq!(winner, Outcome::Ai);
+            }
+            _ => panic!("Expected MatchOver"),
+        }
+    }
+
+    #[test]
+    fn test_tied_match_is_a_draw() {
+        let mut state = MatchState::new(2);
+        state.record(&finished_engine(1, 6, 7));
+        state.record(&finished_engine(2, 7, 6));
+        match state.over_message() {
+            ServerMessage::MatchOver { winner, .. } => assert_eq!(winner, Outcome::Draw),
+            _ => panic!("Expected MatchOver"),
+        }
+    }
+
+    #[test]
+    fn test_match_from_later_board() {
+        let mut state = MatchState::new(2);
+        state.first_board = 5;
+        assert_eq!(state.current_board(), 5);
+
+        state.record(&finished_engine(5, 7, 6));
+        assert_eq!(state.current_board(), 6);
+        assert!(!state.is_over());
+        match state.status_message("P2") {
+            ServerMessage::MatchStatus { board, .. } => assert_eq!(board, 5),
+            _ => panic!("Expected MatchStatus"),
+        }
+    }
+
+    #[test]
+    fn test_hearts_player_totals() {
+        let mut state = MatchState::new(2);
+        for board in 1..=2 {
+            let mut engine = finished_engine(board, 0, 0);
+            engine.rules = crate::game::RuleKind::Hearts.build();
+            engine.player_points = vec![0, 26, 0, 0];
+            state.record(&engine);
+        }
+
+        // 兩副都由 P2 全收 (shooting the moon)
+        let totals = state.player_totals.clone().unwrap();
+        assert_eq!(totals[1], PlayerScore { player_id: "P2".to_string(), taken: 52, points: 0 });
+        assert_eq!(totals[0].points, 52);
+        assert_eq!(state.score, SignedScore { human: -52, ai: -104 });
+    }
+
+    #[test]
+    fn test_forfeit_ends_match() {
+        let mut state = MatchState::new(4);
+        let mut engine = finished_engine(1, 2, 1);
+        engine.forfeit(0);
+        state.record(&engine);
+        assert!(state.is_over());
+    }
+}
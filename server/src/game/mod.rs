@@ -1,7 +1,39 @@
+pub mod analysis;
+pub mod auction;
+pub mod boards;
+pub mod cardset;
+pub mod claim;
+pub mod dealgen;
 pub mod deck;
 pub mod engine;
+pub mod eval;
+pub mod fairness;
+pub mod match_state;
+pub mod pbn;
+pub mod rules;
+pub mod scoring;
+pub mod solver;
+#[cfg(test)]
+pub mod testing;
+pub mod timer;
 
+#[allow(unused_imports)]
+pub use auction::{Auction, AuctionError, Bid, Call, Contract, Strain};
+pub use boards::{BoardSource, DealSet};
+pub use cardset::CardSet;
+pub use dealgen::DealConstraints;
 #[allow(unused_imports)]
 pub use deck::{CardData, Rank, Suit};
 #[allow(unused_imports)]
-pub use engine::{GameEngine, GamePlayer, PlayError, PlayResult, TrickResolution};
+pub use engine::{
+    AuctionResult, Claim, ClaimError, ClaimOutcome, GameEngine, GamePlayer, PassError, PassResult,
+    PlayError, PlayResult, TrickResolution, Undo, UndoError, UndoOutcome,
+};
+#[allow(unused_imports)]
+pub use eval::HandEvaluation;
+#[allow(unused_imports)]
+pub use fairness::DealSeed;
+pub use match_state::MatchState;
+#[allow(unused_imports)]
+pub use rules::{Hearts, MAX_SEATS, MIN_SEATS, PassDirection, RuleKind, RuleSet, TieBreak, TrickDuel, UndoPolicy, trick_winner};
+pub use timer::TurnTimer;
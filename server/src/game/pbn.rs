@@ -0,0 +1,440 @@
+//! PBN (Portable Bridge Notation) 匯出與匯入
+//!
+//! 匯出：把一副牌 (發到的手牌、出牌歷史、合約與分數) 寫成 PBN 牌局記錄。
+//! 匯入：讀取 `[Deal]` 標籤的手牌，取代 `Deck::shuffle` 發牌。
+//!
+//! 座位對應與複式計分相同：HUMAN 隊為 N/S、AI 隊為 E/W (各隊依座位順序先 N/E 後 S/W)。
+//! 本遊戲依座位 index 輪流出牌，與 PBN 的順時針順序不同；每個 trick 的領牌、跟牌與贏家
+//! 不受影響，但叫牌的欄位順序無法對應，因此不匯出 `[Auction]`。
+
+use super::deck::{CardData, Rank, Suit};
+use super::engine::{GameEngine, GamePlayer};
+use super::scoring::Vulnerability;
+use crate::net::ConnectionId;
+use crate::protocol::{ServerMessage, Team};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// PBN 座位 (順時針)
+pub const SEATS: [char; 4] = ['N', 'E', 'S', 'W'];
+
+/// PBN 讀寫錯誤
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PbnError {
+    /// PBN 只描述 4 人牌局
+    WrongPlayerCount(usize),
+    /// `[Deal]` 格式錯誤
+    InvalidDeal(String),
+    /// 同一張牌出現兩次
+    DuplicateCard(String),
+    /// 手牌張數與規則不符
+    WrongHandSize { expected: usize, found: usize },
+    /// 沒有 `[Deal]` 標籤 (或引擎沒有發牌記錄)
+    MissingDeal,
+}
+
+impl fmt::Display for PbnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PbnError::WrongPlayerCount(n) => write!(f, "PBN needs 4 players, got {}", n),
+            PbnError::InvalidDeal(s) => write!(f, "invalid Deal tag: {}", s),
+            PbnError::DuplicateCard(c) => write!(f, "card {} appears twice", c),
+            PbnError::WrongHandSize { expected, found } => {
+                write!(f, "hand has {} cards, rules need {}", found, expected)
+            }
+            PbnError::MissingDeal => write!(f, "no Deal found"),
+        }
+    }
+}
+
+/// PBN 座位 (N, E, S, W) 對應的玩家 index
+pub fn compass_seats(players: &[GamePlayer]) -> Result<[usize; 4], PbnError> {
+    if players.len() != 4 {
+        return Err(PbnError::WrongPlayerCount(players.len()));
+    }
+    let of_team = |team: Team| -> Vec<usize> { (0..4).filter(|&i| players[i].team == team).collect() };
+    let (ns, ew) = (of_team(Team::Human), of_team(Team::Ai));
+    if ns.len() == 2 && ew.len() == 2 {
+        Ok([ns[0], ew[0], ns[1], ew[1]])
+    } else {
+        Ok([0, 1, 2, 3])
+    }
+}
+
+/// 一手牌的 PBN 表示 (花色依 S.H.D.C，點數由大到小，缺門為空字串)
+pub fn hand_to_pbn(hand: &[CardData]) -> String {
+    Suit::all()
+        .iter()
+        .map(|&suit| {
+            let mut ranks: Vec<Rank> = hand.iter().filter(|c| c.suit == suit).map(|c| c.rank).collect();
+            ranks.sort_by(|a, b| b.cmp(a));
+            ranks.iter().map(|r| r.pbn_char()).collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// 單張牌的 PBN 表示 (花色在前，e.g., "SA"、"HT")
+fn card_to_pbn(card: CardData) -> String {
+    format!("{}{}", card.suit.symbol(), card.rank.pbn_char())
+}
+
+fn vulnerability_tag(vul: Vulnerability) -> &'static str {
+    match vul {
+        Vulnerability::None => "None",
+        Vulnerability::NorthSouth => "NS",
+        Vulnerability::EastWest => "EW",
+        Vulnerability::Both => "All",
+    }
+}
+
+/// 今天的 PBN 日期 (YYYY.MM.DD，UTC)
+pub fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or(0) as i64;
+    pbn_date(days)
+}
+
+/// 由 1970-01-01 起的天數換算 PBN 日期 (公曆，每 400 年一個週期)
+fn pbn_date(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+/// 把一副牌寫成 PBN 牌局記錄 (進行中的牌只寫出已完成的 trick)
+pub fn write_game(engine: &GameEngine, event: &str, date: &str) -> Result<String, PbnError> {
+    let seats = compass_seats(&engine.players)?;
+    if engine.dealt_hands.len() != 4 {
+        return Err(PbnError::MissingDeal);
+    }
+    let seat_of = |idx: usize| seats.iter().position(|&p| p == idx).unwrap_or(0);
+    let owner_of = |card: CardData| (0..4).find(|&idx| engine.dealt_hands[idx].contains(card));
+
+    let mut tags: Vec<(&str, String)> = vec![
+        ("Event", event.to_string()),
+        ("Site", "Card Arena".to_string()),
+        ("Date", date.to_string()),
+        ("Board", engine.board.to_string()),
+        ("West", engine.players[seats[3]].player_id.clone()),
+        ("North", engine.players[seats[0]].player_id.clone()),
+        ("East", engine.players[seats[1]].player_id.clone()),
+        ("South", engine.players[seats[2]].player_id.clone()),
+        ("Dealer", SEATS[seat_of(engine.dealer_idx)].to_string()),
+        ("Vulnerable", vulnerability_tag(Vulnerability::for_board(engine.board)).to_string()),
+    ];
+
+    // Deal 由莊家開始順時針列出四手
+    let dealer_seat = seat_of(engine.dealer_idx);
+    let hands: Vec<String> = (0..4)
+        .map(|i| hand_to_pbn(&engine.dealt_hands[seats[(dealer_seat + i) % 4]].to_vec()))
+        .collect();
+    tags.push(("Deal", format!("{}:{}", SEATS[dealer_seat], hands.join(" "))));
+
+    match engine.contract {
+        Some(contract) => {
+            let declarer_team = engine.players[contract.declarer].team;
+            tags.push(("Declarer", SEATS[seat_of(contract.declarer)].to_string()));
+            tags.push(("Contract", contract.to_string()));
+            tags.push(("Result", engine.tricks_for(declarer_team).to_string()));
+        }
+        None => {
+            tags.push(("Declarer", String::new()));
+            tags.push(("Contract", String::new()));
+            tags.push(("Result", String::new()));
+        }
+    }
+    if let Some(hand_score) = engine.hand_score() {
+        tags.push(("Score", format!("NS {}", hand_score.score_for(Team::Human))));
+    }
+
+    // 非標準標籤 (PBN 允許自訂)
+    tags.push(("Rules", engine.rules.name().to_string()));
+    if engine.contract.is_none()
+        && let Some(trump) = engine.trump
+    {
+        tags.push(("Trump", trump.symbol().to_string()));
+    }
+    match &engine.deal_set {
+        Some(name) => tags.push(("DealSet", name.clone())),
+        None => tags.push(("Seed", engine.seed.to_string())),
+    }
+    tags.push(("Tricks", format!("NS {} EW {}", engine.score.human, engine.score.ai)));
+
+    let mut out = String::new();
+    for (name, value) in &tags {
+        out.push_str(&format!("[{} \"{}\"]\n", name, value));
+    }
+
+    // Play：每行一個 trick，由首引者起順時針列出各座位的牌
+    let first_lead = engine
+        .history
+        .first()
+        .and_then(|h| h.cards.first())
+        .and_then(|c| CardData::from_protocol_string(c))
+        .and_then(owner_of);
+    if let Some(leader) = first_lead {
+        let lead_seat = seat_of(leader);
+        out.push_str(&format!("[Play \"{}\"]\n", SEATS[lead_seat]));
+        for trick in &engine.history {
+            let cards: Vec<CardData> =
+                trick.cards.iter().filter_map(|c| CardData::from_protocol_string(c)).collect();
+            let line: Vec<String> = (0..4)
+                .map(|i| {
+                    let idx = seats[(lead_seat + i) % 4];
+                    cards
+                        .iter()
+                        .find(|&&c| owner_of(c) == Some(idx))
+                        .map(|&c| card_to_pbn(c))
+                        .unwrap_or_else(|| "-".to_string())
+                })
+                .collect();
+            out.push_str(&line.join(" "));
+            out.push('\n');
+        }
+        out.push_str("*\n");
+    }
+
+    Ok(out)
+}
+
+/// 從 PBN 讀出的一副牌
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PbnDeal {
+    /// `[Board]` 牌號
+    pub board: Option<u32>,
+    /// `[Dealer]` 座位 (0..4 對應 N, E, S, W)
+    pub dealer: Option<usize>,
+    /// N, E, S, W 的手牌
+    pub hands: [Vec<CardData>; 4],
+}
+
+impl PbnDeal {
+    /// `[Deal]` 標籤的值 (從 N 開始)
+    pub fn deal_tag(&self) -> String {
+        let hands: Vec<String> = self.hands.iter().map(|h| hand_to_pbn(h)).collect();
+        format!("N:{}", hands.join(" "))
+    }
+
+    /// 依座位對應轉成玩家 index 順序的手牌
+    pub fn hands_for(&self, players: &[GamePlayer]) -> Result<Vec<Vec<CardData>>, PbnError> {
+        let seats = compass_seats(players)?;
+        let mut hands = vec![Vec::new(); 4];
+        for (seat, &idx) in seats.iter().enumerate() {
+            hands[idx] = self.hands[seat].clone();
+        }
+        Ok(hands)
+    }
+}
+
+/// 解析 `[Deal]` 的值 (e.g., "N:AKQ.T9.8765.432 ...")
+pub fn parse_deal(value: &str) -> Result<[Vec<CardData>; 4], PbnError> {
+    let invalid = || PbnError::InvalidDeal(value.to_string());
+    let (first, rest) = value.trim().split_once(':').ok_or_else(invalid)?;
+    let first_seat = first
+        .trim()
+        .chars()
+        .next()
+        .and_then(|c| SEATS.iter().position(|&s| s == c.to_ascii_uppercase()))
+        .ok_or_else(invalid)?;
+
+    let hand_strs: Vec<&str> = rest.split_whitespace().collect();
+    if hand_strs.len() != 4 {
+        return Err(invalid());
+    }
+
+    let mut hands: [Vec<CardData>; 4] = Default::default();
+    let mut seen = HashSet::new();
+    for (i, hand_str) in hand_strs.iter().enumerate() {
+        let suits: Vec<&str> = hand_str.split('.').collect();
+        if suits.len() != 4 {
+            return Err(invalid());
+        }
+        let hand = &mut hands[(first_seat + i) % 4];
+        for (suit, ranks) in Suit::all().into_iter().zip(suits) {
+            for c in ranks.chars() {
+                let rank = Rank::from_str(&c.to_string()).ok_or_else(invalid)?;
+                let card = CardData::new(suit, rank);
+                if !seen.insert(card) {
+                    return Err(PbnError::DuplicateCard(card.to_protocol_string()));
+                }
+                hand.push(card);
+            }
+        }
+    }
+    Ok(hands)
+}
+
+/// 解析標籤行 `[Name "Value"]`
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+    let (name, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((name.to_string(), value.to_string()))
+}
+
+/// 讀取 PBN 檔中每副牌的 `[Deal]` (以空行分隔牌局；沒有 Deal 的牌局略過)
+pub fn read_deals(text: &str) -> Result<Vec<PbnDeal>, PbnError> {
+    let mut deals = Vec::new();
+    let mut tags: Vec<(String, String)> = Vec::new();
+
+    let mut finish = |tags: &mut Vec<(String, String)>| -> Result<(), PbnError> {
+        let get = |name: &str| tags.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str());
+        if let Some(deal) = get("Deal") {
+            deals.push(PbnDeal {
+                board: get("Board").and_then(|b| b.parse().ok()),
+                dealer: get("Dealer")
+                    .and_then(|d| d.chars().next())
+                    .and_then(|c| SEATS.iter().position(|&s| s == c.to_ascii_uppercase())),
+                hands: parse_deal(deal)?,
+            });
+        }
+        tags.clear();
+        Ok(())
+    };
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            finish(&mut tags)?;
+        } else if line.starts_with('%') || line.starts_with(';') {
+            continue;
+        } else if let Some(tag) = parse_tag(line) {
+            tags.push(tag);
+        }
+    }
+    finish(&mut tags)?;
+
+    if deals.is_empty() {
+        return Err(PbnError::MissingDeal);
+    }
+    Ok(deals)
+}
+
+/// 以 PBN 的牌發牌 (取代洗牌)，手牌張數必須符合規則
+pub fn deal_from_pbn(engine: &mut GameEngine, deal: &PbnDeal) -> Result<Vec<(ConnectionId, ServerMessage)>, PbnError> {
+    let expected = engine.cards_per_player();
+    let hands = deal.hands_for(&engine.players)?;
+    if let Some(hand) = hands.iter().find(|h| h.len() != expected) {
+        return Err(PbnError::WrongHandSize { expected, found: hand.len() });
+    }
+    Ok(engine.deal_hands(hands))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::auction::{Contract, Doubling, Strain};
+    use crate::game::testing::{self, play_out};
+
+    fn new_engine() -> GameEngine {
+        testing::new_engine(2024)
+    }
+
+    const DEAL: &str = "N:AKQJ.T98.765.432 T98.765.432.AKQJ 765.432.AKQJ.T98 432.AKQJ.T98.765";
+
+    #[test]
+    fn test_parse_deal() {
+        let hands = parse_deal(DEAL).unwrap();
+        assert!(hands.iter().all(|h| h.len() == 13));
+        assert!(hands[0].contains(&CardData::from_protocol_string("10H").unwrap()));
+        assert!(hands[3].contains(&CardData::from_protocol_string("AH").unwrap()));
+
+        // 從 E 開始列出時，第一手屬於 E
+        let rotated = parse_deal("E:AKQJ.T98.765.432 T98.765.432.AKQJ 765.432.AKQJ.T98 432.AKQJ.T98.765").unwrap();
+        assert_eq!(rotated[1], hands[0]);
+
+        assert!(matches!(parse_deal("N:AKQ.T98"), Err(PbnError::InvalidDeal(_))));
+        assert!(matches!(
+            parse_deal("N:AA.T98.765.432 T98.765.432.AKQJ 765.432.AKQJ.T98 432.AKQJ.T98.765"),
+            Err(PbnError::DuplicateCard(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_deals_and_deal_into_engine() {
+        let text = format!(
+            "% PBN 2.1\n[Event \"Test\"]\n[Board \"3\"]\n[Dealer \"S\"]\n[Deal \"{}\"]\n\n[Event \"No deal\"]\n",
+            DEAL
+        );
+        let deals = read_deals(&text).unwrap();
+        assert_eq!(deals.len(), 1);
+        assert_eq!(deals[0].board, Some(3));
+        assert_eq!(deals[0].dealer, Some(2));
+
+        // N/S 為 HUMAN (P1, P2)，E/W 為 AI (P3, P4)
+        let mut engine = new_engine();
+        deal_from_pbn(&mut engine, &deals[0]).unwrap();
+        assert_eq!(engine.players[0].hand.len(), 13);
+        assert!(engine.players[1].hand.contains(CardData::from_protocol_string("AD").unwrap()));
+        assert!(engine.players[2].hand.contains(CardData::from_protocol_string("AC").unwrap()));
+        assert_eq!(engine.dealt_hands.len(), 4);
+
+        assert!(matches!(read_deals("[Event \"x\"]\n"), Err(PbnError::MissingDeal)));
+    }
+
+    #[test]
+    fn test_write_game_round_trip() {
+        let mut engine = new_engine();
+        engine.deal();
+        engine.contract = Some(Contract {
+            level: 4,
+            strain: Strain::Spades,
+            doubling: Doubling::Undoubled,
+            declarer: 0,
+        });
+        play_out(&mut engine);
+
+        let pbn = write_game(&engine, "Card Arena", "2026.01.02").unwrap();
+        assert!(pbn.contains("[Date \"2026.01.02\"]\n"));
+        assert!(pbn.contains("[West \"P4\"]\n[North \"P1\"]\n[East \"P3\"]\n[South \"P2\"]\n"));
+        assert!(pbn.contains("[Contract \"4S\"]\n"));
+        assert!(pbn.contains("[Declarer \"N\"]\n"));
+        assert!(pbn.contains(&format!("[Seed \"{}\"]", engine.seed)));
+        assert!(pbn.trim_end().ends_with('*'));
+
+        // 13 個 trick，每行 4 張
+        let play: Vec<&str> = pbn.lines().skip_while(|l| !l.starts_with("[Play")).skip(1).collect();
+        assert_eq!(play.len(), 14);
+        assert!(play[..13].iter().all(|l| l.split_whitespace().count() == 4));
+
+        // 匯出的 Deal 可再讀回相同的手牌
+        let deals = read_deals(&pbn).unwrap();
+        let mut reloaded = new_engine();
+        deal_from_pbn(&mut reloaded, &deals[0]).unwrap();
+        assert_eq!(reloaded.dealt_hands, engine.dealt_hands);
+    }
+
+    #[test]
+    fn test_write_requires_deal() {
+        let engine = new_engine();
+        assert_eq!(write_game(&engine, "x", "?").unwrap_err(), PbnError::MissingDeal);
+    }
+
+    #[test]
+    fn test_pbn_date() {
+        assert_eq!(pbn_date(0), "1970.01.01");
+        assert_eq!(pbn_date(59), "1970.03.01");
+        assert_eq!(pbn_date(11_016), "2000.02.29");
+        assert_eq!(pbn_date(11_017), "2000.03.01");
+        assert_eq!(pbn_date(19_722), "2023.12.31");
+        assert_eq!(pbn_date(19_782), "2024.02.29");
+        assert_eq!(pbn_date(47_541), "2100.03.01");
+        assert_eq!(pbn_date(-1), "1969.12.31");
+
+        let date = today();
+        assert_eq!(date.len(), 10);
+        assert!(date.as_str() >= "2024.01.01");
+    }
+}
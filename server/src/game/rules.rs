@@ -0,0 +1,503 @@
+//! 出牌規則 (RuleSet)
+//!
+//! 把合法出牌、trick 贏家、計分與一副牌的結束條件從 GameEngine 抽離，
+//! 讓同一套大廳、協議與 AI 流程可以承載不同的吃墩遊戲 (Whist 家族)。
+
+use super::cardset::CardSet;
+use super::deck::{CardData, Rank, Suit};
+use serde::{Deserialize, Serialize};
+
+/// 規則判斷時可見的牌局狀態
+#[derive(Debug, Clone, Copy)]
+pub struct TrickContext<'a> {
+    /// 當前 trick 編號 (1-based)
+    pub trick: u32,
+    /// 當前 trick 已出的牌 (player_idx, card)
+    pub table: &'a [(usize, CardData)],
+    /// 之前 tricks 已出過的牌
+    pub played: CardSet,
+}
+
+/// 一桌的座位數下限
+pub const MIN_SEATS: usize = 2;
+/// 一桌的座位數上限
+pub const MAX_SEATS: usize = 6;
+
+/// 吃墩遊戲規則
+pub trait RuleSet: Send + Sync {
+    /// 規則名稱 (協議與設定使用)
+    fn name(&self) -> &'static str;
+
+    /// 規則種類
+    fn kind(&self) -> RuleKind;
+
+    /// 每位玩家的手牌張數 (房間未指定手牌張數時)
+    fn cards_per_player(&self, num_players: usize) -> usize {
+        52 / num_players
+    }
+
+    /// 是否支援這個座位數 (搭檔制兩隊人數要相同，只能是偶數)
+    fn supports_seats(&self, num_players: usize) -> bool {
+        (MIN_SEATS..=MAX_SEATS).contains(&num_players) && (num_players.is_multiple_of(2) || !self.partnership())
+    }
+
+    /// 是否可以只發部分手牌 (HAND_SIZE)
+    fn supports_short_hands(&self) -> bool {
+        true
+    }
+
+    /// 取得合法出牌
+    fn legal_moves(&self, hand: CardSet, ctx: &TrickContext) -> CardSet;
+
+    /// 判定 trick 贏家 (回傳 player_idx)
+    fn trick_winner(&self, table: &[(usize, CardData)], trump: Option<Suit>) -> usize;
+
+    /// trick 贏家得到的分數
+    fn trick_points(&self, cards: &[CardData]) -> u32;
+
+    /// 一副牌是否結束
+    fn is_hand_over(&self, tricks_played: u32, hands: &[CardSet]) -> bool {
+        let _ = tricks_played;
+        hands.iter().all(|h| h.is_empty())
+    }
+
+    /// 是否為搭檔制 (叫牌、王牌與隊伍勝負只適用搭檔制；否則以個人計分)
+    fn partnership(&self) -> bool {
+        true
+    }
+
+    /// 出牌前每人要傳出的張數 (0 = 不傳牌)
+    fn pass_count(&self) -> usize {
+        0
+    }
+
+    /// 第一個 trick 的首引者 (None = 由莊家首引)
+    fn opening_leader(&self, hands: &[CardSet]) -> Option<usize> {
+        let _ = hands;
+        None
+    }
+
+    /// 一副牌結束時的個人分數 (輸入為各玩家吃到的 trick 分數)
+    fn final_points(&self, taken: &[u32]) -> Vec<u32> {
+        taken.to_vec()
+    }
+}
+
+/// 傳牌方向 (依副數輪替：左、右、對家、不傳)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PassDirection {
+    Left,
+    Right,
+    Across,
+    Hold,
+}
+
+impl PassDirection {
+    /// 第 hand 副牌 (1-based) 的傳牌方向
+    pub fn for_hand(hand: u32) -> PassDirection {
+        match (hand.max(1) - 1) % 4 {
+            0 => PassDirection::Left,
+            1 => PassDirection::Right,
+            2 => PassDirection::Across,
+            _ => PassDirection::Hold,
+        }
+    }
+
+    /// 座位 from 要把牌傳給誰 (Hold 時為自己)
+    pub fn target(self, from: usize, num_players: usize) -> usize {
+        match self {
+            PassDirection::Left => (from + 1) % num_players,
+            PassDirection::Right => (from + num_players - 1) % num_players,
+            PassDirection::Across => (from + num_players / 2) % num_players,
+            PassDirection::Hold => from,
+        }
+    }
+
+    /// 協議字串
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PassDirection::Left => "LEFT",
+            PassDirection::Right => "RIGHT",
+            PassDirection::Across => "ACROSS",
+            PassDirection::Hold => "HOLD",
+        }
+    }
+}
+
+/// 可選的規則種類 (依房間設定)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RuleKind {
+    #[default]
+    TrickDuel,
+    Hearts,
+}
+
+impl RuleKind {
+    pub fn from_str(s: &str) -> Option<RuleKind> {
+        match s.to_uppercase().replace('-', "_").as_str() {
+            "TRICK_DUEL" => Some(RuleKind::TrickDuel),
+            "HEARTS" => Some(RuleKind::Hearts),
+            _ => None,
+        }
+    }
+
+    /// 建立對應的規則
+    pub fn build(self) -> Box<dyn RuleSet> {
+        match self {
+            RuleKind::TrickDuel => Box::new(TrickDuel),
+            RuleKind::Hearts => Box::new(Hearts),
+        }
+    }
+}
+
+/// 一副牌分數平手時的勝負判定 (依房間設定)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TieBreak {
+    /// 判和
+    #[default]
+    Draw,
+    /// 最後一個 trick 的贏家隊伍獲勝
+    LastTrick,
+    /// 吃到較多大牌 (A, K, Q, J, 10) 的隊伍獲勝，仍相同則判和
+    HighCards,
+}
+
+impl TieBreak {
+    pub fn from_str(s: &str) -> Option<TieBreak> {
+        match s.to_uppercase().replace('-', "_").as_str() {
+            "DRAW" => Some(TieBreak::Draw),
+            "LAST_TRICK" => Some(TieBreak::LastTrick),
+            "HIGH_CARDS" => Some(TieBreak::HighCards),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TieBreak::Draw => "DRAW",
+            TieBreak::LastTrick => "LAST_TRICK",
+            TieBreak::HighCards => "HIGH_CARDS",
+        }
+    }
+}
+
+/// 悔牌 (收回最後一張牌) 的範圍 (依房間設定；允許悔牌的房間視為練習房)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum UndoPolicy {
+    /// 不允許悔牌
+    #[default]
+    Off,
+    /// 只能收回進行中 trick 的牌
+    Trick,
+    /// 可以跨過已結算的 trick
+    Board,
+}
+
+impl UndoPolicy {
+    pub fn from_str(s: &str) -> Option<UndoPolicy> {
+        match s.to_uppercase().as_str() {
+            "OFF" => Some(UndoPolicy::Off),
+            "TRICK" => Some(UndoPolicy::Trick),
+            "BOARD" => Some(UndoPolicy::Board),
+            _ => None,
+        }
+    }
+}
+
+/// Trick Duel (預設規則)
+///
+/// - 必須跟領牌花色，沒有才可出任意牌
+/// - 有王牌時最大的王牌獲勝，否則領牌花色最大者獲勝
+/// - 每個 trick 1 分，所有手牌出完即結束
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TrickDuel;
+
+impl RuleSet for TrickDuel {
+    fn name(&self) -> &'static str {
+        "TRICK_DUEL"
+    }
+
+    fn kind(&self) -> RuleKind {
+        RuleKind::TrickDuel
+    }
+
+    fn legal_moves(&self, hand: CardSet, ctx: &TrickContext) -> CardSet {
+        follow_suit(hand, ctx.table)
+    }
+
+    fn trick_winner(&self, table: &[(usize, CardData)], trump: Option<Suit>) -> usize {
+        trick_winner(table, trump)
+    }
+
+    fn trick_points(&self, _cards: &[CardData]) -> u32 {
+        1
+    }
+}
+
+/// 紅心 (Hearts)
+///
+/// - 個人計分，出牌前依副數輪流向左、右、對家傳 3 張 (第 4 副不傳)
+/// - 持有 ♣2 者首引，第一個 trick 不能墊紅心或 ♠Q (除非手上只剩這些牌)
+/// - 紅心在被墊出 (broken) 之前不能領出，除非手上只剩紅心
+/// - 沒有王牌；每張紅心 1 分、♠Q 13 分，分數越低越好
+/// - 一人吃下全部 26 分 (shooting the moon) 時自己 0 分，其他人各 26 分
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Hearts;
+
+impl Hearts {
+    /// 一副牌的總罰分
+    pub const TOTAL_POINTS: u32 = 26;
+
+    /// ♣2 (首引牌)
+    pub const OPENING_CARD: CardData = CardData {
+        suit: Suit::Clubs,
+        rank: Rank::TWO,
+    };
+
+    /// ♠Q
+    pub const QUEEN_OF_SPADES: CardData = CardData {
+        suit: Suit::Spades,
+        rank: Rank::QUEEN,
+    };
+
+    /// 單張牌的罰分
+    pub fn card_points(card: &CardData) -> u32 {
+        if card.suit == Suit::Hearts {
+            1
+        } else if *card == Self::QUEEN_OF_SPADES {
+            13
+        } else {
+            0
+        }
+    }
+}
+
+impl RuleSet for Hearts {
+    fn name(&self) -> &'static str {
+        "HEARTS"
+    }
+
+    fn kind(&self) -> RuleKind {
+        RuleKind::Hearts
+    }
+
+    fn legal_moves(&self, hand: CardSet, ctx: &TrickContext) -> CardSet {
+        // 第一個 trick 由 ♣2 首引
+        if ctx.trick == 1 && ctx.table.is_empty() && hand.contains(Self::OPENING_CARD) {
+            return CardSet::single(Self::OPENING_CARD);
+        }
+
+        let moves = follow_suit(hand, ctx.table);
+        // 只剩會被限制的牌時不再限制
+        let keep_if_any = |filtered: CardSet| if filtered.is_empty() { moves } else { filtered };
+        let hearts = CardSet::suit_mask(Suit::Hearts);
+
+        if ctx.table.is_empty() {
+            // 紅心未破之前不能領紅心
+            let broken = !ctx.played.of_suit(Suit::Hearts).is_empty();
+            if broken {
+                return moves;
+            }
+            return keep_if_any(moves - hearts);
+        }
+
+        if ctx.trick == 1 {
+            // 第一個 trick 不能墊分數牌
+            return keep_if_any(moves - hearts - CardSet::single(Self::QUEEN_OF_SPADES));
+        }
+
+        moves
+    }
+
+    fn trick_winner(&self, table: &[(usize, CardData)], _trump: Option<Suit>) -> usize {
+        trick_winner(table, None)
+    }
+
+    fn trick_points(&self, cards: &[CardData]) -> u32 {
+        cards.iter().map(Self::card_points).sum()
+    }
+
+    fn partnership(&self) -> bool {
+        false
+    }
+
+    /// 傳牌方向與射月 (26 分) 都以 4 人為準
+    fn supports_seats(&self, num_players: usize) -> bool {
+        num_players == 4
+    }
+
+    /// ♣2 首引與射月都假設整副牌發完
+    fn supports_short_hands(&self) -> bool {
+        false
+    }
+
+    fn pass_count(&self) -> usize {
+        3
+    }
+
+    fn opening_leader(&self, hands: &[CardSet]) -> Option<usize> {
+        hands.iter().position(|h| h.contains(Self::OPENING_CARD))
+    }
+
+    fn final_points(&self, taken: &[u32]) -> Vec<u32> {
+        // Shooting the moon
+        match taken.iter().position(|&p| p == Self::TOTAL_POINTS) {
+            Some(shooter) => (0..taken.len())
+                .map(|i| if i == shooter { 0 } else { Self::TOTAL_POINTS })
+                .collect(),
+            None => taken.to_vec(),
+        }
+    }
+}
+
+/// 跟牌規則：有領牌花色必須跟，沒有才可出任意牌
+/// 王牌不影響跟牌規則，只有缺領牌花色時才能王吃
+pub fn follow_suit(hand: CardSet, table: &[(usize, CardData)]) -> CardSet {
+    let lead_suit = match table.first() {
+        Some((_, card)) => card.suit,
+        // 第一個出牌的人可以出任何牌
+        None => return hand,
+    };
+
+    let same_suit = hand.of_suit(lead_suit);
+    if same_suit.is_empty() { hand } else { same_suit }
+}
+
+/// 判定 trick 贏家 (回傳 player_idx)
+/// 有人出王牌時最大的王牌獲勝，否則領牌花色最大者獲勝
+pub fn trick_winner(table: &[(usize, CardData)], trump: Option<Suit>) -> usize {
+    let lead_suit = table[0].1.suit;
+    let winning_suit = match trump {
+        Some(t) if table.iter().any(|(_, c)| c.suit == t) => t,
+        _ => lead_suit,
+    };
+
+    table
+        .iter()
+        .filter(|(_, card)| card.suit == winning_suit)
+        .max_by_key(|(_, card)| card.rank.0)
+        .map(|(idx, _)| *idx)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::deck::Rank;
+
+    #[test]
+    fn test_follow_suit() {
+        let hand: CardSet = [
+            CardData::new(Suit::Spades, Rank::ACE),
+            CardData::new(Suit::Hearts, Rank::TWO),
+        ]
+        .into_iter()
+        .collect();
+        let table = vec![(0, CardData::new(Suit::Hearts, Rank::KING))];
+        assert_eq!(follow_suit(hand, &table), CardSet::single(CardData::new(Suit::Hearts, Rank::TWO)));
+        assert_eq!(follow_suit(hand, &[]).len(), 2);
+
+        let table = vec![(0, CardData::new(Suit::Clubs, Rank::KING))];
+        assert_eq!(follow_suit(hand, &table).len(), 2);
+    }
+
+    #[test]
+    fn test_trick_duel_rules() {
+        let rules = RuleKind::from_str("trick-duel").unwrap().build();
+        assert_eq!(rules.name(), "TRICK_DUEL");
+        assert_eq!(rules.cards_per_player(4), 13);
+        assert_eq!(rules.cards_per_player(3), 17);
+        assert!(rules.supports_seats(2) && rules.supports_seats(6));
+        assert!(!rules.supports_seats(7));
+        assert!(!rules.supports_seats(3) && !rules.supports_seats(5));
+        assert!(!RuleKind::Hearts.build().supports_seats(3));
+        assert!(rules.supports_short_hands() && !RuleKind::Hearts.build().supports_short_hands());
+        assert_eq!(rules.trick_points(&[]), 1);
+
+        let empty = CardSet::EMPTY;
+        assert!(rules.is_hand_over(13, &[empty, empty]));
+        let hand = CardSet::single(CardData::new(Suit::Spades, Rank::ACE));
+        assert!(!rules.is_hand_over(12, &[hand, empty]));
+        assert!(RuleKind::from_str("euchre").is_none());
+    }
+
+    fn card(s: &str) -> CardData {
+        CardData::from_protocol_string(s).unwrap()
+    }
+
+    fn cards(list: &[&str]) -> CardSet {
+        list.iter().map(|s| card(s)).collect()
+    }
+
+    #[test]
+    fn test_pass_direction_rotation() {
+        assert_eq!(PassDirection::for_hand(1), PassDirection::Left);
+        assert_eq!(PassDirection::for_hand(2), PassDirection::Right);
+        assert_eq!(PassDirection::for_hand(3), PassDirection::Across);
+        assert_eq!(PassDirection::for_hand(4), PassDirection::Hold);
+        assert_eq!(PassDirection::for_hand(5), PassDirection::Left);
+
+        assert_eq!(PassDirection::Left.target(3, 4), 0);
+        assert_eq!(PassDirection::Right.target(0, 4), 3);
+        assert_eq!(PassDirection::Across.target(1, 4), 3);
+        assert_eq!(PassDirection::Hold.target(2, 4), 2);
+    }
+
+    #[test]
+    fn test_hearts_opening_lead_and_first_trick() {
+        let rules = RuleKind::Hearts.build();
+        let hand = cards(&["2C", "9C", "QS", "5H"]);
+        let ctx = TrickContext { trick: 1, table: &[], played: CardSet::EMPTY };
+        assert_eq!(rules.legal_moves(hand, &ctx), cards(&["2C"]));
+
+        // 缺門時第一個 trick 不能墊分數牌
+        let hand = cards(&["QS", "5H", "3D"]);
+        let table = vec![(0, card("2C"))];
+        let ctx = TrickContext { trick: 1, table: &table, played: CardSet::EMPTY };
+        assert_eq!(rules.legal_moves(hand, &ctx), cards(&["3D"]));
+
+        // 只剩分數牌時可以出
+        let hand = cards(&["QS", "5H"]);
+        assert_eq!(rules.legal_moves(hand, &ctx).len(), 2);
+
+        let hands = vec![CardSet::EMPTY, hand, CardSet::EMPTY];
+        assert_eq!(rules.opening_leader(&hands), None);
+        let hands = vec![CardSet::EMPTY, hand, cards(&["2C"])];
+        assert_eq!(rules.opening_leader(&hands), Some(2));
+    }
+
+    #[test]
+    fn test_hearts_broken() {
+        let rules = RuleKind::Hearts.build();
+        let hand = cards(&["AH", "4D"]);
+        let ctx = TrickContext { trick: 3, table: &[], played: CardSet::EMPTY };
+        assert_eq!(rules.legal_moves(hand, &ctx), cards(&["4D"]));
+
+        let ctx = TrickContext { trick: 3, table: &[], played: cards(&["7H"]) };
+        assert_eq!(rules.legal_moves(hand, &ctx).len(), 2);
+
+        // 只剩紅心時可以領紅心
+        let hand = cards(&["AH", "2H"]);
+        let ctx = TrickContext { trick: 3, table: &[], played: CardSet::EMPTY };
+        assert_eq!(rules.legal_moves(hand, &ctx).len(), 2);
+    }
+
+    #[test]
+    fn test_hearts_scoring() {
+        let rules = RuleKind::Hearts.build();
+        assert!(!rules.partnership());
+        assert_eq!(rules.pass_count(), 3);
+        assert_eq!(rules.trick_points(&cards(&["QS", "2H", "KH", "3C"]).to_vec()), 15);
+        assert_eq!(rules.trick_points(&cards(&["QC", "2D"]).to_vec()), 0);
+
+        // 王牌不影響 trick 贏家
+        let table = vec![(0, card("2D")), (1, card("AS")), (2, card("9D"))];
+        assert_eq!(rules.trick_winner(&table, Some(Suit::Spades)), 2);
+
+        assert_eq!(rules.final_points(&[3, 10, 13, 0]), vec![3, 10, 13, 0]);
+        assert_eq!(rules.final_points(&[0, 26, 0, 0]), vec![26, 0, 26, 26]);
+    }
+}
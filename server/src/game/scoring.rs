@@ -0,0 +1,271 @@
+//! 複式橋牌計分 (Duplicate Scoring)
+//!
+//! 依合約、實際墩數與身價計算一副牌的分數。
+//! 分數以主打方的角度表示：做成為正，倒約為負。
+
+use super::auction::{Contract, Doubling, Strain};
+use crate::protocol::Team;
+
+/// 身價 (HUMAN 隊視為 NS，AI 隊視為 EW)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vulnerability {
+    None,
+    NorthSouth,
+    EastWest,
+    Both,
+}
+
+impl Vulnerability {
+    /// 依牌號決定身價 (標準 16 副循環)
+    pub fn for_board(board: u32) -> Vulnerability {
+        const CYCLE: [Vulnerability; 16] = [
+            Vulnerability::None,
+            Vulnerability::NorthSouth,
+            Vulnerability::EastWest,
+            Vulnerability::Both,
+            Vulnerability::NorthSouth,
+            Vulnerability::EastWest,
+            Vulnerability::Both,
+            Vulnerability::None,
+            Vulnerability::EastWest,
+            Vulnerability::Both,
+            Vulnerability::None,
+            Vulnerability::NorthSouth,
+            Vulnerability::Both,
+            Vulnerability::None,
+            Vulnerability::NorthSouth,
+            Vulnerability::EastWest,
+        ];
+        CYCLE[(board.max(1) as usize - 1) % 16]
+    }
+
+    /// 隊伍是否有身價
+    pub fn is_vulnerable(self, team: Team) -> bool {
+        match self {
+            Vulnerability::None => false,
+            Vulnerability::Both => true,
+            Vulnerability::NorthSouth => team == Team::Human,
+            Vulnerability::EastWest => team == Team::Ai,
+        }
+    }
+}
+
+/// 一副牌的計分結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandScore {
+    pub contract: Contract,
+    /// 結果字串 (e.g., "4S+1")
+    pub result: String,
+    /// 主打方隊伍
+    pub declarer_team: Team,
+    /// 主打方分數 (倒約為負)
+    pub declarer_score: i32,
+}
+
+impl HandScore {
+    /// 計算主打方拿到 tricks_taken 墩時的結果
+    pub fn new(contract: Contract, declarer_team: Team, tricks_taken: u32, vul: Vulnerability) -> Self {
+        Self {
+            contract,
+            result: result_string(&contract, tricks_taken),
+            declarer_team,
+            declarer_score: score_contract(&contract, tricks_taken, vul.is_vulnerable(declarer_team)),
+        }
+    }
+
+    /// 隊伍的分數 (防守方為主打方分數取負)
+    pub fn score_for(&self, team: Team) -> i32 {
+        if team == self.declarer_team {
+            self.declarer_score
+        } else {
+            -self.declarer_score
+        }
+    }
+}
+
+/// 合約結果字串 (e.g., "4S=", "4S+1", "3NTX-2")
+pub fn result_string(contract: &Contract, tricks_taken: u32) -> String {
+    let diff = tricks_taken as i32 - contract.tricks_required() as i32;
+    match diff {
+        0 => format!("{}=", contract),
+        d if d > 0 => format!("{}+{}", contract, d),
+        d => format!("{}{}", contract, d),
+    }
+}
+
+/// 計算一副牌的複式分數 (主打方角度)
+pub fn score_contract(contract: &Contract, tricks_taken: u32, vulnerable: bool) -> i32 {
+    let required = contract.tricks_required();
+    if tricks_taken >= required {
+        made_score(contract, tricks_taken - required, vulnerable)
+    } else {
+        -undertrick_penalty(contract, required - tricks_taken, vulnerable)
+    }
+}
+
+/// 賭倍倍數 (1 / 2 / 4)
+fn doubling_multiplier(doubling: Doubling) -> i32 {
+    match doubling {
+        Doubling::Undoubled => 1,
+        Doubling::Doubled => 2,
+        Doubling::Redoubled => 4,
+    }
+}
+
+/// 每墩的基本分 (NT 第一墩 40 分另計)
+fn trick_value(strain: Strain) -> i32 {
+    match strain {
+        Strain::Clubs | Strain::Diamonds => 20,
+        Strain::Hearts | Strain::Spades | Strain::NoTrump => 30,
+    }
+}
+
+/// 做成合約的分數
+fn made_score(contract: &Contract, overtricks: u32, vulnerable: bool) -> i32 {
+    let multiplier = doubling_multiplier(contract.doubling);
+    let level = contract.level as i32;
+
+    // 合約墩分
+    let mut base = trick_value(contract.strain) * level;
+    if contract.strain == Strain::NoTrump {
+        base += 10;
+    }
+    let contract_points = base * multiplier;
+
+    // 超墩分
+    let overtrick_points = overtricks as i32
+        * match contract.doubling {
+            Doubling::Undoubled => trick_value(contract.strain),
+            Doubling::Doubled => if vulnerable { 200 } else { 100 },
+            Doubling::Redoubled => if vulnerable { 400 } else { 200 },
+        };
+
+    // 成局 / 部分合約獎分
+    let game_bonus = if contract_points >= 100 {
+        if vulnerable { 500 } else { 300 }
+    } else {
+        50
+    };
+
+    // 滿貫獎分
+    let slam_bonus = match contract.level {
+        6 => if vulnerable { 750 } else { 500 },
+        7 => if vulnerable { 1500 } else { 1000 },
+        _ => 0,
+    };
+
+    // 賭倍做成獎分
+    let insult = match contract.doubling {
+        Doubling::Undoubled => 0,
+        Doubling::Doubled => 50,
+        Doubling::Redoubled => 100,
+    };
+
+    contract_points + overtrick_points + game_bonus + slam_bonus + insult
+}
+
+/// 倒約罰分 (正值)
+fn undertrick_penalty(contract: &Contract, undertricks: u32, vulnerable: bool) -> i32 {
+    let n = undertricks as i32;
+
+    if contract.doubling == Doubling::Undoubled {
+        return n * if vulnerable { 100 } else { 50 };
+    }
+
+    // 賭倍: 無身價 100, 200, 200, 之後每墩 300；有身價 200, 之後每墩 300
+    let doubled = if vulnerable {
+        200 + (n - 1) * 300
+    } else {
+        match n {
+            1 => 100,
+            2 => 300,
+            3 => 500,
+            _ => 500 + (n - 3) * 300,
+        }
+    };
+
+    if contract.doubling == Doubling::Redoubled {
+        doubled * 2
+    } else {
+        doubled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(level: u8, strain: Strain, doubling: Doubling) -> Contract {
+        Contract {
+            level,
+            strain,
+            doubling,
+            declarer: 0,
+        }
+    }
+
+    #[test]
+    fn test_made_contracts() {
+        let four_spades = contract(4, Strain::Spades, Doubling::Undoubled);
+        assert_eq!(score_contract(&four_spades, 10, false), 420);
+        assert_eq!(score_contract(&four_spades, 11, true), 650);
+
+        assert_eq!(score_contract(&contract(3, Strain::NoTrump, Doubling::Undoubled), 9, false), 400);
+        assert_eq!(score_contract(&contract(1, Strain::NoTrump, Doubling::Undoubled), 7, false), 90);
+        assert_eq!(score_contract(&contract(2, Strain::Diamonds, Doubling::Undoubled), 9, true), 110);
+    }
+
+    #[test]
+    fn test_doubled_and_redoubled_made() {
+        // 2HX 做成 = 120 墩分 (成局) + 300 + 50
+        assert_eq!(score_contract(&contract(2, Strain::Hearts, Doubling::Doubled), 8, false), 470);
+        // 1CXX 做成 = 80 + 50 + 100
+        assert_eq!(score_contract(&contract(1, Strain::Clubs, Doubling::Redoubled), 7, false), 230);
+        // 1NTX+1 有身價 = 80 + 50 + 200 + 50
+        assert_eq!(score_contract(&contract(1, Strain::NoTrump, Doubling::Doubled), 8, true), 380);
+    }
+
+    #[test]
+    fn test_slams() {
+        assert_eq!(score_contract(&contract(6, Strain::NoTrump, Doubling::Undoubled), 12, true), 1440);
+        assert_eq!(score_contract(&contract(7, Strain::Clubs, Doubling::Undoubled), 13, false), 1440);
+        assert_eq!(score_contract(&contract(6, Strain::Spades, Doubling::Undoubled), 13, false), 1010);
+    }
+
+    #[test]
+    fn test_undertricks() {
+        let four_hearts = contract(4, Strain::Hearts, Doubling::Undoubled);
+        assert_eq!(score_contract(&four_hearts, 8, false), -100);
+        assert_eq!(score_contract(&four_hearts, 8, true), -200);
+
+        let doubled = contract(4, Strain::Hearts, Doubling::Doubled);
+        assert_eq!(score_contract(&doubled, 9, true), -200);
+        assert_eq!(score_contract(&doubled, 7, false), -500);
+        assert_eq!(score_contract(&doubled, 6, false), -800);
+        assert_eq!(score_contract(&doubled, 7, true), -800);
+
+        let redoubled = contract(4, Strain::Hearts, Doubling::Redoubled);
+        assert_eq!(score_contract(&redoubled, 8, false), -600);
+    }
+
+    #[test]
+    fn test_result_string() {
+        let four_spades = contract(4, Strain::Spades, Doubling::Undoubled);
+        assert_eq!(result_string(&four_spades, 10), "4S=");
+        assert_eq!(result_string(&four_spades, 11), "4S+1");
+        assert_eq!(result_string(&contract(3, Strain::NoTrump, Doubling::Doubled), 7), "3NTX-2");
+    }
+
+    #[test]
+    fn test_vulnerability_for_board() {
+        assert_eq!(Vulnerability::for_board(1), Vulnerability::None);
+        assert_eq!(Vulnerability::for_board(2), Vulnerability::NorthSouth);
+        assert_eq!(Vulnerability::for_board(4), Vulnerability::Both);
+        assert_eq!(Vulnerability::for_board(16), Vulnerability::EastWest);
+        assert_eq!(Vulnerability::for_board(17), Vulnerability::None);
+
+        assert!(Vulnerability::NorthSouth.is_vulnerable(Team::Human));
+        assert!(!Vulnerability::NorthSouth.is_vulnerable(Team::Ai));
+        assert!(Vulnerability::Both.is_vulnerable(Team::Ai));
+    }
+}
@@ -0,0 +1,799 @@
+//! 雙明手解算 (Double Dummy Solver)
+//!
+//! 四家手牌都已知時，求出雙方都以最佳打法進行時各能拿到的墩數。
+//! 搜尋為 alpha-beta 的布林視窗版本 (「A 隊能否至少拿到 k 墩」)，由二分逼近實際墩數；
+//! 每個 trick 開始時的局面存入置換表 (transposition table) 記錄墩數上下界，
+//! 同一家手中相連的牌 (中間沒有其他家還留著的牌) 只搜尋一張，
+//! 出牌依「領出大牌、便宜地贏下 trick、同伴已贏時跟小牌」排序以提早剪枝。
+//!
+//! 座位輪流順序與引擎相同 (index 遞增)，跟牌與王牌規則同 TrickDuel；只適用 4 人搭檔制。
+
+use super::auction::Strain;
+use super::cardset::CardSet;
+use super::deck::{CardData, Suit};
+use super::engine::{GameEngine, GamePhase};
+use super::pbn::{self, PbnError};
+use crate::protocol::Team;
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::fmt;
+
+/// 解算表的王牌順序
+pub const STRAINS: [Strain; 5] = [Strain::NoTrump, Strain::Spades, Strain::Hearts, Strain::Diamonds, Strain::Clubs];
+
+/// 無法解算的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolverError {
+    /// 只支援 4 人
+    WrongPlayerCount(usize),
+    /// 規則不是搭檔制 (e.g., Hearts)
+    NotPartnership(&'static str),
+    /// 還沒發牌或不在出牌階段
+    NotPlaying,
+    /// 各家剩餘張數與桌面不一致 (或同一張牌出現兩次)
+    InconsistentHands,
+    /// PBN 牌型格式錯誤
+    Pbn(PbnError),
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::WrongPlayerCount(n) => write!(f, "double dummy needs 4 players, got {}", n),
+            SolverError::NotPartnership(rules) => write!(f, "{} is not a partnership game", rules),
+            SolverError::NotPlaying => write!(f, "no cards in play"),
+            SolverError::InconsistentHands => write!(f, "hands do not match the cards on the table"),
+            SolverError::Pbn(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<PbnError> for SolverError {
+    fn from(e: PbnError) -> Self {
+        SolverError::Pbn(e)
+    }
+}
+
+/// 四家手牌已知的局面
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Position {
+    /// 各座位 (引擎的 player index) 剩下的手牌
+    pub hands: [CardSet; 4],
+    /// 各座位的隊伍
+    pub teams: [Team; 4],
+    pub trump: Option<Suit>,
+    /// 本 trick 已出的牌 (player_idx, card)，第一張為領牌
+    pub table: Vec<(usize, CardData)>,
+    /// 本 trick 的領牌者
+    pub leader: usize,
+}
+
+impl Position {
+    /// trick 開始前的局面
+    pub fn new(hands: [CardSet; 4], teams: [Team; 4], trump: Option<Suit>, leader: usize) -> Self {
+        Self {
+            hands,
+            teams,
+            trump,
+            table: Vec::new(),
+            leader,
+        }
+    }
+
+    /// 引擎目前的出牌局面
+    pub fn from_engine(engine: &GameEngine) -> Result<Self, SolverError> {
+        let (_, teams) = dealt_hands(engine)?;
+        let leader = match (&engine.phase, engine.table.first()) {
+            (_, Some(&(idx, _))) => idx,
+            (GamePhase::WaitingForPlay { current_player_idx }, None) => *current_player_idx,
+            (GamePhase::GameOver, None) => engine.last_trick_winner.unwrap_or(0),
+            _ => return Err(SolverError::NotPlaying),
+        };
+        let hands = [0, 1, 2, 3].map(|i| engine.players[i].hand);
+        let position = Self {
+            hands,
+            teams,
+            trump: engine.trump,
+            table: engine.table.clone(),
+            leader,
+        };
+        position.validate()?;
+        Ok(position)
+    }
+
+    /// 下一個出牌的座位
+    pub fn to_move(&self) -> usize {
+        (self.leader + self.table.len()) % 4
+    }
+
+    /// 還沒打完的 trick 數 (含本 trick)
+    pub fn tricks_left(&self) -> u32 {
+        self.hands[self.leader].len() + !self.table.is_empty() as u32
+    }
+
+    /// 檢查桌面的出牌順序、各家張數與牌是否重複
+    fn validate(&self) -> Result<(), SolverError> {
+        let n = self.tricks_left();
+        let mut seen = CardSet::EMPTY;
+        for seat in 0..4 {
+            let played = self.table.iter().any(|&(idx, _)| idx == seat) as u32;
+            if self.hands[seat].len() + played != n || !(seen & self.hands[seat]).is_empty() {
+                return Err(SolverError::InconsistentHands);
+            }
+            seen |= self.hands[seat];
+        }
+        for (i, &(idx, card)) in self.table.iter().enumerate() {
+            if idx != (self.leader + i) % 4 || seen.contains(card) {
+                return Err(SolverError::InconsistentHands);
+            }
+            seen.insert(card);
+        }
+        Ok(())
+    }
+}
+
+/// 引擎這副牌發到的手牌與各座位的隊伍
+pub fn dealt_hands(engine: &GameEngine) -> Result<([CardSet; 4], [Team; 4]), SolverError> {
+    if engine.players.len() != 4 {
+        return Err(SolverError::WrongPlayerCount(engine.players.len()));
+    }
+    if !engine.rules.partnership() {
+        return Err(SolverError::NotPartnership(engine.rules.name()));
+    }
+    if engine.dealt_hands.len() != 4 {
+        return Err(SolverError::NotPlaying);
+    }
+    let hands = [0, 1, 2, 3].map(|i| engine.dealt_hands[i]);
+    let teams = [0, 1, 2, 3].map(|i| engine.players[i].team);
+    Ok((hands, teams))
+}
+
+/// PBN `[Deal]` 的手牌依引擎的座位對應排好 (N, S 為 HUMAN 的 index 0, 1；E, W 為 AI 的 index 2, 3)
+pub fn pbn_hands(deal: &str) -> Result<([CardSet; 4], [Team; 4]), SolverError> {
+    let [north, east, south, west] = pbn::parse_deal(deal)?;
+    let hands = [&north, &south, &east, &west].map(|hand| hand.iter().collect());
+    Ok((hands, [Team::Human, Team::Human, Team::Ai, Team::Ai]))
+}
+
+/// PBN 座位在引擎 index 順序下的名稱 (見 [`pbn_hands`])
+pub const PBN_SEAT_LABELS: [&str; 4] = ["N", "S", "E", "W"];
+
+/// 局面中 `team` 還能拿到的墩數 (含本 trick)
+pub fn solve(position: &Position, team: Team) -> Result<u32, SolverError> {
+    Solver::new(position.trump, position.teams).solve(position, team)
+}
+
+/// 同一副牌、同一王牌下連續解算多個局面 (置換表沿用，e.g., 賽後逐張重播)
+pub struct Solver {
+    search: Search,
+    trump: Option<Suit>,
+    teams: [Team; 4],
+    /// 上一個局面 A 隊的剩餘墩數 (下一次搜尋的起點)
+    guess: Option<u32>,
+}
+
+impl Solver {
+    pub fn new(trump: Option<Suit>, teams: [Team; 4]) -> Self {
+        Self {
+            search: Search::new(trump, teams),
+            trump,
+            teams,
+            guess: None,
+        }
+    }
+
+    /// 局面中 `team` 還能拿到的墩數 (含本 trick)
+    pub fn solve(&mut self, position: &Position, team: Team) -> Result<u32, SolverError> {
+        position.validate()?;
+        if position.trump != self.trump || position.teams != self.teams {
+            *self = Solver::new(position.trump, position.teams);
+        }
+        let won = self.search.load(position);
+        let guess = self.guess.unwrap_or(position.tricks_left() / 2);
+        let tricks_a = won + self.search.best(guess);
+        self.guess = Some(tricks_a);
+        Ok(if team == position.teams[0] {
+            tricks_a
+        } else {
+            position.tricks_left() - tricks_a
+        })
+    }
+}
+
+/// 雙明手墩數表：各王牌 (NT, S, H, D, C) 由各座位首引時 HUMAN 隊拿到的墩數
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrickTable {
+    pub tricks: u32,
+    /// `human[strain][leader]`，王牌順序同 [`STRAINS`]
+    pub human: [[u32; 4]; 5],
+}
+
+impl TrickTable {
+    /// `team` 在某王牌、某座位首引時拿到的墩數
+    pub fn tricks_for(&self, team: Team, strain: Strain, leader: usize) -> u32 {
+        let s = STRAINS.iter().position(|&x| x == strain).unwrap_or(0);
+        match team {
+            Team::Human => self.human[s][leader],
+            Team::Ai => self.tricks - self.human[s][leader],
+        }
+    }
+
+    /// 管理介面用的表格 (每列為一個首引座位，數字為 HUMAN-AI 墩數)
+    pub fn format(&self, labels: [&str; 4], teams: [Team; 4]) -> String {
+        let mut out = format!("{:<14}", "Leader");
+        for strain in STRAINS {
+            out.push_str(&format!("{:>7}", strain.symbol()));
+        }
+        out.push('\n');
+        for leader in 0..4 {
+            let name = format!("{} ({})", labels[leader], format!("{:?}", teams[leader]).to_uppercase());
+            out.push_str(&format!("{:<14}", name));
+            for strain in STRAINS {
+                let (human, ai) = (self.tricks_for(Team::Human, strain, leader), self.tricks_for(Team::Ai, strain, leader));
+                out.push_str(&format!("{:>7}", format!("{}-{}", human, ai)));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// 完整一副牌的雙明手墩數表
+pub fn trick_table(hands: [CardSet; 4], teams: [Team; 4]) -> Result<TrickTable, SolverError> {
+    let mut table = TrickTable {
+        tricks: hands[0].len(),
+        human: [[0; 4]; 5],
+    };
+    for (s, strain) in STRAINS.iter().enumerate() {
+        // 同一王牌的置換表可沿用到每個首引座位
+        let mut search = Search::new(strain.trump(), teams);
+        let mut guess = table.tricks / 2;
+        for leader in 0..4 {
+            let position = Position::new(hands, teams, strain.trump(), leader);
+            position.validate()?;
+            search.load(&position);
+            let tricks_a = search.best(guess);
+            guess = tricks_a;
+            table.human[s][leader] = if teams[0] == Team::Human {
+                tricks_a
+            } else {
+                table.tricks - tricks_a
+            };
+        }
+    }
+    Ok(table)
+}
+
+/// 置換表的 key (相對大小的四家手牌, 領牌者)
+type TtKey = ([u64; 4], u8);
+
+/// 置換表用的簡單 hash (key 本身已是分散的位元，不需要 SipHash 的抗碰撞)
+#[derive(Default)]
+struct KeyHasher(u64);
+
+impl Hasher for KeyHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.write_u64(b as u64);
+        }
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = (self.0.rotate_left(5) ^ n).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+}
+
+/// 搜尋狀態 (手牌與桌面以 bit 位置表示，A 隊為座位 0 的隊伍)
+struct Search {
+    trump: Option<u32>,
+    side_a: [bool; 4],
+    /// trick 開始時的局面 → A 隊剩餘墩數的 (下界, 上界)
+    tt: HashMap<TtKey, (u8, u8), BuildHasherDefault<KeyHasher>>,
+    hands: [u64; 4],
+    table: [u32; 4],
+    table_len: usize,
+    leader: usize,
+}
+
+impl Search {
+    fn new(trump: Option<Suit>, teams: [Team; 4]) -> Self {
+        Self {
+            trump: trump.map(|s| s as u32),
+            side_a: teams.map(|t| t == teams[0]),
+            tt: HashMap::default(),
+            hands: [0; 4],
+            table: [0; 4],
+            table_len: 0,
+            leader: 0,
+        }
+    }
+
+    /// 載入局面；桌面已滿 4 張時先結算，回傳 A 隊因此拿到的墩數
+    fn load(&mut self, position: &Position) -> u32 {
+        self.hands = position.hands.map(|h| h.0);
+        self.leader = position.leader;
+        self.table_len = position.table.len();
+        for (i, &(_, card)) in position.table.iter().enumerate() {
+            self.table[i] = CardSet::index(card);
+        }
+        if self.table_len == 4 {
+            let winner = self.trick_winner();
+            self.leader = winner;
+            self.table_len = 0;
+            return self.side_a[winner] as u32;
+        }
+        0
+    }
+
+    /// A 隊從目前局面能拿到的最多墩數 (從 guess 附近開始試，猜得準時只需兩次搜尋)
+    fn best(&mut self, guess: u32) -> u32 {
+        let (mut lo, mut hi) = (0, self.hands[self.to_move()].count_ones());
+        let mut next = guess.clamp(lo + 1, hi.max(1));
+        while lo < hi {
+            let target = next.clamp(lo + 1, hi);
+            if self.search(target) {
+                lo = target;
+                next = target + 1;
+            } else {
+                hi = target - 1;
+                next = target - 1;
+            }
+            // 第一次之後若沒有落在答案旁邊就改用二分
+            if next != lo + 1 && next != hi {
+                next = (lo + hi).div_ceil(2);
+            }
+        }
+        lo
+    }
+
+    fn to_move(&self) -> usize {
+        (self.leader + self.table_len) % 4
+    }
+
+    /// c 是否壓過 w (不同花色時只有王牌壓得過)
+    fn beats(&self, c: u32, w: u32) -> bool {
+        if c / 13 == w / 13 {
+            c > w
+        } else {
+            self.trump == Some(c / 13)
+        }
+    }
+
+    /// 目前桌面最大的牌在 table 中的位置
+    fn winning_slot(&self) -> usize {
+        (1..self.table_len).fold(0, |best, i| {
+            if self.beats(self.table[i], self.table[best]) { i } else { best }
+        })
+    }
+
+    fn trick_winner(&self) -> usize {
+        (self.leader + self.winning_slot()) % 4
+    }
+
+    /// A 隊能否在剩下的 trick (含本 trick) 中至少拿到 target 墩
+    fn search(&mut self, target: u32) -> bool {
+        if target == 0 {
+            return true;
+        }
+        let seat = self.to_move();
+        let remaining = self.hands[seat].count_ones();
+        if target > remaining {
+            return false;
+        }
+
+        let key = (self.table_len == 0).then(|| self.tt_key());
+        if let Some(key) = &key {
+            if let Some(&(lower, upper)) = self.tt.get(key) {
+                if lower as u32 >= target {
+                    return true;
+                }
+                if (upper as u32) < target {
+                    return false;
+                }
+            }
+            // 領牌方連續的大牌是穩拿的墩數；另一方持有最大的王牌也穩拿一墩
+            let sure = self.quick_tricks();
+            let other_trump = self.top_trump_owner().is_some_and(|owner| self.side_a[owner] != self.side_a[seat]) as u32;
+            let (sure_a, sure_b) = if self.side_a[seat] { (sure, other_trump) } else { (other_trump, sure) };
+            if sure_a >= target {
+                return true;
+            }
+            if target > remaining - sure_b {
+                return false;
+            }
+        }
+
+        let maximizing = self.side_a[seat];
+        let mut moves = [0u32; 13];
+        let n = self.moves(seat, &mut moves);
+        let mut result = !maximizing;
+        for &card in &moves[..n] {
+            let bit = 1u64 << card;
+            self.hands[seat] ^= bit;
+            self.table[self.table_len] = card;
+            self.table_len += 1;
+
+            let ok = if self.table_len == 4 {
+                let (leader, table) = (self.leader, self.table);
+                let winner = self.trick_winner();
+                self.leader = winner;
+                self.table_len = 0;
+                let ok = self.search(target - self.side_a[winner] as u32);
+                self.leader = leader;
+                self.table = table;
+                self.table_len = 4;
+                ok
+            } else {
+                self.search(target)
+            };
+
+            self.table_len -= 1;
+            self.hands[seat] ^= bit;
+            if ok == maximizing {
+                result = ok;
+                break;
+            }
+        }
+
+        if let Some(key) = key {
+            let bounds = self.tt.entry(key).or_insert((0, remaining as u8));
+            if result {
+                bounds.0 = bounds.0.max(target as u8);
+            } else {
+                bounds.1 = bounds.1.min(target as u8 - 1);
+            }
+        }
+        result
+    }
+
+    /// 置換表的 key：每個花色只保留還在場上的牌之間的相對大小，
+    /// 已出的牌不同但相對位置相同的局面共用同一筆
+    fn tt_key(&self) -> TtKey {
+        let mut key = [0u64; 4];
+        let live = self.hands.iter().fold(0, |acc, h| acc | h);
+        for suit in 0..4 {
+            let base = suit * 13;
+            let mut cards = (live >> base) & 0x1FFF;
+            let mut slot = base + 12;
+            while cards != 0 {
+                let card = base + 31 - (cards as u32).leading_zeros();
+                cards &= !(1 << (card - base));
+                let owner = self.hands.iter().position(|h| h & (1 << card) != 0).unwrap_or(0);
+                key[owner] |= 1 << slot;
+                slot -= 1;
+            }
+        }
+        (key, self.leader as u8)
+    }
+
+    /// 領牌方可以連續兌現的大牌數：領牌者自己由最大張連下來的牌，
+    /// 或先領小牌到同伴的大牌、再由同伴兌現 (有王牌時副牌的張數不超過持有王牌的對手在該花色的張數)
+    fn quick_tricks(&self) -> u32 {
+        let leader = self.leader;
+        let (own, _) = self.winners_of(leader);
+        let partner = (leader + 1..leader + 4).map(|i| i % 4).find(|&i| self.side_a[i] == self.side_a[leader]);
+        match partner {
+            Some(partner) => {
+                let (via_partner, suits) = self.winners_of(partner);
+                let entry = (0..4).any(|suit| suits[suit] > 0 && self.hands[leader] & (0x1FFF << (suit * 13)) != 0);
+                if entry { own.max(via_partner) } else { own }
+            }
+            None => own,
+        }
+    }
+
+    /// 某一家在各花色比其他三家都大、且不會被對手王吃的張數 (合計, 各花色)
+    fn winners_of(&self, seat: usize) -> (u32, [u32; 4]) {
+        let hand = self.hands[seat];
+        let mut others = 0;
+        let mut ruff_limit = [13u32; 4];
+        for (i, &h) in self.hands.iter().enumerate() {
+            if i == seat {
+                continue;
+            }
+            others |= h;
+            if let Some(t) = self.trump
+                && self.side_a[i] != self.side_a[seat]
+                && h & (0x1FFF << (t * 13)) != 0
+            {
+                for (suit, limit) in ruff_limit.iter_mut().enumerate() {
+                    if suit as u32 != t {
+                        *limit = (*limit).min((h & (0x1FFF << (suit * 13))).count_ones());
+                    }
+                }
+            }
+        }
+
+        let mut per_suit = [0u32; 4];
+        for (suit, count) in per_suit.iter_mut().enumerate() {
+            let mask = 0x1FFFu64 << (suit * 13);
+            let mine = hand & mask;
+            if mine == 0 {
+                continue;
+            }
+            let top_other = others & mask;
+            let winners = if top_other == 0 {
+                mine.count_ones()
+            } else {
+                (mine >> (64 - top_other.leading_zeros())).count_ones()
+            };
+            *count = winners.min(ruff_limit[suit]);
+        }
+        (per_suit.iter().sum::<u32>().min(hand.count_ones()), per_suit)
+    }
+
+    /// 場上最大的王牌在誰手上
+    fn top_trump_owner(&self) -> Option<usize> {
+        let mask = 0x1FFFu64 << (self.trump? * 13);
+        let live = self.hands.iter().fold(0, |acc, h| acc | h) & mask;
+        if live == 0 {
+            return None;
+        }
+        let top = 1u64 << (63 - live.leading_zeros());
+        self.hands.iter().position(|h| h & top != 0)
+    }
+
+    /// 依序排好的候選出牌，回傳張數
+    fn moves(&self, seat: usize, out: &mut [u32; 13]) -> usize {
+        let hand = self.hands[seat];
+        let led = (self.table_len > 0).then(|| self.table[0] / 13);
+        let playable = match led {
+            Some(suit) if hand & (0x1FFF << (suit * 13)) != 0 => hand & (0x1FFF << (suit * 13)),
+            _ => hand,
+        };
+
+        // 還在場上的牌 (手牌與本 trick 桌面)，用來判斷相連的牌
+        let mut live = self.hands.iter().fold(0, |acc, h| acc | h);
+        for &card in &self.table[..self.table_len] {
+            live |= 1 << card;
+        }
+        let winning = (self.table_len > 0).then(|| {
+            let slot = self.winning_slot();
+            let winner = (self.leader + slot) % 4;
+            let partner_winning = self.side_a[winner] == self.side_a[seat];
+            (self.table[slot], partner_winning && !self.beaten_later(seat, self.table[slot]))
+        });
+
+        let mut scored = [(0i32, 0u32); 13];
+        let mut n = 0;
+        for suit in 0..4 {
+            let base = suit * 13;
+            if (playable >> base) & 0x1FFF == 0 {
+                continue;
+            }
+            let mut prev_mine = false;
+            for rank in (0..13).rev() {
+                let card = base + rank;
+                if live & (1 << card) == 0 {
+                    continue;
+                }
+                let mine = playable & (1 << card) != 0;
+                if mine && !prev_mine {
+                    let score = match winning {
+                        None => self.lead_score(seat, card),
+                        Some((best, partner_safe)) => self.follow_score(seat, card, best, partner_safe),
+                    };
+                    scored[n] = (score, card);
+                    n += 1;
+                }
+                prev_mine = mine;
+            }
+        }
+
+        scored[..n].sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        for (slot, &(_, card)) in out.iter_mut().zip(&scored[..n]) {
+            *slot = card;
+        }
+        n
+    }
+
+    /// 本 trick 之後出牌的對手能否壓過這張牌
+    fn beaten_later(&self, seat: usize, card: u32) -> bool {
+        let led = if self.table_len > 0 { self.table[0] / 13 } else { card / 13 };
+        let led_mask = 0x1FFFu64 << (led * 13);
+        let suit = card / 13;
+        (self.table_len + 1..4).map(|k| (self.leader + k) % 4).any(|other| {
+            if self.side_a[other] == self.side_a[seat] {
+                return false;
+            }
+            let hand = self.hands[other];
+            let legal = if hand & led_mask != 0 { hand & led_mask } else { hand };
+            let same = legal & (0x1FFF << (suit * 13));
+            if same != 0 && 63 - same.leading_zeros() > card {
+                return true;
+            }
+            matches!(self.trump, Some(t) if t != suit && legal & (0x1FFF << (t * 13)) != 0)
+        })
+    }
+
+    /// 領牌的排序分數 (越大越先搜尋)：先兌現穩贏的牌，其次領小牌給同伴的大牌，最後從小牌領出
+    fn lead_score(&self, seat: usize, card: u32) -> i32 {
+        let rank = (card % 13) as i32;
+        let suit_mask = 0x1FFFu64 << (card / 13 * 13);
+        if !self.beaten_later(seat, card) {
+            return 100 + rank;
+        }
+        let partner = (0..4).find(|&i| i != seat && self.side_a[i] == self.side_a[seat]).unwrap_or(seat);
+        let partner_top = self.hands[partner] & suit_mask;
+        if partner_top != 0 && !self.beaten_later(seat, 63 - partner_top.leading_zeros()) {
+            return 80 - rank;
+        }
+        40 - rank
+    }
+
+    /// 跟牌的排序分數：同伴穩贏時跟小牌，否則先試穩贏的最小張，再試逼出對手大牌，最後墊小牌
+    fn follow_score(&self, seat: usize, card: u32, best: u32, partner_safe: bool) -> i32 {
+        let rank = (card % 13) as i32;
+        let wins = self.beats(card, best);
+        match (partner_safe, wins) {
+            (true, false) => 90 - rank,
+            (true, true) => -rank,
+            (false, true) if !self.beaten_later(seat, card) => 80 - rank,
+            (false, true) => 30 + rank,
+            (false, false) => 20 - rank,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::deck::Deck;
+    use crate::game::rules::{RuleKind, trick_winner};
+    use crate::game::testing;
+
+    const TEAMS: [Team; 4] = [Team::Human, Team::Human, Team::Ai, Team::Ai];
+
+    /// 以 seed 洗牌後每家取 n 張
+    fn random_hands(seed: u64, n: usize) -> [Vec<CardData>; 4] {
+        let mut deck = Deck::new();
+        deck.shuffle(seed);
+        let hands = deck.deal(4, 13);
+        [0, 1, 2, 3].map(|i| hands[i][..n].to_vec())
+    }
+
+    fn sets(hands: &[Vec<CardData>; 4]) -> [CardSet; 4] {
+        [0, 1, 2, 3].map(|i| hands[i].iter().collect())
+    }
+
+    fn set(cards: &[&str]) -> CardSet {
+        cards.iter().map(|s| card(s)).collect()
+    }
+
+    /// 不剪枝的完整 minimax，回傳 HUMAN 隊的墩數
+    fn brute_force(hands: &mut [Vec<CardData>; 4], table: &mut Vec<(usize, CardData)>, leader: usize, trump: Option<Suit>) -> u32 {
+        let seat = (leader + table.len()) % 4;
+        if hands[seat].is_empty() {
+            return 0;
+        }
+        let led = table.first().map(|(_, c)| c.suit);
+        let follow: Vec<CardData> = hands[seat].iter().copied().filter(|c| Some(c.suit) == led).collect();
+        let legal = if follow.is_empty() { hands[seat].clone() } else { follow };
+        let values = legal.into_iter().map(|card| {
+            hands[seat].retain(|&c| c != card);
+            table.push((seat, card));
+            let value = if table.len() == 4 {
+                let winner = trick_winner(table, trump);
+                let saved = std::mem::take(table);
+                let v = (TEAMS[winner] == Team::Human) as u32 + brute_force(hands, table, winner, trump);
+                *table = saved;
+                v
+            } else {
+                brute_force(hands, table, leader, trump)
+            };
+            table.pop();
+            hands[seat].push(card);
+            value
+        });
+        let values: Vec<u32> = values.collect();
+        if TEAMS[seat] == Team::Human {
+            values.into_iter().max().unwrap()
+        } else {
+            values.into_iter().min().unwrap()
+        }
+    }
+
+    fn card(s: &str) -> CardData {
+        CardData::from_protocol_string(s).unwrap()
+    }
+
+    #[test]
+    fn test_matches_brute_force_on_endings() {
+        for seed in 0..12 {
+            let hands = random_hands(seed, 4);
+            for strain in STRAINS {
+                for leader in 0..4 {
+                    let position = Position::new(sets(&hands), TEAMS, strain.trump(), leader);
+                    let mut vecs = hands.clone();
+                    let expected = brute_force(&mut vecs, &mut Vec::new(), leader, strain.trump());
+                    assert_eq!(solve(&position, Team::Human).unwrap(), expected, "seed {} {:?} leader {}", seed, strain, leader);
+                    assert_eq!(solve(&position, Team::Ai).unwrap(), 4 - expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_trick_table_matches_single_solves() {
+        let hands = sets(&random_hands(7, 5));
+        let table = trick_table(hands, TEAMS).unwrap();
+        assert_eq!(table.tricks, 5);
+        for strain in STRAINS {
+            for leader in 0..4 {
+                let position = Position::new(hands, TEAMS, strain.trump(), leader);
+                assert_eq!(table.tricks_for(Team::Human, strain, leader), solve(&position, Team::Human).unwrap());
+                assert_eq!(table.tricks_for(Team::Ai, strain, leader), solve(&position, Team::Ai).unwrap());
+            }
+        }
+        let text = table.format(PBN_SEAT_LABELS, TEAMS);
+        assert!(text.starts_with("Leader"));
+        assert_eq!(text.lines().count(), 5);
+    }
+
+    #[test]
+    fn test_trump_and_mid_trick_positions() {
+        // P1 拿 AS KS，P3 黑桃缺門但有王牌：無王時 HUMAN 兩墩，紅心王牌時 P3 王吃後 AI 拿下兩墩
+        let hands = [set(&["AS", "KS"]), set(&["2C", "3C"]), set(&["2H", "4D"]), set(&["5D", "6D"])];
+        let nt = Position::new(hands, TEAMS, None, 0);
+        assert_eq!(solve(&nt, Team::Human).unwrap(), 2);
+        let hearts = Position::new(hands, TEAMS, Some(Suit::Hearts), 0);
+        assert_eq!(solve(&hearts, Team::Human).unwrap(), 0);
+
+        // 桌面已有 AS、2C：本 trick 仍計入
+        let mut mid = hearts.clone();
+        mid.hands[0] = set(&["KS"]);
+        mid.hands[1] = set(&["3C"]);
+        mid.table = vec![(0, card("AS")), (1, card("2C"))];
+        assert_eq!(mid.to_move(), 2);
+        assert_eq!(mid.tricks_left(), 2);
+        assert_eq!(solve(&mid, Team::Ai).unwrap(), 2);
+
+        // 桌面滿 4 張時先結算
+        let mut full = nt.clone();
+        full.hands = [set(&["KS"]), set(&["3C"]), set(&["4D"]), set(&["6D"])];
+        full.table = vec![(0, card("AS")), (1, card("2C")), (2, card("2H")), (3, card("5D"))];
+        assert_eq!(solve(&full, Team::Human).unwrap(), 2);
+
+        // 張數不一致
+        let mut broken = nt.clone();
+        broken.hands[3] = set(&["5D"]);
+        assert_eq!(solve(&broken, Team::Human), Err(SolverError::InconsistentHands));
+    }
+
+    #[test]
+    fn test_engine_positions() {
+        let mut engine = testing::new_engine(42);
+        engine.deal();
+        let (dealt, teams) = dealt_hands(&engine).unwrap();
+        assert_eq!(teams, TEAMS);
+        assert_eq!(dealt[0].len(), 13);
+
+        // 打完 10 個 trick 再出一張時，局面與引擎一致，雙方墩數合計為剩下的 trick 數
+        testing::play_first_legal(&mut engine, 41);
+        let position = Position::from_engine(&engine).unwrap();
+        assert_eq!(position.table.len(), 1);
+        assert_eq!(position.tricks_left(), 3);
+        let human = solve(&position, Team::Human).unwrap();
+        assert_eq!(human + solve(&position, Team::Ai).unwrap(), 3);
+
+        let mut hearts = testing::new_engine(42);
+        hearts.rules = RuleKind::Hearts.build();
+        hearts.deal();
+        assert!(matches!(dealt_hands(&hearts), Err(SolverError::NotPartnership(_))));
+    }
+
+    #[test]
+    fn test_pbn_hands_follow_engine_seats() {
+        let deal = "N:AKQJ.T98.765.432 T98.765.432.AKQJ 765.432.AKQJ.T98 432.AKQJ.T98.765";
+        let (hands, teams) = pbn_hands(deal).unwrap();
+        assert_eq!(teams, TEAMS);
+        // index 1 為 S、index 2 為 E
+        assert!(hands[1].contains(card("AD")));
+        assert!(hands[2].contains(card("AC")));
+        assert!(matches!(pbn_hands("N:AK"), Err(SolverError::Pbn(_))));
+    }
+}
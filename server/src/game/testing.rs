@@ -0,0 +1,38 @@
+//! 測試共用的牌局 (4 人：P1, P2 為 HUMAN 隊，P3, P4 為 AI 隊)
+
+use super::engine::{GameEngine, PlayResult};
+use crate::net::ConnectionId;
+use crate::protocol::{PlayerId, Team};
+
+/// 4 個座位 (conn_id 1..=4)
+pub fn four_players() -> Vec<(ConnectionId, PlayerId, Team)> {
+    vec![
+        (1, "P1".to_string(), Team::Human),
+        (2, "P2".to_string(), Team::Human),
+        (3, "P3".to_string(), Team::Ai),
+        (4, "P4".to_string(), Team::Ai),
+    ]
+}
+
+/// 4 人牌局 (尚未發牌)
+pub fn new_engine(seed: u64) -> GameEngine {
+    GameEngine::new(seed, four_players())
+}
+
+/// 依序替輪到的座位出第一張合法牌，trick 出完就結算
+pub fn play_first_legal(engine: &mut GameEngine, count: usize) {
+    for _ in 0..count {
+        let idx = engine.current_player_idx().expect("no seat to play");
+        let card = engine.get_legal_moves(idx).first().unwrap();
+        if let PlayResult::TrickComplete(_) = engine.play_card(idx, card) {
+            engine.resolve_trick();
+        }
+    }
+}
+
+/// 出第一張合法牌直到這副牌結束
+pub fn play_out(engine: &mut GameEngine) {
+    while engine.current_player_idx().is_some() {
+        play_first_legal(engine, 1);
+    }
+}
@@ -0,0 +1,109 @@
+//! 出牌計時器
+//!
+//! 追蹤每個房間目前等待中的 YOUR_TURN 期限，由 game loop 定期檢查。
+
+use crate::protocol::RoomId;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// 單一房間的出牌期限
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TurnDeadline {
+    /// 等待出牌的玩家 index
+    pub player_idx: usize,
+    /// 發出 YOUR_TURN 時的 trick 編號
+    pub trick: u32,
+    /// 到期時間
+    pub expires_at: Instant,
+}
+
+/// 出牌計時器 (room_id -> deadline)
+#[derive(Debug, Default)]
+pub struct TurnTimer {
+    deadlines: HashMap<RoomId, TurnDeadline>,
+}
+
+impl TurnTimer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 設定房間的出牌期限 (覆蓋舊的期限)
+    pub fn arm(&mut self, room_id: &str, player_idx: usize, trick: u32, timeout: Duration) {
+        self.arm_at(room_id, player_idx, trick, Instant::now() + timeout);
+    }
+
+    /// 以指定到期時間設定期限
+    pub fn arm_at(&mut self, room_id: &str, player_idx: usize, trick: u32, expires_at: Instant) {
+        self.deadlines.insert(
+            room_id.to_string(),
+            TurnDeadline {
+                player_idx,
+                trick,
+                expires_at,
+            },
+        );
+    }
+
+    /// 取消房間的出牌期限
+    pub fn disarm(&mut self, room_id: &str) -> Option<TurnDeadline> {
+        self.deadlines.remove(room_id)
+    }
+
+    /// 取得房間目前的期限
+    pub fn get(&self, room_id: &str) -> Option<&TurnDeadline> {
+        self.deadlines.get(room_id)
+    }
+
+    /// 取出所有已到期的期限 (到期者會被移除)
+    pub fn take_expired(&mut self, now: Instant) -> Vec<(RoomId, TurnDeadline)> {
+        let expired: Vec<RoomId> = self
+            .deadlines
+            .iter()
+            .filter(|(_, d)| d.expires_at <= now)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        expired
+            .into_iter()
+            .filter_map(|id| self.deadlines.remove(&id).map(|d| (id, d)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_expired() {
+        let mut timer = TurnTimer::new();
+        let now = Instant::now();
+
+        timer.arm_at("R001", 0, 1, now);
+        timer.arm_at("R002", 1, 3, now + Duration::from_secs(30));
+
+        let expired = timer.take_expired(now);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].0, "R001");
+        assert_eq!(expired[0].1.player_idx, 0);
+
+        // 已到期的會被移除，未到期的保留
+        assert!(timer.get("R001").is_none());
+        assert!(timer.get("R002").is_some());
+    }
+
+    #[test]
+    fn test_rearm_and_disarm() {
+        let mut timer = TurnTimer::new();
+        let now = Instant::now();
+
+        timer.arm_at("R001", 0, 1, now);
+        timer.arm_at("R001", 2, 1, now + Duration::from_secs(30));
+        assert_eq!(timer.get("R001").unwrap().player_idx, 2);
+        assert!(timer.take_expired(now).is_empty());
+
+        assert!(timer.disarm("R001").is_some());
+        assert!(timer.disarm("R001").is_none());
+    }
+}
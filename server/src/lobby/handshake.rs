@@ -14,6 +14,7 @@ pub enum HandshakeResult {
 }
 
 /// 驗證並處理 HELLO 訊息
+#[allow(clippy::too_many_arguments)]
 pub fn process_hello(
     role: &Role,
     nickname: &str,
@@ -37,7 +38,7 @@ pub fn process_hello(
 
     // 驗證暱稱長度
     let nickname_len = nickname.chars().count();
-    if nickname_len < MIN_NICKNAME_LEN || nickname_len > MAX_NICKNAME_LEN {
+    if !(MIN_NICKNAME_LEN..=MAX_NICKNAME_LEN).contains(&nickname_len) {
         return HandshakeResult::Error(ServerMessage::Error {
             code: ErrorCode::InvalidHello,
             message: format!(
@@ -48,25 +49,25 @@ pub fn process_hello(
     }
 
     // AI 角色需要驗證 token
-    if *role == Role::Ai {
-        if let Some(expected_token) = ai_auth_token {
-            match auth {
-                Some(token) if token == expected_token => {}
-                Some(_) => {
-                    return HandshakeResult::Error(ServerMessage::Error {
-                        code: ErrorCode::AuthFailed,
-                        message: "Invalid AI authentication token".to_string(),
-                    });
-                }
-                None => {
-                    return HandshakeResult::Error(ServerMessage::Error {
-                        code: ErrorCode::AuthFailed,
-                        message: "AI client requires authentication token".to_string(),
-                    });
-                }
+    // 如果沒有設定 ai_auth_token，則不需驗證 (開發模式)
+    if *role == Role::Ai
+        && let Some(expected_token) = ai_auth_token
+    {
+        match auth {
+            Some(token) if token == expected_token => {}
+            Some(_) => {
+                return HandshakeResult::Error(ServerMessage::Error {
+                    code: ErrorCode::AuthFailed,
+                    message: "Invalid AI authentication token".to_string(),
+                });
+            }
+            None => {
+                return HandshakeResult::Error(ServerMessage::Error {
+                    code: ErrorCode::AuthFailed,
+                    message: "AI client requires authentication token".to_string(),
+                });
             }
         }
-        // 如果沒有設定 ai_auth_token，則不需驗證 (開發模式)
     }
 
     // 處理暱稱重複
@@ -79,6 +80,7 @@ pub fn process_hello(
         player_id,
         nickname: final_nickname,
         room: room_id.to_string(),
+        resume_token: None,
     })
 }
 
@@ -1,5 +1,7 @@
 pub mod handshake;
 pub mod room;
+pub mod session;
 
 pub use handshake::{HandshakeResult, process_hello};
-pub use room::{Room, RoomManager, RoomState};
+pub use room::{Room, RoomConfig, RoomManager, RoomState};
+pub use session::SessionStore;
@@ -1,28 +1,30 @@
 use crate::ai::AiPlayer;
+use crate::game::match_state::default_first_board;
+use crate::game::{BoardSource, DealConstraints, DealSeed, MAX_SEATS, RuleKind, Suit, TieBreak, UndoPolicy};
 use crate::net::ConnectionId;
 use crate::protocol::{PlayerInfo, Role, RoomId, ServerMessage, Team};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 
-const MAX_PLAYERS: usize = 4;
-const REQUIRED_HUMANS: usize = 2;
-
-/// 虛擬連線 ID (用於內建 AI 玩家)
+/// 虛擬連線 ID (用於內建 AI 玩家) 的起點：ConnectionId 最大的 MAX_SEATS 個值
 /// AI 玩家不佔用真實 TCP 連線
-pub const AI_VIRTUAL_CONN_ID_1: ConnectionId = ConnectionId::MAX - 1;
-pub const AI_VIRTUAL_CONN_ID_2: ConnectionId = ConnectionId::MAX;
+const AI_VIRTUAL_CONN_BASE: ConnectionId = ConnectionId::MAX - (MAX_SEATS as ConnectionId - 1);
 
 /// 玩家狀態
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Player {
     pub conn_id: ConnectionId,
     pub player_id: String,
     pub nickname: String,
     pub role: Role,
     pub team: Option<Team>,
+    /// 是否在線 (斷線等待續連時為 false)
+    pub connected: bool,
 }
 
 /// 房間狀態
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RoomState {
     /// 等待玩家加入
     Waiting,
@@ -33,49 +35,214 @@ pub enum RoomState {
     Finished,
 }
 
+/// 房間遊戲設定 (建立房間時套用)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomConfig {
+    /// 王牌花色 (None = 無王)
+    pub trump: Option<Suit>,
+    /// 出牌前先叫牌 (王牌由合約決定，忽略 trump)
+    pub auction: bool,
+    /// 一場比賽的副數
+    pub boards: u32,
+    /// 出牌規則
+    pub rules: RuleKind,
+    /// 手牌來源 (洗牌或預設牌組)
+    #[serde(default)]
+    pub board_source: BoardSource,
+    /// 比賽第一副的牌號
+    #[serde(default = "default_first_board")]
+    pub first_board: u32,
+    /// 每副牌結束後送出賽後分析 (ANALYSIS)
+    #[serde(default)]
+    pub analysis: bool,
+    /// 一副牌分數平手時的勝負判定
+    #[serde(default)]
+    pub tie_break: TieBreak,
+    /// 座位數 (2-6)
+    #[serde(default = "default_seats")]
+    pub seats: usize,
+    /// 每人手牌張數 (None = 依規則發完整副牌)
+    #[serde(default)]
+    pub hand_size: Option<usize>,
+    /// 悔牌範圍
+    #[serde(default)]
+    pub undo: UndoPolicy,
+    /// 練習房：內建 AI 對手自動同意悔牌，不需要真人對手同意
+    #[serde(default)]
+    pub practice: bool,
+    /// 管理員 CONSTRAIN 之前的手牌來源 (CONSTRAIN OFF 時還原)
+    #[serde(default)]
+    pub replaced_source: Option<BoardSource>,
+}
+
+fn default_seats() -> usize {
+    4
+}
+
+impl Default for RoomConfig {
+    fn default() -> Self {
+        Self {
+            trump: None,
+            auction: false,
+            boards: 1,
+            rules: RuleKind::default(),
+            board_source: BoardSource::default(),
+            first_board: default_first_board(),
+            analysis: false,
+            tie_break: TieBreak::default(),
+            seats: default_seats(),
+            hand_size: None,
+            undo: UndoPolicy::default(),
+            practice: false,
+            replaced_source: None,
+        }
+    }
+}
+
+/// 房間設定錯誤 (啟動時拒絕)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// 規則不支援這個座位數
+    UnsupportedSeats { rules: &'static str, seats: usize },
+    /// 每人手牌張數超過整副牌平分的張數
+    HandSizeTooLarge { hand_size: usize, max: usize },
+    /// 規則需要發完整副牌，不能設定 HAND_SIZE
+    ShortHandsUnsupported(&'static str),
+    /// 預設牌組與條件發牌只適用 4 人、每人 13 張
+    PresetNeedsFullDeal,
+    /// 牌組缺少比賽要打的牌號
+    MissingBoard(u32),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::UnsupportedSeats { rules, seats } => write!(f, "{} does not support {} seats", rules, seats),
+            ConfigError::HandSizeTooLarge { hand_size, max } => {
+                write!(f, "hand size {} exceeds {} cards per seat", hand_size, max)
+            }
+            ConfigError::ShortHandsUnsupported(rules) => write!(f, "{} must deal full hands", rules),
+            ConfigError::PresetNeedsFullDeal => write!(f, "DEAL_FILE and DEAL_CONSTRAINTS need 4 seats with 13 cards each"),
+            ConfigError::MissingBoard(b) => write!(f, "deal set has no board {}", b),
+        }
+    }
+}
+
+impl RoomConfig {
+    /// 檢查設定組合 (座位數、手牌張數與手牌來源要能搭配規則，牌組涵蓋比賽的每一副)
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let rules = self.rules.build();
+        if !rules.supports_seats(self.seats) {
+            return Err(ConfigError::UnsupportedSeats { rules: rules.name(), seats: self.seats });
+        }
+        let full = rules.cards_per_player(self.seats);
+        if let Some(hand_size) = self.hand_size.filter(|&n| n != full) {
+            if !rules.supports_short_hands() {
+                return Err(ConfigError::ShortHandsUnsupported(rules.name()));
+            }
+            if hand_size > full {
+                return Err(ConfigError::HandSizeTooLarge { hand_size, max: full });
+            }
+        }
+        let full_deal = self.seats == 4 && self.hand_size.is_none_or(|n| n == full);
+        if !matches!(self.board_source, BoardSource::Shuffle) && !full_deal {
+            return Err(ConfigError::PresetNeedsFullDeal);
+        }
+        if let BoardSource::DealSet(set) = &self.board_source
+            && let Some(board) = (self.first_board..self.first_board + self.boards).find(|&b| set.get(b).is_none())
+        {
+            return Err(ConfigError::MissingBoard(board));
+        }
+        Ok(())
+    }
+
+    /// 是否在出牌前叫牌 (只適用 4 人搭檔制規則)
+    pub fn auction_enabled(&self) -> bool {
+        self.auction && self.seats == 4 && self.rules.build().partnership()
+    }
+
+    /// 真人隊伍的座位數 (前半，座位數為奇數時多一席)
+    pub fn human_seats(&self) -> usize {
+        self.seats.div_ceil(2)
+    }
+
+    /// 管理員設定條件發牌 (CONSTRAIN)；None 還原成設定條件之前的手牌來源
+    pub fn set_constraints(&mut self, constraints: Option<DealConstraints>) {
+        match constraints {
+            Some(c) => {
+                let previous = std::mem::replace(&mut self.board_source, BoardSource::Constrained(c));
+                self.replaced_source.get_or_insert(previous);
+            }
+            None => {
+                self.board_source = match self.replaced_source.take() {
+                    Some(previous) => previous,
+                    None if matches!(self.board_source, BoardSource::Constrained(_)) => BoardSource::Shuffle,
+                    None => return,
+                };
+            }
+        }
+    }
+
+    /// 開局時已確定的王牌 (叫牌模式下尚未決定；非搭檔制規則沒有王牌)
+    pub fn fixed_trump(&self) -> Option<Suit> {
+        if self.auction || !self.rules.build().partnership() { None } else { self.trump }
+    }
+}
+
 /// 房間
+#[derive(Serialize, Deserialize)]
 pub struct Room {
     pub id: RoomId,
     pub state: RoomState,
     pub players: Vec<Player>,
     pub nicknames: HashSet<String>,
-    pub seed: u64,
+    /// 目前這副牌的 seed 與 server secret (ROOM_START 只公布承諾值)
+    pub deal_seed: DealSeed,
     /// Bridge Mode: Server 內建 2 AI，等待 2 Human 加入
     pub bridge_mode: bool,
+    /// 遊戲設定
+    pub config: RoomConfig,
 }
 
 impl Room {
-    /// 建立新房間 (傳統模式: 等待 4 人)
-    pub fn new(id: impl Into<String>) -> Self {
-        Self {
-            id: id.into(),
-            state: RoomState::Waiting,
-            players: Vec::with_capacity(MAX_PLAYERS),
-            nicknames: HashSet::new(),
-            seed: generate_seed(),
-            bridge_mode: false,
-        }
-    }
-
-    /// 建立 Bridge Mode 房間 (內建 2 AI，等待 2 Human)
-    pub fn new_bridge_mode(id: impl Into<String>) -> Self {
+    /// 依設定建立房間 (Bridge Mode 時內建 AI 坐滿後半的座位，等待真人坐滿前半)
+    pub fn with_config(id: impl Into<String>, bridge_mode: bool, config: RoomConfig) -> Self {
         let mut room = Self {
             id: id.into(),
             state: RoomState::Waiting,
-            players: Vec::with_capacity(MAX_PLAYERS),
+            players: Vec::with_capacity(config.seats),
             nicknames: HashSet::new(),
-            seed: generate_seed(),
-            bridge_mode: true,
+            deal_seed: DealSeed::generate(),
+            bridge_mode,
+            config,
         };
 
-        // 預先加入 2 個內建 AI (P3, P4 位置)
-        let (ai1, ai2) = AiPlayer::create_partners();
-        room.add_builtin_ai(&ai1, AI_VIRTUAL_CONN_ID_1);
-        room.add_builtin_ai(&ai2, AI_VIRTUAL_CONN_ID_2);
+        if bridge_mode {
+            // 預先加入內建 AI (4 人時為 P3, P4 位置)
+            let ai_players = AiPlayer::create_team(room.config.human_seats(), room.ai_seats());
+            for (i, ai) in ai_players.iter().enumerate() {
+                room.add_builtin_ai(ai, Self::virtual_conn_id(i, ai_players.len()));
+            }
+        }
 
         room
     }
 
+    /// 第 index 個內建 AI (共 count 個) 的虛擬連線 ID (最後一個為 ConnectionId::MAX)
+    fn virtual_conn_id(index: usize, count: usize) -> ConnectionId {
+        ConnectionId::MAX - (count - 1 - index) as ConnectionId
+    }
+
+    /// 座位數
+    pub fn seats(&self) -> usize {
+        self.config.seats
+    }
+
+    /// 內建 AI 的座位數 (Bridge Mode)
+    fn ai_seats(&self) -> usize {
+        self.seats() - self.config.human_seats()
+    }
+
     /// 加入內建 AI 玩家
     fn add_builtin_ai(&mut self, ai: &AiPlayer, virtual_conn_id: ConnectionId) {
         let player = Player {
@@ -84,6 +251,7 @@ impl Room {
             nickname: ai.nickname.clone(),
             role: Role::Ai,
             team: Some(Team::Ai), // AI 隊伍固定
+            connected: true,
         };
         self.nicknames.insert(ai.nickname.clone());
         self.players.push(player);
@@ -92,17 +260,17 @@ impl Room {
     /// 檢查房間是否已滿
     pub fn is_full(&self) -> bool {
         if self.bridge_mode {
-            // Bridge Mode: 2 humans = full
-            self.human_count() >= REQUIRED_HUMANS
+            // Bridge Mode: 真人坐滿前半座位 = full
+            self.human_count() >= self.config.human_seats()
         } else {
-            self.players.len() >= MAX_PLAYERS
+            self.players.len() >= self.seats()
         }
     }
 
-    /// 取得下一個 player slot (1-4)
+    /// 取得下一個 player slot (1-based)
     pub fn next_slot(&self) -> u32 {
         if self.bridge_mode {
-            // Bridge Mode: Human 分配 P1, P2
+            // Bridge Mode: Human 分配 P1, P2, ...
             (self.human_count() + 1) as u32
         } else {
             (self.players.len() + 1) as u32
@@ -133,6 +301,7 @@ impl Room {
             nickname: nickname.to_string(),
             role,
             team,
+            connected: true,
         };
         self.nicknames.insert(nickname.to_string());
 
@@ -156,24 +325,45 @@ impl Room {
         }
     }
 
+    /// 設定座位的在線狀態
+    pub fn set_connected(&mut self, player_id: &str, connected: bool) {
+        if let Some(player) = self.players.iter_mut().find(|p| p.player_id == player_id) {
+            player.connected = connected;
+        }
+    }
+
+    /// 將座位綁定到新的連線，回傳舊的 conn_id
+    pub fn rebind_conn(&mut self, player_id: &str, new_conn_id: ConnectionId) -> Option<ConnectionId> {
+        let player = self.players.iter_mut().find(|p| p.player_id == player_id)?;
+        let old_conn_id = player.conn_id;
+        player.conn_id = new_conn_id;
+        player.connected = true;
+        Some(old_conn_id)
+    }
+
+    /// 是否有座位斷線 (遊戲暫停中)
+    pub fn is_paused(&self) -> bool {
+        self.players.iter().any(|p| !p.connected)
+    }
+
     /// 取得需要的玩家數量
     pub fn players_needed(&self) -> u32 {
         if self.bridge_mode {
             // Bridge Mode: 需要幾個 Human
-            (REQUIRED_HUMANS - self.human_count()) as u32
+            self.config.human_seats().saturating_sub(self.human_count()) as u32
         } else {
-            (MAX_PLAYERS - self.players.len()) as u32
+            self.seats().saturating_sub(self.players.len()) as u32
         }
     }
 
     /// 檢查是否可以開始遊戲
     pub fn can_start(&self) -> bool {
         if self.bridge_mode {
-            // Bridge Mode: 2 Humans 即可開始
-            self.human_count() >= REQUIRED_HUMANS
+            // Bridge Mode: 真人坐滿前半座位即可開始
+            self.human_count() >= self.config.human_seats()
         } else {
-            // 傳統模式: 4 人且至少 1 個 HUMAN
-            if self.players.len() != MAX_PLAYERS {
+            // 傳統模式: 坐滿且至少 1 個 HUMAN
+            if self.players.len() != self.seats() {
                 return false;
             }
             self.players.iter().any(|p| p.role == Role::Human)
@@ -188,9 +378,10 @@ impl Room {
         }
 
         // 傳統模式: HUMAN 和 AI 各一隊
-        // 依加入順序分配 (前 2 人 Human 隊，後 2 人 AI 隊)
+        // 依加入順序分配 (前半 Human 隊，後半 AI 隊)
+        let human_seats = self.config.human_seats();
         for (i, player) in self.players.iter_mut().enumerate() {
-            player.team = Some(if i < 2 { Team::Human } else { Team::Ai });
+            player.team = Some(if i < human_seats { Team::Human } else { Team::Ai });
         }
     }
 
@@ -204,11 +395,15 @@ impl Room {
     }
 
     /// 產生 ROOM_START 訊息
-    pub fn room_start_message(&self) -> ServerMessage {
+    pub fn room_start_message(&self, board: u32) -> ServerMessage {
         ServerMessage::RoomStart {
             room: self.id.clone(),
             players: self.players.iter().map(|p| p.to_player_info()).collect(),
-            seed: self.seed,
+            seed_commitment: self.deal_seed.commitment(),
+            trump: self.config.fixed_trump().map(|s| s.symbol().to_string()),
+            rules: self.config.rules.build().name().to_string(),
+            board,
+            deal_set: self.config.board_source.deal_set_name().map(str::to_string),
         }
     }
 
@@ -229,13 +424,13 @@ impl Room {
 
     /// 檢查是否為虛擬連線 (內建 AI)
     pub fn is_virtual_conn(conn_id: ConnectionId) -> bool {
-        conn_id == AI_VIRTUAL_CONN_ID_1 || conn_id == AI_VIRTUAL_CONN_ID_2
+        conn_id >= AI_VIRTUAL_CONN_BASE
     }
 
     /// 檢查 player_id 是否為內建 AI - 預留供未來擴充
     #[allow(dead_code)]
     pub fn is_builtin_ai(&self, player_id: &str) -> bool {
-        player_id == "P3" || player_id == "P4"
+        self.find_player_by_id(player_id).is_some_and(|p| Self::is_virtual_conn(p.conn_id))
     }
 
     /// 透過 conn_id 找玩家
@@ -245,11 +440,16 @@ impl Room {
     }
 
     /// 透過 player_id 找玩家
-    #[allow(dead_code)]
     pub fn find_player_by_id(&self, player_id: &str) -> Option<&Player> {
         self.players.iter().find(|p| p.player_id == player_id)
     }
 
+    /// 重新產生 seed 與 secret (重新發牌時)
+    pub fn reseed(&mut self) -> DealSeed {
+        self.deal_seed = DealSeed::generate();
+        self.deal_seed.clone()
+    }
+
     /// 重置房間 (Bridge Mode 專用)
     /// 移除所有 Human 玩家，保留 AI，重置狀態為 Waiting
     pub fn reset_for_bridge_mode(&mut self) -> Vec<ConnectionId> {
@@ -275,7 +475,7 @@ impl Room {
         self.state = RoomState::Waiting;
 
         // 重新產生 seed
-        self.seed = generate_seed();
+        self.deal_seed = DealSeed::generate();
 
         human_conn_ids
     }
@@ -300,6 +500,8 @@ pub struct RoomManager {
     next_room_id: u32,
     /// 是否啟用 Bridge Mode (預設 true)
     pub bridge_mode: bool,
+    /// 新房間套用的遊戲設定
+    pub default_config: RoomConfig,
 }
 
 impl RoomManager {
@@ -309,6 +511,7 @@ impl RoomManager {
             conn_to_room: HashMap::new(),
             next_room_id: 1,
             bridge_mode: true, // 預設啟用 Bridge Mode
+            default_config: RoomConfig::default(),
         }
     }
 
@@ -320,6 +523,7 @@ impl RoomManager {
             conn_to_room: HashMap::new(),
             next_room_id: 1,
             bridge_mode: false,
+            default_config: RoomConfig::default(),
         }
     }
 
@@ -339,15 +543,19 @@ impl RoomManager {
         // 建立新房間
         let room_id = format!("R{:03}", self.next_room_id);
         self.next_room_id += 1;
-        let room = if self.bridge_mode {
-            Room::new_bridge_mode(&room_id)
-        } else {
-            Room::new(&room_id)
-        };
+        let room = Room::with_config(&room_id, self.bridge_mode, self.default_config.clone());
         self.rooms.insert(room_id.clone(), room);
         self.rooms.get_mut(&room_id).unwrap()
     }
 
+    /// 加入從快照還原的房間 (新房間編號接在其後)
+    pub fn restore_room(&mut self, room: Room) {
+        if let Some(n) = room.id.strip_prefix('R').and_then(|n| n.parse::<u32>().ok()) {
+            self.next_room_id = self.next_room_id.max(n + 1);
+        }
+        self.rooms.insert(room.id.clone(), room);
+    }
+
     /// 將連線關聯到房間
     pub fn associate_conn(&mut self, conn_id: ConnectionId, room_id: &str) {
         self.conn_to_room.insert(conn_id, room_id.to_string());
@@ -373,16 +581,15 @@ impl RoomManager {
 
     /// 處理連線斷開
     pub fn handle_disconnect(&mut self, conn_id: ConnectionId) -> Option<Player> {
-        if let Some(room_id) = self.conn_to_room.remove(&conn_id) {
-            if let Some(room) = self.rooms.get_mut(&room_id) {
-                return room.remove_player(conn_id);
-            }
+        if let Some(room_id) = self.conn_to_room.remove(&conn_id)
+            && let Some(room) = self.rooms.get_mut(&room_id)
+        {
+            return room.remove_player(conn_id);
         }
         None
     }
 
     /// 透過 ID 取得房間
-    #[allow(dead_code)]
     pub fn get_room(&self, room_id: &str) -> Option<&Room> {
         self.rooms.get(room_id)
     }
@@ -457,22 +664,13 @@ impl Default for RoomManager {
     }
 }
 
-/// 產生隨機 seed
-fn generate_seed() -> u64 {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_nanos() as u64)
-        .unwrap_or(12345)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_room_creation() {
-        let room = Room::new("R001");
+        let room = Room::with_config("R001", false, RoomConfig::default());
         assert_eq!(room.id, "R001");
         assert_eq!(room.state, RoomState::Waiting);
         assert!(room.players.is_empty());
@@ -482,7 +680,7 @@ mod tests {
 
     #[test]
     fn test_add_players() {
-        let mut room = Room::new("R001");
+        let mut room = Room::with_config("R001", false, RoomConfig::default());
 
         room.add_player(1, "P1", "Alice", Role::Human);
         assert_eq!(room.players.len(), 1);
@@ -498,7 +696,7 @@ mod tests {
 
     #[test]
     fn test_cannot_start_without_human() {
-        let mut room = Room::new("R001");
+        let mut room = Room::with_config("R001", false, RoomConfig::default());
         room.add_player(1, "P1", "Bot1", Role::Ai);
         room.add_player(2, "P2", "Bot2", Role::Ai);
         room.add_player(3, "P3", "Bot3", Role::Ai);
@@ -525,7 +723,7 @@ mod tests {
 
     #[test]
     fn test_bridge_mode_room_creation() {
-        let room = Room::new_bridge_mode("R001");
+        let room = Room::with_config("R001", true, RoomConfig::default());
 
         assert!(room.bridge_mode);
         assert_eq!(room.players.len(), 2); // AI 已預先加入
@@ -539,7 +737,7 @@ mod tests {
 
     #[test]
     fn test_bridge_mode_add_humans() {
-        let mut room = Room::new_bridge_mode("R001");
+        let mut room = Room::with_config("R001", true, RoomConfig::default());
 
         // 加入第一個 Human
         room.add_player(1, "P1", "Alice", Role::Human);
@@ -565,9 +763,49 @@ mod tests {
         assert_eq!(room.players[3].player_id, "P4");
     }
 
+    #[test]
+    fn test_bridge_mode_seat_counts() {
+        // 6 人桌：P4-P6 為內建 AI，等待 3 位真人
+        let config = RoomConfig { seats: 6, ..RoomConfig::default() };
+        let mut room = Room::with_config("R001", true, config);
+        let ids: Vec<_> = room.players.iter().map(|p| p.player_id.as_str()).collect();
+        assert_eq!(ids, ["P4", "P5", "P6"]);
+        assert!(room.players.iter().all(|p| Room::is_virtual_conn(p.conn_id)));
+        assert_eq!(room.players_needed(), 3);
+        for (i, name) in ["Alice", "Bob", "Carol"].iter().enumerate() {
+            assert_eq!(room.next_slot(), i as u32 + 1);
+            room.add_player(i as ConnectionId + 1, &format!("P{}", i + 1), name, Role::Human);
+        }
+        assert!(room.can_start());
+        assert!(room.is_builtin_ai("P6") && !room.is_builtin_ai("P3"));
+
+        // 2 人桌：1 位真人對 1 個 AI；3 人桌真人隊多一席
+        let config = RoomConfig { seats: 2, auction: true, ..RoomConfig::default() };
+        let room = Room::with_config("R002", true, config);
+        assert_eq!(room.players[0].player_id, "P2");
+        assert_eq!(room.players[0].conn_id, ConnectionId::MAX);
+        assert_eq!(room.players_needed(), 1);
+        assert!(!room.config.auction_enabled());
+        assert_eq!(RoomConfig { seats: 3, ..RoomConfig::default() }.human_seats(), 2);
+    }
+
+    #[test]
+    fn test_classic_room_seat_count() {
+        let config = RoomConfig { seats: 3, ..RoomConfig::default() };
+        let mut room = Room::with_config("R001", false, config);
+        room.add_player(1, "P1", "Alice", Role::Human);
+        room.add_player(2, "P2", "Bob", Role::Ai);
+        assert!(!room.can_start());
+        room.add_player(3, "P3", "Carol", Role::Ai);
+        assert!(room.is_full() && room.can_start());
+        room.assign_teams();
+        let teams: Vec<_> = room.players.iter().map(|p| p.team).collect();
+        assert_eq!(teams, [Some(Team::Human), Some(Team::Human), Some(Team::Ai)]);
+    }
+
     #[test]
     fn test_bridge_mode_real_conn_ids() {
-        let mut room = Room::new_bridge_mode("R001");
+        let mut room = Room::with_config("R001", true, RoomConfig::default());
         room.add_player(1, "P1", "Alice", Role::Human);
         room.add_player(2, "P2", "Bob", Role::Human);
 
@@ -591,7 +829,7 @@ mod tests {
 
     #[test]
     fn test_bridge_mode_reset() {
-        let mut room = Room::new_bridge_mode("R001");
+        let mut room = Room::with_config("R001", true, RoomConfig::default());
 
         // 加入 2 個 Human
         room.add_player(1, "P1", "Alice", Role::Human);
@@ -612,4 +850,144 @@ mod tests {
         assert_eq!(room.state, RoomState::Waiting);
         assert_eq!(room.players_needed(), 2);
     }
+
+    #[test]
+    fn test_manager_applies_default_config() {
+        let mut manager = RoomManager::new();
+        manager.default_config.trump = Some(Suit::Hearts);
+
+        let room = manager.get_or_create_waiting_room();
+        assert_eq!(room.config.trump, Some(Suit::Hearts));
+
+        match room.room_start_message(1) {
+            ServerMessage::RoomStart { trump, board, deal_set, .. } => {
+                assert_eq!(trump.as_deref(), Some("H"));
+                assert_eq!(board, 1);
+                assert_eq!(deal_set, None);
+            }
+            _ => panic!("Expected RoomStart"),
+        }
+    }
+
+    #[test]
+    fn test_rebind_conn_resumes_seat() {
+        let mut room = Room::with_config("R001", true, RoomConfig::default());
+        room.add_player(1, "P1", "Alice", Role::Human);
+        room.add_player(2, "P2", "Bob", Role::Human);
+
+        room.set_connected("P1", false);
+        assert!(room.is_paused());
+
+        assert_eq!(room.rebind_conn("P1", 7), Some(1));
+        assert!(!room.is_paused());
+        assert_eq!(room.find_player(7).unwrap().player_id, "P1");
+        assert!(room.find_player(1).is_none());
+        assert!(room.rebind_conn("P9", 8).is_none());
+    }
+
+    #[test]
+    fn test_restore_room_keeps_numbering() {
+        let mut manager = RoomManager::new();
+        let mut room = Room::with_config("R007", true, RoomConfig::default());
+        room.state = RoomState::Playing;
+        manager.restore_room(room);
+
+        assert_eq!(manager.get_or_create_waiting_room().id, "R008");
+        assert!(manager.get_room("R007").is_some());
+    }
+
+    #[test]
+    fn test_constrain_off_restores_deal_set() {
+        let set = crate::game::boards::DealSet {
+            name: "club".to_string(),
+            boards: Vec::new(),
+        };
+        let mut config = RoomConfig {
+            board_source: BoardSource::DealSet(set.clone()),
+            ..RoomConfig::default()
+        };
+        let first = DealConstraints::parse("N:hcp=15-17").unwrap();
+        let second = DealConstraints::parse("E:S=6+").unwrap();
+        config.set_constraints(Some(first));
+        config.set_constraints(Some(second));
+        assert_eq!(config.board_source, BoardSource::Constrained(second));
+        config.set_constraints(None);
+        assert_eq!(config.board_source, BoardSource::DealSet(set.clone()));
+        // 沒有被取代的來源時 OFF 不動牌組
+        config.set_constraints(None);
+        assert_eq!(config.board_source, BoardSource::DealSet(set));
+
+        // 啟動時設定的條件發牌改回洗牌
+        let mut config = RoomConfig {
+            board_source: BoardSource::Constrained(first),
+            ..RoomConfig::default()
+        };
+        config.set_constraints(None);
+        assert_eq!(config.board_source, BoardSource::Shuffle);
+    }
+
+    #[test]
+    fn test_validate_rejects_unsupported_seats() {
+        let config = RoomConfig { seats: 3, ..RoomConfig::default() };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::UnsupportedSeats { rules: "TRICK_DUEL", seats: 3 })
+        );
+        let config = RoomConfig { seats: 6, ..RoomConfig::default() };
+        assert_eq!(config.validate(), Ok(()));
+        let config = RoomConfig { rules: RuleKind::Hearts, seats: 6, ..RoomConfig::default() };
+        assert_eq!(config.validate(), Err(ConfigError::UnsupportedSeats { rules: "HEARTS", seats: 6 }));
+    }
+
+    #[test]
+    fn test_validate_hand_size_and_source() {
+        let mut config = RoomConfig { seats: 6, hand_size: Some(8), ..RoomConfig::default() };
+        assert_eq!(config.validate(), Ok(()));
+        config.hand_size = Some(9);
+        assert_eq!(config.validate(), Err(ConfigError::HandSizeTooLarge { hand_size: 9, max: 8 }));
+
+        // HEARTS 要發完整副牌
+        let config = RoomConfig { rules: RuleKind::Hearts, hand_size: Some(5), ..RoomConfig::default() };
+        assert_eq!(config.validate(), Err(ConfigError::ShortHandsUnsupported("HEARTS")));
+        let config = RoomConfig { rules: RuleKind::Hearts, hand_size: Some(13), ..RoomConfig::default() };
+        assert_eq!(config.validate(), Ok(()));
+
+        // 條件發牌只產生 4 人 13 張的牌
+        let constraints = DealConstraints::parse("N:hcp=10-20").unwrap();
+        let mut config = RoomConfig {
+            board_source: BoardSource::Constrained(constraints),
+            hand_size: Some(5),
+            ..RoomConfig::default()
+        };
+        assert_eq!(config.validate(), Err(ConfigError::PresetNeedsFullDeal));
+        config.hand_size = None;
+        assert_eq!(config.validate(), Ok(()));
+        config.seats = 2;
+        assert_eq!(config.validate(), Err(ConfigError::PresetNeedsFullDeal));
+    }
+
+    #[test]
+    fn test_validate_deal_set_covers_match() {
+        let mut deal = crate::game::pbn::PbnDeal {
+            board: Some(2),
+            dealer: None,
+            hands: Default::default(),
+        };
+        let mut config = RoomConfig {
+            board_source: BoardSource::DealSet(crate::game::boards::DealSet {
+                name: "club".to_string(),
+                boards: vec![deal.clone()],
+            }),
+            first_board: 2,
+            ..RoomConfig::default()
+        };
+        assert_eq!(config.validate(), Ok(()));
+        config.boards = 2;
+        assert_eq!(config.validate(), Err(ConfigError::MissingBoard(3)));
+        deal.board = Some(3);
+        if let BoardSource::DealSet(set) = &mut config.board_source {
+            set.boards.push(deal);
+        }
+        assert_eq!(config.validate(), Ok(()));
+    }
 }
@@ -0,0 +1,207 @@
+//! 座位續連 (Resume Token)
+//!
+//! WELCOME 時發給每位真人玩家一組 token；遊戲中斷線後，
+//! 在寬限期內以 HELLO 帶上 token 即可取回原座位。
+
+use crate::game::fairness;
+use crate::protocol::{PlayerId, RoomId};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// token 的位元組數 (hex 後 32 字元)
+const TOKEN_BYTES: usize = 16;
+
+/// token 對應的座位
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeatClaim {
+    pub room_id: RoomId,
+    pub player_id: PlayerId,
+}
+
+/// 續連 token 管理
+#[derive(Debug, Default)]
+pub struct SessionStore {
+    /// 已發出的 token
+    tokens: HashMap<String, SeatClaim>,
+    /// 座位已空、等待續連的 token (token -> 寬限期到期時間)
+    pending: HashMap<String, Instant>,
+}
+
+impl SessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 為座位發出新 token (同座位的舊 token 失效)
+    pub fn issue(&mut self, room_id: &str, player_id: &str) -> String {
+        self.revoke(room_id, player_id);
+
+        let token = generate_token();
+        self.tokens.insert(
+            token.clone(),
+            SeatClaim {
+                room_id: room_id.to_string(),
+                player_id: player_id.to_string(),
+            },
+        );
+        token
+    }
+
+    /// 還原快照中的 token (Server 重啟後讓原玩家取回座位)
+    pub fn restore(&mut self, token: &str, room_id: &str, player_id: &str) {
+        self.revoke(room_id, player_id);
+        self.tokens.insert(
+            token.to_string(),
+            SeatClaim {
+                room_id: room_id.to_string(),
+                player_id: player_id.to_string(),
+            },
+        );
+    }
+
+    /// 取得座位目前的 token
+    pub fn token_for(&self, room_id: &str, player_id: &str) -> Option<String> {
+        self.tokens
+            .iter()
+            .find(|(_, c)| c.room_id == room_id && c.player_id == player_id)
+            .map(|(t, _)| t.clone())
+    }
+
+    /// 座位斷線，開始寬限期
+    pub fn mark_disconnected(&mut self, room_id: &str, player_id: &str, grace: Duration) -> bool {
+        match self.token_for(room_id, player_id) {
+            Some(token) => {
+                self.pending.insert(token, Instant::now() + grace);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 以 token 取回座位 (必須在寬限期內)
+    pub fn claim(&mut self, token: &str, now: Instant) -> Option<SeatClaim> {
+        match self.pending.get(token) {
+            Some(expires_at) if *expires_at > now => {
+                self.pending.remove(token);
+                self.tokens.get(token).cloned()
+            }
+            _ => None,
+        }
+    }
+
+    /// 取出寬限期已過的座位 (token 一併失效)
+    pub fn take_expired(&mut self, now: Instant) -> Vec<SeatClaim> {
+        let expired: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, expires_at)| **expires_at <= now)
+            .map(|(t, _)| t.clone())
+            .collect();
+
+        expired
+            .into_iter()
+            .filter_map(|token| {
+                self.pending.remove(&token);
+                self.tokens.remove(&token)
+            })
+            .collect()
+    }
+
+    /// 撤銷單一座位的 token
+    pub fn revoke(&mut self, room_id: &str, player_id: &str) {
+        if let Some(token) = self.token_for(room_id, player_id) {
+            self.tokens.remove(&token);
+            self.pending.remove(&token);
+        }
+    }
+
+    /// 撤銷整個房間的 token (房間重置時)
+    pub fn revoke_room(&mut self, room_id: &str) {
+        let pending = &mut self.pending;
+        self.tokens.retain(|token, claim| {
+            if claim.room_id == room_id {
+                pending.remove(token);
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+/// 產生不可預測的 token (與發牌的 seed 相同，讀取作業系統的隨機來源)
+fn generate_token() -> String {
+    fairness::random_hex(TOKEN_BYTES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_unique_tokens() {
+        let mut store = SessionStore::new();
+        let t1 = store.issue("R001", "P1");
+        let t2 = store.issue("R001", "P2");
+
+        assert_eq!(t1.len(), 32);
+        assert_ne!(t1, t2);
+        assert_eq!(store.token_for("R001", "P1"), Some(t1));
+    }
+
+    #[test]
+    fn test_claim_within_grace() {
+        let mut store = SessionStore::new();
+        let token = store.issue("R001", "P1");
+
+        // 尚未斷線的座位不能被取回
+        assert!(store.claim(&token, Instant::now()).is_none());
+
+        store.mark_disconnected("R001", "P1", Duration::from_secs(60));
+        let claim = store.claim(&token, Instant::now()).unwrap();
+        assert_eq!(claim.room_id, "R001");
+        assert_eq!(claim.player_id, "P1");
+
+        // token 可重複使用 (再次斷線時)
+        assert!(store.mark_disconnected("R001", "P1", Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_claim_after_grace_fails() {
+        let mut store = SessionStore::new();
+        let token = store.issue("R001", "P1");
+        store.mark_disconnected("R001", "P1", Duration::ZERO);
+
+        let now = Instant::now();
+        assert!(store.claim(&token, now).is_none());
+
+        let expired = store.take_expired(now);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].player_id, "P1");
+        assert!(store.token_for("R001", "P1").is_none());
+    }
+
+    #[test]
+    fn test_restore_token() {
+        let mut store = SessionStore::new();
+        store.restore("feedface", "R003", "P2");
+        assert_eq!(store.token_for("R003", "P2"), Some("feedface".to_string()));
+
+        store.mark_disconnected("R003", "P2", Duration::from_secs(60));
+        let claim = store.claim("feedface", Instant::now()).unwrap();
+        assert_eq!(claim.room_id, "R003");
+    }
+
+    #[test]
+    fn test_revoke_room() {
+        let mut store = SessionStore::new();
+        store.issue("R001", "P1");
+        store.issue("R001", "P2");
+        store.issue("R002", "P1");
+
+        store.revoke_room("R001");
+        assert!(store.token_for("R001", "P1").is_none());
+        assert!(store.token_for("R001", "P2").is_none());
+        assert!(store.token_for("R002", "P1").is_some());
+    }
+}
@@ -3,42 +3,150 @@ mod ai;
 mod game;
 mod lobby;
 mod net;
+mod persist;
 mod protocol;
 
 use admin::{
     spawn_admin_server, AdminConfig, AdminEvent, AdminResponse, GameLogger, PlayerInfo, RoomInfo,
 };
-use ai::{AiStrategy, SmartStrategy};
-use game::{CardData, GameEngine, PlayError, PlayResult, TrickResolution};
-use lobby::{HandshakeResult, Room, RoomManager, RoomState, process_hello};
+use ai::{AiStrategy, HeartsStrategy, SmartStrategy};
+use game::boards::BoardError;
+use game::{analysis, claim, fairness, pbn, solver};
+use game::{
+    AuctionError, AuctionResult, Bid, BoardSource, Call, CardData, CardSet, ClaimError, ClaimOutcome, DealConstraints, DealSeed, DealSet, GameEngine, HandEvaluation, MatchState, PassError, PassResult,
+    RuleKind, Suit, TieBreak, UndoPolicy, PlayError, PlayResult, TrickResolution, TurnTimer, UndoError, UndoOutcome,
+};
+use lobby::{HandshakeResult, Room, RoomConfig, RoomManager, RoomState, SessionStore, process_hello};
 use log::{error, info, warn};
 use net::{
     ClientSender, ConnectionId, GameEvent, create_event_channel, create_heartbeat_tracker,
     next_connection_id, spawn_handler, spawn_heartbeat_server,
 };
+use persist::{RoomSnapshot, SnapshotStore};
 use protocol::{ClientMessage, ErrorCode, RejectReason, RoomId, ServerMessage};
 use std::collections::HashMap;
 use std::env;
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::mpsc;
 use std::thread;
+use std::time::{Duration, Instant};
 
 const DEFAULT_PORT: u16 = 8888;
 const DEFAULT_UDP_PORT_OFFSET: u16 = 1; // UDP port = TCP port + 1
 const DEFAULT_ADMIN_PORT_OFFSET: u16 = 2; // Admin port = TCP port + 2
 const STALE_THRESHOLD_SECS: u64 = 10; // Client stale 閾值 (秒)
+const DEFAULT_TURN_TIMEOUT_MS: u32 = 30000; // 出牌時限 (毫秒)
+const DEFAULT_RESUME_GRACE_SECS: u64 = 60; // 斷線續連寬限期 (秒)
+const NEXT_BOARD_DELAY_MS: u64 = 3000; // 比賽中兩副牌之間的間隔 (毫秒)
 
 /// 伺服器設定
 struct ServerConfig {
     ai_auth_token: Option<String>,
+    /// YOUR_TURN 出牌時限 (毫秒)
+    turn_timeout_ms: u32,
+    /// 連續超時幾次判定棄權 (None = 不棄權，只代打)
+    max_timeouts: Option<u32>,
+    /// 斷線後保留座位的寬限期 (秒)
+    resume_grace_secs: u64,
+    /// 新房間的遊戲設定
+    room_config: RoomConfig,
+    /// 房間快照目錄 (None = 不寫快照)
+    snapshot_dir: Option<String>,
 }
 
-impl Default for ServerConfig {
-    fn default() -> Self {
-        Self {
+impl ServerConfig {
+    /// 由環境變數讀取設定 (牌組無法載入、條件或設定組合不合法時回傳錯誤)
+    fn from_env() -> Result<Self, String> {
+        let config = Self {
             ai_auth_token: env::var("AI_AUTH_TOKEN").ok(),
-        }
+            turn_timeout_ms: env::var("TURN_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_TURN_TIMEOUT_MS),
+            max_timeouts: env::var("MAX_TIMEOUTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n: &u32| n > 0),
+            resume_grace_secs: env::var("RESUME_GRACE_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_RESUME_GRACE_SECS),
+            room_config: RoomConfig {
+                // TRUMP=S/H/D/C 指定王牌，未設定或 NT 為無王
+                trump: env::var("TRUMP")
+                    .ok()
+                    .and_then(|v| v.chars().next())
+                    .and_then(Suit::from_char),
+                // AUCTION=1 時出牌前先叫牌
+                auction: env::var("AUCTION").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true")),
+                // BOARDS=N 一場比賽進行 N 副牌
+                boards: env::var("BOARDS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .filter(|&n: &u32| n > 0)
+                    .unwrap_or(1),
+                // RULES=TRICK_DUEL/HEARTS 選擇出牌規則
+                rules: env::var("RULES")
+                    .ok()
+                    .and_then(|v| RuleKind::from_str(&v))
+                    .unwrap_or_default(),
+                // DEAL_FILE=path 從預設牌組 (.pbn 或 .json) 依牌號取牌；
+                // DEAL_CONSTRAINTS=spec 產生符合條件的牌 (e.g., "N:hcp=15-17,bal")；都未設定時洗牌
+                board_source: board_source_from_env()?,
+                // FIRST_BOARD=N 比賽從第 N 副牌開始
+                first_board: env::var("FIRST_BOARD")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .filter(|&n: &u32| n > 0)
+                    .unwrap_or(1),
+                // ANALYSIS=1 每副牌結束後送出賽後分析
+                analysis: env::var("ANALYSIS").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true")),
+                // TIE_BREAK=DRAW/LAST_TRICK/HIGH_CARDS 平手時的勝負判定
+                tie_break: env::var("TIE_BREAK")
+                    .ok()
+                    .and_then(|v| TieBreak::from_str(&v))
+                    .unwrap_or_default(),
+                // SEATS=2..6 每桌座位數 (前半為 HUMAN 隊；規則不支援時拒絕啟動)
+                seats: env::var("SEATS").ok().and_then(|v| v.parse().ok()).unwrap_or(4),
+                // HAND_SIZE=N 每人只發 N 張 (練習用的短牌局)；未設定時發完整副牌
+                hand_size: env::var("HAND_SIZE")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .filter(|&n: &usize| n > 0),
+                // UNDO=OFF/TRICK/BOARD 練習房允許悔牌 (TRICK 只限進行中的 trick)
+                undo: env::var("UNDO")
+                    .ok()
+                    .and_then(|v| UndoPolicy::from_str(&v))
+                    .unwrap_or_default(),
+                // PRACTICE=1 練習房，內建 AI 對手自動同意悔牌
+                practice: env::var("PRACTICE").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true")),
+                replaced_source: None,
+            },
+            // SNAPSHOT_DIR=path 每個 trick 後寫入房間快照，重啟時還原
+            snapshot_dir: env::var("SNAPSHOT_DIR").ok().filter(|v| !v.is_empty()),
+        };
+
+        config.room_config.validate().map_err(|e| e.to_string())?;
+        Ok(config)
+    }
+}
+
+/// 由環境變數決定牌組來源 (DEAL_FILE 優先；載入或解析失敗時回傳錯誤，不改回洗牌)
+fn board_source_from_env() -> Result<BoardSource, String> {
+    if let Some(path) = env::var("DEAL_FILE").ok().filter(|v| !v.is_empty()) {
+        let set = DealSet::load(Path::new(&path)).map_err(|e| format!("cannot load deal file {}: {}", path, e))?;
+        info!("[MATCH] Loaded deal set '{}' with {} board(s) from {}", set.name, set.boards.len(), path);
+        return Ok(BoardSource::DealSet(set));
+    }
+    if let Some(spec) = env::var("DEAL_CONSTRAINTS").ok().filter(|v| !v.is_empty()) {
+        let constraints = DealConstraints::parse(&spec)
+            .and_then(|c| c.probe().map(|_| c))
+            .map_err(|e| format!("DEAL_CONSTRAINTS: {}", e))?;
+        info!("[MATCH] Dealing with constraints {}", constraints);
+        return Ok(BoardSource::Constrained(constraints));
     }
+    Ok(BoardSource::Shuffle)
 }
 
 /// 伺服器狀態
@@ -51,6 +159,18 @@ struct ServerState {
     games: HashMap<RoomId, GameEngine>,
     /// 連線到房間的對應 (conn_id -> room_id)
     conn_to_room: HashMap<ConnectionId, RoomId>,
+    /// 各房間的出牌期限
+    turn_timer: TurnTimer,
+    /// 續連 token
+    sessions: SessionStore,
+    /// 比賽進度 (room_id -> match)
+    matches: HashMap<RoomId, MatchState>,
+    /// 下一副牌的開始時間 (room_id -> instant)
+    next_boards: HashMap<RoomId, Instant>,
+    /// 房間快照 (未設定 SNAPSHOT_DIR 時為 None)
+    snapshots: Option<SnapshotStore>,
+    /// 賽後分析與 Admin DD / ANALYSE 的背景解算
+    analysis: analysis::Worker,
 }
 
 impl ServerState {
@@ -60,6 +180,12 @@ impl ServerState {
             room_manager: RoomManager::new(),
             games: HashMap::new(),
             conn_to_room: HashMap::new(),
+            turn_timer: TurnTimer::new(),
+            sessions: SessionStore::new(),
+            matches: HashMap::new(),
+            next_boards: HashMap::new(),
+            snapshots: None,
+            analysis: analysis::Worker::spawn(analysis::MAX_QUEUED_JOBS),
         }
     }
 }
@@ -67,6 +193,18 @@ impl ServerState {
 fn main() {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
+    let config = match ServerConfig::from_env() {
+        Ok(c) => c,
+        Err(e) => {
+            error!("[SERVER] Invalid configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = fairness::check_random_source() {
+        error!("[SERVER] No secure random source: {}", e);
+        std::process::exit(1);
+    }
+
     let port = parse_port_from_args().unwrap_or(DEFAULT_PORT);
     let addr: SocketAddr = format!("0.0.0.0:{}", port).parse().expect("Invalid address");
 
@@ -125,7 +263,6 @@ fn main() {
         }
     }
 
-    let config = ServerConfig::default();
     game_loop(event_rx, admin_rx, logger, config);
 }
 
@@ -159,6 +296,18 @@ fn game_loop(
     config: ServerConfig,
 ) {
     let mut state = ServerState::new();
+    state.room_manager.default_config = config.room_config.clone();
+
+    if let Some(dir) = &config.snapshot_dir {
+        match SnapshotStore::new(dir) {
+            Ok(store) => {
+                info!("[SNAPSHOT] Writing room snapshots to {}", store.dir().display());
+                state.snapshots = Some(store);
+                restore_snapshots(&mut state, &config);
+            }
+            Err(e) => warn!("[SNAPSHOT] Cannot open snapshot dir {}: {} (snapshots disabled)", dir, e),
+        }
+    }
 
     info!("[GAME] Game loop started");
 
@@ -187,8 +336,10 @@ fn game_loop(
                     let room_id = state.conn_to_room.remove(&conn_id);
 
                     if let Some(room_id) = room_id {
-                        // 處理 Bridge Mode 遊戲重啟
-                        handle_bridge_mode_disconnect(conn_id, &room_id, &mut state, &logger);
+                        // 遊戲中的真人斷線先保留座位；否則處理 Bridge Mode 遊戲重啟
+                        if !pause_for_resume(conn_id, &room_id, &mut state, &logger, &config) {
+                            handle_bridge_mode_disconnect(conn_id, &room_id, &mut state, &logger);
+                        }
                     } else if let Some(player) = state.room_manager.handle_disconnect(conn_id) {
                         info!(
                             "[GAME] Player '{}' ({}) disconnected from room",
@@ -216,6 +367,11 @@ fn game_loop(
                 break;
             }
         }
+
+        // 檢查出牌超時與續連寬限期
+        handle_turn_timeouts(&mut state, &logger, &config);
+        handle_expired_sessions(&mut state, &logger);
+        handle_next_boards(&mut state, &logger, &config);
     }
 
     info!("[GAME] Game loop ended");
@@ -239,16 +395,338 @@ fn handle_message(
             nickname,
             proto,
             auth,
-        } => {
-            handle_hello(conn_id, role, nickname, *proto, auth, state, logger, config);
-        }
+            resume,
+        } => match resume {
+            Some(token) => handle_resume(conn_id, token, state, logger),
+            None => handle_hello(conn_id, role, nickname, *proto, auth, state, logger, config),
+        },
 
         ClientMessage::Play { card } => {
             handle_play(conn_id, card, state, logger);
         }
+
+        ClientMessage::Bid { bid } => match Bid::from_protocol_string(bid) {
+            Some(bid) => handle_call(conn_id, Call::Bid(bid), state, logger),
+            None => send_to(
+                &state.clients,
+                conn_id,
+                &ServerMessage::Error {
+                    code: ErrorCode::InvalidMove,
+                    message: format!("Invalid bid: {}", bid),
+                },
+            ),
+        },
+        ClientMessage::Pass => handle_call(conn_id, Call::Pass, state, logger),
+        ClientMessage::Double => handle_call(conn_id, Call::Double, state, logger),
+        ClientMessage::Redouble => handle_call(conn_id, Call::Redouble, state, logger),
+        ClientMessage::PassCards { cards } => handle_pass_cards(conn_id, cards, state, logger),
+        ClientMessage::GetHistory => handle_get_history(conn_id, state),
+        ClientMessage::GetState => handle_get_state(conn_id, state),
+        ClientMessage::Claim { tricks } => handle_claim(conn_id, *tricks, state, logger),
+        ClientMessage::Concede => handle_concede(conn_id, state, logger),
+        ClientMessage::ClaimResponse { accept } => handle_claim_response(conn_id, *accept, state, logger),
+        ClientMessage::UndoRequest => handle_undo_request(conn_id, state, logger),
+        ClientMessage::UndoResponse { accept } => handle_undo_response(conn_id, *accept, state, logger),
+    }
+}
+
+/// 回覆 GET_HISTORY：本副牌已結算的 tricks 與桌面上的牌 (遊戲暫停中也可索取)
+fn handle_get_history(conn_id: ConnectionId, state: &ServerState) {
+    let engine = state.conn_to_room.get(&conn_id).and_then(|room_id| state.games.get(room_id));
+    let msg = match engine {
+        Some(engine) => engine.history_message(),
+        None => ServerMessage::Error {
+            code: ErrorCode::ProtocolError,
+            message: "Not in a game".to_string(),
+        },
+    };
+    send_to(&state.clients, conn_id, &msg);
+}
+
+/// 回覆 GET_STATE：這條連線座位的完整牌局狀態 (遊戲暫停中也可索取)
+fn handle_get_state(conn_id: ConnectionId, state: &ServerState) {
+    let engine = state.conn_to_room.get(&conn_id).and_then(|room_id| state.games.get(room_id));
+    let msg = match engine.and_then(|e| e.find_player_idx(conn_id).map(|idx| e.state_snapshot_message(idx))) {
+        Some(msg) => msg,
+        None => ServerMessage::Error {
+            code: ErrorCode::ProtocolError,
+            message: "Not in a game".to_string(),
+        },
+    };
+    send_to(&state.clients, conn_id, &msg);
+}
+
+/// CLAIM：所有真人看得到宣告者的手牌，內建 AI 對手先自行檢查，其餘等真人對手回覆
+fn handle_claim(conn_id: ConnectionId, tricks: u32, state: &mut ServerState, logger: &GameLogger) {
+    let room_id = match request_room(conn_id, state) {
+        Some(id) => id,
+        None => return,
+    };
+    let engine = state.games.get_mut(&room_id).unwrap();
+    let player_idx = match engine.validate_claim(conn_id, tricks) {
+        Ok(idx) => idx,
+        Err(e) => {
+            reject_claim(conn_id, e, state);
+            return;
+        }
+    };
+
+    info!(
+        "[ENGINE] {} claims {} of the remaining {} tricks",
+        engine.players[player_idx].player_id,
+        tricks,
+        engine.tricks_remaining()
+    );
+    let claim_msg = engine.start_claim(player_idx, tricks);
+    state.turn_timer.disarm(&room_id);
+    broadcast_to_humans(&room_id, &claim_msg, state);
+    respond_to_claim_for_ai(&room_id, state, logger);
+}
+
+/// CONCEDE：剩下的 tricks 全歸對手，不必等對手回覆
+fn handle_concede(conn_id: ConnectionId, state: &mut ServerState, logger: &GameLogger) {
+    let room_id = match request_room(conn_id, state) {
+        Some(id) => id,
+        None => return,
+    };
+    let engine = state.games.get_mut(&room_id).unwrap();
+    let player_idx = match engine.validate_claim(conn_id, 0) {
+        Ok(idx) => idx,
+        Err(e) => {
+            reject_claim(conn_id, e, state);
+            return;
+        }
+    };
+
+    info!(
+        "[ENGINE] {} concedes the remaining {} tricks",
+        engine.players[player_idx].player_id,
+        engine.tricks_remaining()
+    );
+    let result_msg = engine.concede(player_idx);
+    finish_claim(&room_id, ClaimOutcome::Accepted(result_msg), state, logger);
+}
+
+/// CLAIM_RESPONSE：真人對手接受或拒絕宣告
+fn handle_claim_response(conn_id: ConnectionId, accept: bool, state: &mut ServerState, logger: &GameLogger) {
+    let room_id = match request_room(conn_id, state) {
+        Some(id) => id,
+        None => return,
+    };
+    let engine = state.games.get_mut(&room_id).unwrap();
+    let player_idx = match engine.validate_claim_response(conn_id) {
+        Ok(idx) => idx,
+        Err(e) => {
+            reject_claim(conn_id, e, state);
+            return;
+        }
+    };
+
+    info!(
+        "[ENGINE] {} {} the claim",
+        engine.players[player_idx].player_id,
+        if accept { "accepts" } else { "rejects" }
+    );
+    let outcome = engine.respond_claim(player_idx, accept, None);
+    finish_claim(&room_id, outcome, state, logger);
+}
+
+/// 內建 AI 對手數過宣告方穩贏的墩後回覆 (只看牌面，不做雙明手解算)
+fn respond_to_claim_for_ai(room_id: &str, state: &mut ServerState, logger: &GameLogger) {
+    let engine = match state.games.get_mut(room_id) {
+        Some(e) => e,
+        None => return,
+    };
+    let claim = match engine.claim.clone() {
+        Some(c) => c,
+        None => return,
+    };
+
+    let verdict = claim::verify(engine, &claim);
+    let accept = verdict == claim::Verdict::Holds;
+    let mut outcome = ClaimOutcome::Pending;
+    for idx in engine.claim_responders() {
+        if !Room::is_virtual_conn(engine.players[idx].conn_id) {
+            continue;
+        }
+        info!(
+            "[AI] {} {} the claim ({:?})",
+            engine.players[idx].player_id,
+            if accept { "accepts" } else { "rejects" },
+            verdict
+        );
+        outcome = engine.respond_claim(idx, accept, verdict.reason());
+        if !matches!(outcome, ClaimOutcome::Pending) {
+            break;
+        }
+    }
+    finish_claim(room_id, outcome, state, logger);
+}
+
+/// 宣告有了結果：廣播 CLAIM_RESULT，接受時結束這副牌，拒絕時繼續出牌
+/// (還在等真人對手時由 process_ai_turns 設定回覆期限)
+fn finish_claim(room_id: &str, outcome: ClaimOutcome, state: &mut ServerState, logger: &GameLogger) {
+    match outcome {
+        ClaimOutcome::Pending => process_ai_turns(room_id, state, logger),
+        ClaimOutcome::Accepted(result_msg) => {
+            broadcast_to_humans(room_id, &result_msg, state);
+            finish_game(room_id, state, logger);
+        }
+        ClaimOutcome::Rejected(result_msg) => {
+            broadcast_to_humans(room_id, &result_msg, state);
+            process_ai_turns(room_id, state, logger);
+        }
+    }
+}
+
+/// UNDO_REQUEST：所有真人看得到要收回的牌，內建 AI 對手自動同意，其餘等真人對手回覆
+fn handle_undo_request(conn_id: ConnectionId, state: &mut ServerState, logger: &GameLogger) {
+    let room_id = match request_room(conn_id, state) {
+        Some(id) => id,
+        None => return,
+    };
+    let practice = state.room_manager.get_room(&room_id).is_some_and(|r| r.config.practice);
+    let engine = state.games.get_mut(&room_id).unwrap();
+    let player_idx = match engine.validate_undo(conn_id) {
+        Ok(idx) => idx,
+        Err(e) => {
+            reject_undo(conn_id, e, state);
+            return;
+        }
+    };
+    // 練習房以外至少要有一位真人對手同意
+    let opponents = engine.undo_opponents(player_idx);
+    if !practice && opponents.iter().all(|&idx| Room::is_virtual_conn(engine.players[idx].conn_id)) {
+        reject_undo(conn_id, UndoError::NoHumanOpponent, state);
+        return;
+    }
+
+    info!("[ENGINE] {} asks to take back the last card", engine.players[player_idx].player_id);
+    let request_msg = engine.start_undo(player_idx);
+    state.turn_timer.disarm(&room_id);
+    broadcast_to_humans(&room_id, &request_msg, state);
+
+    // 內建 AI 對手一律同意 (練習房以外還要等真人對手)
+    let engine = state.games.get_mut(&room_id).unwrap();
+    let mut outcome = UndoOutcome::Pending;
+    for idx in engine.undo_responders() {
+        if Room::is_virtual_conn(engine.players[idx].conn_id) {
+            info!("[AI] {} agrees to the undo", engine.players[idx].player_id);
+            outcome = engine.respond_undo(idx, true);
+        }
+    }
+    finish_undo(&room_id, outcome, state, logger);
+}
+
+/// UNDO_RESPONSE：真人對手同意或拒絕悔牌
+fn handle_undo_response(conn_id: ConnectionId, accept: bool, state: &mut ServerState, logger: &GameLogger) {
+    let room_id = match request_room(conn_id, state) {
+        Some(id) => id,
+        None => return,
+    };
+    let engine = state.games.get_mut(&room_id).unwrap();
+    let player_idx = match engine.validate_undo_response(conn_id) {
+        Ok(idx) => idx,
+        Err(e) => {
+            reject_undo(conn_id, e, state);
+            return;
+        }
+    };
+
+    info!(
+        "[ENGINE] {} {} the undo",
+        engine.players[player_idx].player_id,
+        if accept { "agrees to" } else { "refuses" }
+    );
+    let outcome = engine.respond_undo(player_idx, accept);
+    finish_undo(&room_id, outcome, state, logger);
+}
+
+/// 悔牌有了結果：廣播 UNDO_RESULT，同意時再廣播 PLAY_CANCEL，之後從 (收回後的) 目前座位繼續出牌
+fn finish_undo(room_id: &str, outcome: UndoOutcome, state: &mut ServerState, logger: &GameLogger) {
+    match outcome {
+        UndoOutcome::Pending => {}
+        UndoOutcome::Accepted(result_msg, cancel_msg) => {
+            broadcast_to_humans(room_id, &result_msg, state);
+            broadcast_to_humans(room_id, &cancel_msg, state);
+            save_snapshot(room_id, state);
+        }
+        UndoOutcome::Rejected(result_msg) => broadcast_to_humans(room_id, &result_msg, state),
+    }
+    process_ai_turns(room_id, state, logger);
+}
+
+/// 回覆不合法的 UNDO_REQUEST / UNDO_RESPONSE
+fn reject_undo(conn_id: ConnectionId, e: UndoError, state: &ServerState) {
+    info!("[ENGINE] #{} undo message rejected: {:?}", conn_id, e);
+    let (code, message) = match e {
+        UndoError::NotInGame | UndoError::NotPlaying => (ErrorCode::InvalidMove, "Can only undo during play"),
+        UndoError::Disabled => (ErrorCode::InvalidMove, "Undo is not allowed in this room"),
+        UndoError::RequestPending => (ErrorCode::InvalidMove, "A claim or undo is already pending"),
+        UndoError::NothingToUndo => (ErrorCode::InvalidMove, "No card to take back"),
+        UndoError::CrossesTrick => (ErrorCode::InvalidMove, "Cannot take back a card from a finished trick"),
+        UndoError::NoHumanOpponent => (ErrorCode::InvalidMove, "Undo needs a human opponent to agree"),
+        UndoError::NoRequest => (ErrorCode::ProtocolError, "No undo to respond to"),
+        UndoError::NotOpponent => (ErrorCode::InvalidMove, "Only the opponents respond to an undo"),
+    };
+    send_to(
+        &state.clients,
+        conn_id,
+        &ServerMessage::Error {
+            code,
+            message: message.to_string(),
+        },
+    );
+}
+
+/// 宣告與悔牌訊息的共同檢查：回傳連線所在的房間 (不在遊戲中或遊戲暫停時回覆 ERROR)
+fn request_room(conn_id: ConnectionId, state: &ServerState) -> Option<RoomId> {
+    let reject = |message: &str| {
+        send_to(
+            &state.clients,
+            conn_id,
+            &ServerMessage::Error {
+                code: ErrorCode::ProtocolError,
+                message: message.to_string(),
+            },
+        );
+    };
+    let room_id = match state.conn_to_room.get(&conn_id) {
+        Some(id) if state.games.contains_key(id) => id.clone(),
+        _ => {
+            reject("Not in a game");
+            return None;
+        }
+    };
+    if is_room_paused(&room_id, state) {
+        reject("Game paused, waiting for a player to reconnect");
+        return None;
     }
+    Some(room_id)
+}
+
+/// 回覆不合法的 CLAIM / CONCEDE / CLAIM_RESPONSE
+fn reject_claim(conn_id: ConnectionId, e: ClaimError, state: &ServerState) {
+    info!("[ENGINE] #{} claim message rejected: {:?}", conn_id, e);
+    let (code, message) = match e {
+        ClaimError::NotInGame | ClaimError::NotPlaying => (ErrorCode::InvalidMove, "Can only claim during play"),
+        ClaimError::NotPartnership => (ErrorCode::InvalidMove, "Claims need partnership scoring"),
+        ClaimError::ClaimPending => (ErrorCode::InvalidMove, "A claim or undo is already pending"),
+        ClaimError::TooManyTricks => (ErrorCode::InvalidMove, "Claimed more tricks than remain"),
+        ClaimError::NoClaim => (ErrorCode::ProtocolError, "No claim to respond to"),
+        ClaimError::NotOpponent => (ErrorCode::InvalidMove, "Only the claimer's opponents respond to a claim"),
+    };
+    send_to(
+        &state.clients,
+        conn_id,
+        &ServerMessage::Error {
+            code,
+            message: message.to_string(),
+        },
+    );
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_hello(
     conn_id: ConnectionId,
     role: &protocol::Role,
@@ -287,27 +765,42 @@ fn handle_hello(
     );
 
     match result {
-        HandshakeResult::Success(welcome_msg) => {
+        HandshakeResult::Success(mut welcome_msg) => {
+            // 真人玩家發給續連 token
+            if let ServerMessage::Welcome {
+                player_id,
+                room: room_id,
+                resume_token,
+                ..
+            } = &mut welcome_msg
+                && *role == protocol::Role::Human
+            {
+                *resume_token = Some(state.sessions.issue(room_id, player_id));
+            }
+
             if let ServerMessage::Welcome {
                 player_id,
                 nickname: final_nickname,
                 room: room_id,
+                ..
             } = &welcome_msg
             {
                 let room_id_clone = room_id.clone();
                 let player_count;
+                let seats;
                 let wait_msg;
                 let conn_ids;
                 let can_start;
-                let seed;
+                let deal_seed;
 
                 {
                     room.add_player(conn_id, player_id, final_nickname, *role);
                     player_count = room.players.len();
+                    seats = room.seats();
                     wait_msg = room.room_wait_message();
                     conn_ids = room.conn_ids();
                     can_start = room.can_start();
-                    seed = room.seed;
+                    deal_seed = room.deal_seed.clone();
                 }
 
                 state.room_manager.associate_conn(conn_id, &room_id_clone);
@@ -316,8 +809,8 @@ fn handle_hello(
                 send_to(&state.clients, conn_id, &welcome_msg);
 
                 info!(
-                    "[LOBBY] Player '{}' ({}) joined room {} ({}/4 players)",
-                    final_nickname, player_id, room_id, player_count
+                    "[LOBBY] Player '{}' ({}) joined room {} ({}/{} players)",
+                    final_nickname, player_id, room_id, player_count, seats
                 );
                 logger.player_join(player_id, final_nickname, room_id);
 
@@ -325,31 +818,31 @@ fn handle_hello(
                     send_to(&state.clients, cid, &wait_msg);
                 }
 
-                if can_start {
-                    if let Some(room) = state.room_manager.get_room_mut(&room_id_clone) {
-                        room.state = RoomState::Playing;
-                        room.assign_teams();
-
-                        let start_msg = room.room_start_message();
-                        let conn_ids = room.conn_ids();
+                if can_start
+                    && let Some(room) = state.room_manager.get_room_mut(&room_id_clone)
+                {
+                    room.state = RoomState::Playing;
+                    room.assign_teams();
 
-                        // 收集更新後的玩家資料 (含 team)
-                        let players_with_teams: Vec<_> = room
-                            .players
-                            .iter()
-                            .map(|p| (p.conn_id, p.player_id.clone(), p.team.unwrap_or(protocol::Team::Human)))
-                            .collect();
+                    let start_msg = room.room_start_message(room.config.first_board);
+                    let conn_ids = room.conn_ids();
 
-                        info!("[LOBBY] Room {} starting game with seed {}", room_id_clone, seed);
-                        logger.game_start(&room_id_clone, seed);
+                    // 收集更新後的玩家資料 (含 team)
+                    let players_with_teams: Vec<_> = room
+                        .players
+                        .iter()
+                        .map(|p| (p.conn_id, p.player_id.clone(), p.team.unwrap_or(protocol::Team::Human)))
+                        .collect();
 
-                        for &cid in &conn_ids {
-                            send_to(&state.clients, cid, &start_msg);
-                        }
+                    info!("[LOBBY] Room {} starting game with seed {}", room_id_clone, deal_seed.seed);
+                    logger.game_start(&room_id_clone, deal_seed.seed);
 
-                        // 建立 GameEngine 並發牌
-                        start_game(&room_id_clone, seed, players_with_teams, state, logger);
+                    for &cid in &conn_ids {
+                        send_to(&state.clients, cid, &start_msg);
                     }
+
+                    // 建立 GameEngine 並發牌
+                    start_game(&room_id_clone, deal_seed, players_with_teams, state, logger, config);
                 }
             }
         }
@@ -362,17 +855,55 @@ fn handle_hello(
 
 fn start_game(
     room_id: &str,
-    seed: u64,
+    deal_seed: DealSeed,
     players: Vec<(ConnectionId, String, protocol::Team)>,
     state: &mut ServerState,
     logger: &GameLogger,
+    config: &ServerConfig,
 ) {
     info!("[ENGINE] Creating game engine for room {}", room_id);
 
-    let mut engine = GameEngine::new(seed, players);
+    let mut engine = GameEngine::new(deal_seed.seed, players);
+    engine.secret = deal_seed.secret;
+    engine.turn_timeout_ms = config.turn_timeout_ms;
+    let (mut boards, mut first_board) = (1, 1);
+    if let Some(room) = state.room_manager.get_room(room_id) {
+        engine.trump = room.config.fixed_trump();
+        engine.bidding = room.config.auction_enabled();
+        engine.rules = room.config.rules.build();
+        engine.tie_break = room.config.tie_break;
+        engine.hand_size = room.config.hand_size;
+        engine.undo_policy = room.config.undo;
+        boards = room.config.boards;
+        first_board = room.config.first_board;
+    }
+
+    // 比賽進度決定牌號與莊家
+    let match_state = state.matches.entry(room_id.to_string()).or_insert_with(|| {
+        let mut match_state = MatchState::new(boards);
+        match_state.first_board = first_board;
+        match_state
+    });
+    engine.board = match_state.current_board();
+    engine.dealer_idx = MatchState::dealer_for(engine.board, engine.players.len());
+    info!(
+        "[MATCH] Room {} board {} ({}/{}, dealer {}, rules {})",
+        room_id,
+        engine.board,
+        match_state.results.len() + 1,
+        match_state.total_boards,
+        engine.players[engine.dealer_idx].player_id,
+        engine.rules.name()
+    );
 
     // 發牌 (只發給真人玩家)
-    let deal_messages = engine.deal();
+    let deal_messages = match deal_board(room_id, &mut engine, &state.room_manager) {
+        Ok(messages) => messages,
+        Err(e) => {
+            abort_match(room_id, engine.board, e, state, logger);
+            return;
+        }
+    };
     for (conn_id, msg) in deal_messages {
         if !Room::is_virtual_conn(conn_id) {
             send_to(&state.clients, conn_id, &msg);
@@ -380,12 +911,71 @@ fn start_game(
         }
     }
 
+    // 傳牌階段：通知真人玩家傳牌方向
+    for idx in engine.pending_passers() {
+        if !Room::is_virtual_conn(engine.players[idx].conn_id)
+            && let Some(msg) = engine.pass_request_message(idx)
+        {
+            send_to(&state.clients, engine.players[idx].conn_id, &msg);
+        }
+    }
+
+    let bidding = engine.bidding;
     state.games.insert(room_id.to_string(), engine);
+    save_snapshot(room_id, state);
+
+    if bidding {
+        let auction_msg = state.games[room_id].auction_update_message();
+        broadcast_to_humans(room_id, &auction_msg, state);
+    }
 
     // 處理回合 (如果第一位是 AI，自動出牌；否則發 YOUR_TURN 給 Human)
     process_ai_turns(room_id, state, logger);
 }
 
+/// 依房間的牌組來源發牌
+fn deal_board(
+    room_id: &str,
+    engine: &mut GameEngine,
+    room_manager: &RoomManager,
+) -> Result<Vec<(ConnectionId, ServerMessage)>, BoardError> {
+    match room_manager.get_room(room_id) {
+        Some(room) => room.config.board_source.deal(engine),
+        None => Ok(engine.deal()),
+    }
+}
+
+/// 發不出牌 (牌組缺少牌號或無法套用、條件發牌失敗) 時結束比賽：通知真人並重置房間，不改成洗牌
+fn abort_match(room_id: &str, board: u32, e: BoardError, state: &mut ServerState, logger: &GameLogger) {
+    error!("[MATCH] Room {} cannot deal board {}: {}", room_id, board, e);
+    state.games.remove(room_id);
+    state.matches.remove(room_id);
+    state.next_boards.remove(room_id);
+    state.turn_timer.disarm(room_id);
+    state.sessions.revoke_room(room_id);
+    discard_snapshot(room_id, state);
+
+    let room = match state.room_manager.get_room_mut(room_id) {
+        Some(r) => r,
+        None => return,
+    };
+    let message = ServerMessage::Error {
+        code: ErrorCode::ProtocolError,
+        message: format!("Cannot deal board {}: {}", board, e),
+    };
+    for player in &room.players {
+        if !Room::is_virtual_conn(player.conn_id) {
+            send_to(&state.clients, player.conn_id, &message);
+        }
+    }
+    if room.bridge_mode {
+        room.reset_for_bridge_mode();
+    } else {
+        room.state = RoomState::Waiting;
+    }
+    logger.error(&format!("Room {} cannot deal board {}: {}", room_id, board, e));
+}
+
 fn handle_play(conn_id: ConnectionId, card: &str, state: &mut ServerState, logger: &GameLogger) {
     // 找到該連線所屬的遊戲
     let room_id = match state.conn_to_room.get(&conn_id) {
@@ -404,6 +994,18 @@ fn handle_play(conn_id: ConnectionId, card: &str, state: &mut ServerState, logge
         }
     };
 
+    if is_room_paused(&room_id, state) {
+        send_to(
+            &state.clients,
+            conn_id,
+            &ServerMessage::Error {
+                code: ErrorCode::ProtocolError,
+                message: "Game paused, waiting for a player to reconnect".to_string(),
+            },
+        );
+        return;
+    }
+
     let engine = match state.games.get_mut(&room_id) {
         Some(e) => e,
         None => {
@@ -420,6 +1022,18 @@ fn handle_play(conn_id: ConnectionId, card: &str, state: &mut ServerState, logge
         }
     };
 
+    if engine.claim.is_some() || engine.undo.is_some() {
+        send_to(
+            &state.clients,
+            conn_id,
+            &ServerMessage::Error {
+                code: ErrorCode::ProtocolError,
+                message: "Claim or undo pending, waiting for the opponents".to_string(),
+            },
+        );
+        return;
+    }
+
     // 驗證出牌
     let (player_idx, card_data) = match engine.validate_play(conn_id, card) {
         Ok(result) => result,
@@ -445,213 +1059,750 @@ fn handle_play(conn_id: ConnectionId, card: &str, state: &mut ServerState, logge
         }
     };
 
-    let player_id = engine.players[player_idx].player_id.clone();
-    let current_trick = engine.current_trick;
-
-    info!(
-        "[ENGINE] {} plays {} (trick {})",
-        player_id, card, current_trick
-    );
-    logger.play(&player_id, card, current_trick);
+    // 玩家自行出牌，取消計時並歸零超時次數
+    engine.reset_timeouts(engine.controller_of(player_idx));
+    state.turn_timer.disarm(&room_id);
 
-    // 執行出牌
-    let play_result = engine.play_card(player_idx, card_data);
+    if execute_play(&room_id, player_idx, card_data, state, logger) {
+        // 處理下一位玩家回合 (可能是 AI 自動出牌)
+        process_ai_turns(&room_id, state, logger);
+    }
+}
 
-    match play_result {
-        PlayResult::Continue(broadcast_msg, _next_idx) => {
-            // 廣播出牌給真人玩家
-            broadcast_to_humans(&room_id, &broadcast_msg, state);
+fn handle_call(conn_id: ConnectionId, call: Call, state: &mut ServerState, logger: &GameLogger) {
+    let reject = |state: &ServerState, code: ErrorCode, message: &str| {
+        send_to(
+            &state.clients,
+            conn_id,
+            &ServerMessage::Error {
+                code,
+                message: message.to_string(),
+            },
+        );
+    };
 
-            // 處理下一位玩家回合 (可能是 AI 自動出牌)
-            process_ai_turns(&room_id, state, logger);
+    let room_id = match state.conn_to_room.get(&conn_id) {
+        Some(id) => id.clone(),
+        None => {
+            warn!("[ENGINE] #{} tried to bid but not in any room", conn_id);
+            reject(state, ErrorCode::ProtocolError, "Not in a game");
+            return;
         }
+    };
 
-        PlayResult::TrickComplete(broadcast_msg) => {
-            // 廣播出牌
-            broadcast_to_humans(&room_id, &broadcast_msg, state);
-
-            // 結算 trick
-            let engine = state.games.get_mut(&room_id).unwrap();
-            let resolution = engine.resolve_trick();
-
-            match resolution {
-                TrickResolution::NextTrick(result_msg, next_idx) => {
-                    // 廣播 TRICK_RESULT
-                    broadcast_to_humans(&room_id, &result_msg, state);
-
-                    let engine = state.games.get(&room_id).unwrap();
-                    let winner_id = engine.players[next_idx].player_id.clone();
-                    let trick_num = engine.current_trick - 1;
+    if is_room_paused(&room_id, state) {
+        reject(state, ErrorCode::ProtocolError, "Game paused, waiting for a player to reconnect");
+        return;
+    }
 
-                    info!(
-                        "[ENGINE] Trick {} complete, winner: {}, score: HUMAN={} AI={}",
-                        trick_num,
-                        winner_id,
-                        engine.score.human,
-                        engine.score.ai
-                    );
-                    logger.trick_result(&winner_id, trick_num);
+    let engine = match state.games.get_mut(&room_id) {
+        Some(e) => e,
+        None => {
+            reject(state, ErrorCode::NotYourTurn, "Game not started");
+            return;
+        }
+    };
 
-                    // 處理下一位玩家回合 (可能是 AI 自動出牌)
-                    process_ai_turns(&room_id, state, logger);
+    // 驗證叫牌
+    let player_idx = match engine.validate_call(conn_id, call) {
+        Ok(idx) => idx,
+        Err(e) => {
+            info!("[ENGINE] #{} {} rejected: {:?}", conn_id, call.to_protocol_string(), e);
+            let (code, message) = match e {
+                AuctionError::NotInGame | AuctionError::NotYourTurn => {
+                    (ErrorCode::NotYourTurn, "Not your turn to bid")
                 }
+                AuctionError::NotBidding => (ErrorCode::InvalidMove, "Auction is not in progress"),
+                AuctionError::InsufficientBid => {
+                    (ErrorCode::InvalidMove, "Bid must be higher than the last bid")
+                }
+                AuctionError::CannotDouble => (ErrorCode::InvalidMove, "Can only double an opponent's bid"),
+                AuctionError::CannotRedouble => {
+                    (ErrorCode::InvalidMove, "Can only redouble an opponent's double")
+                }
+            };
+            reject(state, code, message);
+            return;
+        }
+    };
 
-                TrickResolution::GameOver(result_msg) => {
-                    // 廣播最後一個 TRICK_RESULT
-                    broadcast_to_humans(&room_id, &result_msg, state);
-
-                    // 廣播 GAME_OVER
-                    let engine = state.games.get(&room_id).unwrap();
-                    let game_over_msg = engine.game_over_message();
-                    broadcast_to_humans(&room_id, &game_over_msg, state);
-
-                    let human_score = engine.score.human;
-                    let ai_score = engine.score.ai;
+    engine.reset_timeouts(player_idx);
+    state.turn_timer.disarm(&room_id);
 
-                    info!(
-                        "[ENGINE] Game over! Final score: HUMAN={} AI={}, Winner: {:?}",
-                        human_score,
-                        ai_score,
-                        if human_score > ai_score {
-                            "HUMAN"
-                        } else {
-                            "AI"
-                        }
-                    );
-                    logger.game_end(&room_id, human_score, ai_score);
+    execute_call(&room_id, player_idx, call, state, logger);
+    process_ai_turns(&room_id, state, logger);
+}
 
-                    // 移除遊戲 (可選: 保留用於重播)
-                    // state.games.remove(&room_id);
+fn handle_pass_cards(conn_id: ConnectionId, cards: &[String], state: &mut ServerState, logger: &GameLogger) {
+    let reject = |state: &ServerState, code: ErrorCode, message: &str| {
+        send_to(
+            &state.clients,
+            conn_id,
+            &ServerMessage::Error {
+                code,
+                message: message.to_string(),
+            },
+        );
+    };
+
+    let room_id = match state.conn_to_room.get(&conn_id) {
+        Some(id) => id.clone(),
+        None => {
+            warn!("[ENGINE] #{} tried to pass cards but not in any room", conn_id);
+            reject(state, ErrorCode::ProtocolError, "Not in a game");
+            return;
+        }
+    };
+
+    if is_room_paused(&room_id, state) {
+        reject(state, ErrorCode::ProtocolError, "Game paused, waiting for a player to reconnect");
+        return;
+    }
+
+    let engine = match state.games.get_mut(&room_id) {
+        Some(e) => e,
+        None => {
+            reject(state, ErrorCode::NotYourTurn, "Game not started");
+            return;
+        }
+    };
+
+    // 驗證傳牌
+    let (player_idx, card_data) = match engine.validate_pass(conn_id, cards) {
+        Ok(result) => result,
+        Err(e) => {
+            info!("[ENGINE] #{} PASS_CARDS {:?} rejected: {:?}", conn_id, cards, e);
+            let (code, message) = match e {
+                PassError::NotInGame | PassError::NotPassing => {
+                    (ErrorCode::NotYourTurn, "Not in the passing phase")
+                }
+                PassError::AlreadyPassed => (ErrorCode::InvalidMove, "Cards already passed"),
+                PassError::WrongCount => (ErrorCode::InvalidMove, "Wrong number of cards to pass"),
+                PassError::InvalidCard | PassError::NotInHand => {
+                    (ErrorCode::InvalidMove, "Passed cards must be distinct cards in hand")
+                }
+            };
+            reject(state, code, message);
+            return;
+        }
+    };
+
+    engine.reset_timeouts(player_idx);
+
+    execute_pass(&room_id, player_idx, card_data, state, logger);
+    process_ai_turns(&room_id, state, logger);
+}
+
+/// 記錄傳牌；所有人都選好後送出 CARDS_PASSED 並開始出牌
+fn execute_pass(room_id: &str, player_idx: usize, cards: CardSet, state: &mut ServerState, logger: &GameLogger) {
+    let engine = match state.games.get_mut(room_id) {
+        Some(e) => e,
+        None => return,
+    };
+
+    let player_id = engine.players[player_idx].player_id.clone();
+    let cards_str = cards.to_protocol_strings();
+    info!("[ENGINE] {} passes {}", player_id, cards_str.join(" "));
+    logger.pass_cards(&player_id, &cards_str.join(" "));
+
+    match engine.submit_pass(player_idx, cards) {
+        PassResult::Waiting => {}
+        PassResult::Exchanged(messages, leader_idx) => {
+            info!(
+                "[ENGINE] Room {} cards passed, {} leads",
+                room_id, engine.players[leader_idx].player_id
+            );
+            state.turn_timer.disarm(room_id);
+            for (conn_id, msg) in messages {
+                if !Room::is_virtual_conn(conn_id) {
+                    send_to(&state.clients, conn_id, &msg);
                 }
             }
         }
     }
 }
 
-fn send_to(clients: &HashMap<ConnectionId, ClientSender>, conn_id: ConnectionId, msg: &ServerMessage) {
-    if let Some(sender) = clients.get(&conn_id) {
-        if sender.send(msg.clone()).is_err() {
-            warn!("[GAME] Failed to send to #{}", conn_id);
+/// 執行叫牌並廣播 AUCTION_UPDATE
+/// 叫牌結束進入出牌；四家都 PASS 則以新 seed 重新發牌
+fn execute_call(room_id: &str, player_idx: usize, call: Call, state: &mut ServerState, logger: &GameLogger) {
+    let engine = match state.games.get_mut(room_id) {
+        Some(e) => e,
+        None => return,
+    };
+
+    let player_id = engine.players[player_idx].player_id.clone();
+    let call_str = call.to_protocol_string();
+    info!("[ENGINE] {} calls {}", player_id, call_str);
+    logger.bid(&player_id, &call_str);
+
+    match engine.make_call(player_idx, call) {
+        AuctionResult::Continue(update_msg, _next_idx) => {
+            broadcast_to_humans(room_id, &update_msg, state);
+        }
+
+        AuctionResult::Contract(update_msg, leader_idx) => {
+            broadcast_to_humans(room_id, &update_msg, state);
+
+            let engine = state.games.get(room_id).unwrap();
+            if let Some(contract) = engine.contract {
+                info!(
+                    "[ENGINE] Contract {} by {}, {} leads",
+                    contract,
+                    engine.players[contract.declarer].player_id,
+                    engine.players[leader_idx].player_id
+                );
+            }
+        }
+
+        AuctionResult::PassedOut(update_msg) => {
+            broadcast_to_humans(room_id, &update_msg, state);
+
+            // 預設牌組與限制條件的牌號對應固定的牌，重發仍是同一副：記為 0 分流局
+            let redeals = state
+                .room_manager
+                .get_room(room_id)
+                .is_none_or(|room| room.config.board_source.redeals_passed_out());
+            if !redeals {
+                state.turn_timer.disarm(room_id);
+                let engine = state.games.get_mut(room_id).unwrap();
+                engine.close_passed_out();
+                info!("[ENGINE] Room {} board {} passed out", room_id, engine.board);
+                logger.game_end(room_id, 0, 0);
+                advance_match(room_id, state);
+                return;
+            }
+
+            let deal_seed = match state.room_manager.get_room_mut(room_id) {
+                Some(room) => room.reseed(),
+                None => return,
+            };
+            info!("[ENGINE] Room {} passed out, redealing with seed {}", room_id, deal_seed.seed);
+            logger.game_start(room_id, deal_seed.seed);
+
+            let engine = state.games.get_mut(room_id).unwrap();
+            engine.reseed(deal_seed);
+            let deal_messages = match deal_board(room_id, engine, &state.room_manager) {
+                Ok(messages) => messages,
+                Err(e) => {
+                    let board = engine.board;
+                    abort_match(room_id, board, e, state, logger);
+                    return;
+                }
+            };
+            let engine = state.games.get(room_id).unwrap();
+            let auction_msg = engine.auction_update_message();
+            for (conn_id, msg) in deal_messages {
+                if !Room::is_virtual_conn(conn_id) {
+                    send_to(&state.clients, conn_id, &msg);
+                }
+            }
+            broadcast_to_humans(room_id, &auction_msg, state);
+            save_snapshot(room_id, state);
+        }
+    }
+}
+
+/// 執行出牌並廣播結果 (含 trick 結算與 GAME_OVER)
+/// 回傳 true 表示遊戲繼續
+fn execute_play(
+    room_id: &str,
+    player_idx: usize,
+    card: CardData,
+    state: &mut ServerState,
+    logger: &GameLogger,
+) -> bool {
+    let engine = match state.games.get_mut(room_id) {
+        Some(e) => e,
+        None => return false,
+    };
+
+    let player_id = engine.players[player_idx].player_id.clone();
+    let card_str = card.to_protocol_string();
+    let current_trick = engine.current_trick;
+
+    info!("[ENGINE] {} plays {} (trick {})", player_id, card_str, current_trick);
+    logger.play(&player_id, &card_str, current_trick);
+
+    // 執行出牌
+    let play_result = engine.play_card(player_idx, card);
+
+    // 首引之後攤出夢家
+    let dummy_msg = engine.reveal_dummy();
+
+    match play_result {
+        PlayResult::Continue(broadcast_msg, _next_idx) => {
+            // 廣播出牌給真人玩家
+            broadcast_to_humans(room_id, &broadcast_msg, state);
+            if let Some(msg) = dummy_msg {
+                broadcast_to_humans(room_id, &msg, state);
+            }
+            true
+        }
+
+        PlayResult::TrickComplete(broadcast_msg) => {
+            // 廣播出牌
+            broadcast_to_humans(room_id, &broadcast_msg, state);
+            if let Some(msg) = dummy_msg {
+                broadcast_to_humans(room_id, &msg, state);
+            }
+
+            // 結算 trick
+            let engine = state.games.get_mut(room_id).unwrap();
+            let resolution = engine.resolve_trick();
+
+            match resolution {
+                TrickResolution::NextTrick(result_msg, next_idx) => {
+                    // 廣播 TRICK_RESULT
+                    broadcast_to_humans(room_id, &result_msg, state);
+
+                    let engine = state.games.get(room_id).unwrap();
+                    let winner_id = engine.players[next_idx].player_id.clone();
+                    let trick_num = engine.current_trick - 1;
+
+                    info!(
+                        "[ENGINE] Trick {} complete, winner: {}, score: HUMAN={} AI={}",
+                        trick_num, winner_id, engine.score.human, engine.score.ai
+                    );
+                    logger.trick_result(&winner_id, trick_num);
+                    save_snapshot(room_id, state);
+                    true
+                }
+
+                TrickResolution::GameOver(result_msg) => {
+                    // 廣播最後一個 TRICK_RESULT
+                    broadcast_to_humans(room_id, &result_msg, state);
+                    finish_game(room_id, state, logger);
+                    false
+                }
+            }
+        }
+    }
+}
+
+/// 廣播 GAME_OVER 並記錄結果
+fn finish_game(room_id: &str, state: &mut ServerState, logger: &GameLogger) {
+    state.turn_timer.disarm(room_id);
+
+    let engine = match state.games.get(room_id) {
+        Some(e) => e,
+        None => return,
+    };
+
+    let game_over_msg = engine.game_over_message();
+    broadcast_to_humans(room_id, &game_over_msg, state);
+
+    let human_score = engine.score.human;
+    let ai_score = engine.score.ai;
+    let winner = match &game_over_msg {
+        ServerMessage::GameOver { winner, .. } => *winner,
+        _ => protocol::Outcome::Draw,
+    };
+
+    info!(
+        "[ENGINE] Game over! Final score: HUMAN={} AI={}, Winner: {:?}",
+        human_score, ai_score, winner
+    );
+    logger.game_end(room_id, human_score, ai_score);
+
+    if state.room_manager.get_room(room_id).is_some_and(|r| r.config.analysis) {
+        send_analysis(room_id, state);
+    }
+
+    // 移除遊戲 (可選: 保留用於重播)
+    // state.games.remove(room_id);
+
+    advance_match(room_id, state);
+}
+
+/// 在分析的背景執行緒分析剛結束的這副牌，完成後送 ANALYSIS 給房間的真人玩家
+fn send_analysis(room_id: &str, state: &ServerState) {
+    let engine = match state.games.get(room_id) {
+        Some(e) => e,
+        None => return,
+    };
+    let record = match analysis::GameRecord::from_engine(engine) {
+        Ok(r) => r,
+        Err(e) => {
+            info!("[GAME] Room {} board {} not analysed: {}", room_id, engine.board, e);
+            return;
+        }
+    };
+    let senders: Vec<ClientSender> = engine
+        .players
+        .iter()
+        .filter(|p| !Room::is_virtual_conn(p.conn_id))
+        .filter_map(|p| state.clients.get(&p.conn_id).cloned())
+        .collect();
+    let board = record.board;
+    let room = room_id.to_string();
+
+    let queued = state.analysis.submit(move || {
+        let started = Instant::now();
+        match record.analyse() {
+            Ok(result) => {
+                info!(
+                    "[GAME] Room {} board {} analysed in {:?}: {} costly play(s)",
+                    room,
+                    result.board,
+                    started.elapsed(),
+                    result.mistakes.len()
+                );
+                let msg = result.to_message();
+                for sender in senders {
+                    let _ = sender.send(msg.clone());
+                }
+            }
+            Err(e) => warn!("[GAME] Room {} board {} analysis failed: {}", room, record.board, e),
+        }
+    });
+    if !queued {
+        warn!("[GAME] Room {} board {} not analysed: analysis queue is full", room_id, board);
+    }
+}
+
+/// 記錄比賽進度：還有下一副則廣播 MATCH_STATUS 並排程發牌，否則廣播 MATCH_OVER
+fn advance_match(room_id: &str, state: &mut ServerState) {
+    let (engine, match_state) = match (state.games.get(room_id), state.matches.get_mut(room_id)) {
+        (Some(e), Some(m)) => (e, m),
+        _ => return,
+    };
+
+    let result = match_state.record(engine);
+    info!(
+        "[MATCH] Room {} board {} done: HUMAN {:+} AI {:+}",
+        room_id, result.board, result.score.human, result.score.ai
+    );
+
+    if match_state.is_over() {
+        let over_msg = match_state.over_message();
+        info!(
+            "[MATCH] Room {} match over after {} board(s): HUMAN={} AI={}",
+            room_id,
+            match_state.results.len(),
+            match_state.score.human,
+            match_state.score.ai
+        );
+        broadcast_to_humans(room_id, &over_msg, state);
+
+        if let Some(room) = state.room_manager.get_room_mut(room_id) {
+            room.state = RoomState::Finished;
         }
+        discard_snapshot(room_id, state);
+    } else {
+        let next_board = match_state.current_board();
+        let next_dealer = MatchState::dealer_for(next_board, engine.players.len());
+        let status_msg = match_state.status_message(&engine.players[next_dealer].player_id);
+        broadcast_to_humans(room_id, &status_msg, state);
+
+        let starts_at = Instant::now() + Duration::from_millis(NEXT_BOARD_DELAY_MS);
+        state.next_boards.insert(room_id.to_string(), starts_at);
+        save_snapshot(room_id, state);
+    }
+}
+
+/// 開始已到時間的下一副牌 (新的 GameEngine 與 seed)
+fn handle_next_boards(state: &mut ServerState, logger: &GameLogger, config: &ServerConfig) {
+    let now = Instant::now();
+    let due: Vec<RoomId> = state
+        .next_boards
+        .iter()
+        .filter(|(_, starts_at)| **starts_at <= now)
+        .map(|(id, _)| id.clone())
+        .collect();
+
+    for room_id in due {
+        state.next_boards.remove(&room_id);
+
+        let players: Vec<_> = match state.games.get(&room_id) {
+            Some(engine) => engine
+                .players
+                .iter()
+                .map(|p| (p.conn_id, p.player_id.clone(), p.team))
+                .collect(),
+            None => continue,
+        };
+
+        let deal_seed = match state.room_manager.get_room_mut(&room_id) {
+            Some(room) if room.state == RoomState::Playing => room.reseed(),
+            _ => continue,
+        };
+
+        info!("[MATCH] Room {} dealing next board with seed {}", room_id, deal_seed.seed);
+        logger.game_start(&room_id, deal_seed.seed);
+        start_game(&room_id, deal_seed, players, state, logger, config);
+    }
+}
+
+fn send_to(clients: &HashMap<ConnectionId, ClientSender>, conn_id: ConnectionId, msg: &ServerMessage) {
+    if let Some(sender) = clients.get(&conn_id)
+        && sender.send(msg.clone()).is_err()
+    {
+        warn!("[GAME] Failed to send to #{}", conn_id);
     }
 }
 
 /// 檢查並處理 AI 玩家的回合
 /// 如果當前玩家是 AI，自動選擇並出牌，直到輪到 Human 或遊戲結束
 fn process_ai_turns(room_id: &str, state: &mut ServerState, logger: &GameLogger) {
-    let strategy = SmartStrategy::default();
-
     loop {
+        // 有座位等待續連時暫停
+        if is_room_paused(room_id, state) {
+            return;
+        }
+
         let engine = match state.games.get_mut(room_id) {
             Some(e) => e,
             None => return,
         };
 
+        // 攤牌宣告或悔牌請求等待真人對手回覆：暫停出牌，回覆期限與出牌相同
+        if engine.claim.is_some() || engine.undo.is_some() {
+            if state.turn_timer.get(room_id).is_none() {
+                let responders = [engine.claim_responders(), engine.undo_responders()].concat();
+                let responder = responders.first().copied().unwrap_or(0);
+                let timeout = Duration::from_millis(engine.turn_timeout_ms as u64);
+                let current_trick = engine.current_trick;
+                state.turn_timer.arm(room_id, responder, current_trick, timeout);
+            }
+            return;
+        }
+
+        // 傳牌階段：AI 先選好，真人玩家共用一個期限
+        let pending = engine.pending_passers();
+        if !pending.is_empty() {
+            let (ai_passers, human_passers): (Vec<usize>, Vec<usize>) = pending
+                .into_iter()
+                .partition(|&idx| Room::is_virtual_conn(engine.players[idx].conn_id));
+
+            if let Some(&idx) = ai_passers.first() {
+                let cards = choose_auto_pass(engine, idx, strategy_for(engine).as_ref());
+                execute_pass(room_id, idx, cards, state, logger);
+                continue;
+            }
+
+            if state.turn_timer.get(room_id).is_none() {
+                let timeout = Duration::from_millis(engine.turn_timeout_ms as u64);
+                let current_trick = engine.current_trick;
+                state.turn_timer.arm(room_id, human_passers[0], current_trick, timeout);
+            }
+            return;
+        }
+
+        // 叫牌階段
+        if let Some(bidder_idx) = engine.current_bidder_idx() {
+            let bidder_conn_id = engine.players[bidder_idx].conn_id;
+
+            if !Room::is_virtual_conn(bidder_conn_id) {
+                // Human 玩家由 AUCTION_UPDATE 的 next 得知輪到自己，只需設定期限
+                let timeout = Duration::from_millis(engine.turn_timeout_ms as u64);
+                let current_trick = engine.current_trick;
+                state.turn_timer.arm(room_id, bidder_idx, current_trick, timeout);
+                return;
+            }
+
+            let call = choose_auto_call(engine, bidder_idx, &SmartStrategy::new());
+            info!(
+                "[AI] {} chooses {}",
+                engine.players[bidder_idx].player_id,
+                call.to_protocol_string()
+            );
+            execute_call(room_id, bidder_idx, call, state, logger);
+            continue;
+        }
+
         // 取得當前玩家
         let current_idx = match engine.current_player_idx() {
             Some(idx) => idx,
             None => return, // 遊戲已結束或尚未開始
         };
 
-        let current_conn_id = engine.players[current_idx].conn_id;
+        // 夢家由主打者控制
+        let current_conn_id = engine.players[engine.controller_of(current_idx)].conn_id;
 
         // 檢查是否為 AI (虛擬連線)
         if !Room::is_virtual_conn(current_conn_id) {
-            // Human 玩家，發送 YOUR_TURN 並結束 AI 處理迴圈
+            // Human 玩家，發送 YOUR_TURN、設定出牌期限並結束 AI 處理迴圈
             let your_turn_msg = engine.your_turn_message(current_idx);
+            let timeout = Duration::from_millis(engine.turn_timeout_ms as u64);
+            let current_trick = engine.current_trick;
             send_to(&state.clients, current_conn_id, &your_turn_msg);
             info!(
                 "[ENGINE] YOUR_TURN -> {} (trick {})",
-                engine.players[current_idx].player_id, engine.current_trick
+                engine.players[current_idx].player_id, current_trick
             );
+            state.turn_timer.arm(room_id, current_idx, current_trick, timeout);
             return;
         }
 
         // AI 玩家，自動出牌
-        let player_id = engine.players[current_idx].player_id.clone();
-        let hand = engine.players[current_idx].hand.clone();
-        let legal_moves = engine.get_legal_moves(current_idx);
-        let table: Vec<(usize, CardData)> = engine.table.clone();
-        let is_leader = table.is_empty();
-        let current_trick = engine.current_trick;
-
-        // 使用策略選擇牌
-        let chosen_card = strategy.choose_card(&hand, &legal_moves, &table, is_leader);
-        let card_str = chosen_card.to_protocol_string();
+        let chosen_card = choose_auto_play(engine, current_idx, strategy_for(engine).as_ref());
 
         info!(
             "[AI] {} chooses {} (trick {}, is_leader={})",
-            player_id, card_str, current_trick, is_leader
+            engine.players[current_idx].player_id,
+            chosen_card.to_protocol_string(),
+            engine.current_trick,
+            engine.table.is_empty()
         );
-        logger.play(&player_id, &card_str, current_trick);
 
-        // 執行出牌
-        let play_result = engine.play_card(current_idx, chosen_card);
+        if !execute_play(room_id, current_idx, chosen_card, state, logger) {
+            return;
+        }
+    }
+}
 
-        match play_result {
-            PlayResult::Continue(broadcast_msg, _next_idx) => {
-                // 廣播出牌給所有真人玩家
-                broadcast_to_humans(room_id, &broadcast_msg, state);
-                // 繼續迴圈處理下一位玩家
-            }
+/// 依房間規則選擇 AI 出牌策略
+fn strategy_for(engine: &GameEngine) -> Box<dyn AiStrategy> {
+    match engine.rules.kind() {
+        RuleKind::TrickDuel => Box::new(SmartStrategy::with_trump(engine.trump)),
+        RuleKind::Hearts => Box::new(HeartsStrategy::new()),
+    }
+}
 
-            PlayResult::TrickComplete(broadcast_msg) => {
-                // 廣播出牌
-                broadcast_to_humans(room_id, &broadcast_msg, state);
+/// 使用 AI 策略替玩家選擇傳出的牌 (AI 傳牌與超時代傳共用)
+fn choose_auto_pass(engine: &GameEngine, player_idx: usize, strategy: &dyn AiStrategy) -> CardSet {
+    strategy.choose_pass(engine.players[player_idx].hand, engine.rules.pass_count())
+}
 
-                // 結算 trick
-                let engine = state.games.get_mut(room_id).unwrap();
-                let resolution = engine.resolve_trick();
+/// 使用 AI 策略替玩家選牌 (AI 出牌與超時代打共用)
+fn choose_auto_play(engine: &GameEngine, player_idx: usize, strategy: &dyn AiStrategy) -> CardData {
+    let hand = engine.players[player_idx].hand;
+    let legal_moves = engine.get_legal_moves(player_idx);
+    let is_leader = engine.table.is_empty();
 
-                match resolution {
-                    TrickResolution::NextTrick(result_msg, next_idx) => {
-                        // 廣播 TRICK_RESULT
-                        broadcast_to_humans(room_id, &result_msg, state);
+    strategy.choose_card(hand, legal_moves, &engine.table, is_leader)
+}
 
-                        let engine = state.games.get(room_id).unwrap();
-                        let winner_id = engine.players[next_idx].player_id.clone();
-                        let trick_num = engine.current_trick - 1;
+/// 使用 AI 策略替玩家叫牌 (AI 叫牌與超時代叫共用)
+fn choose_auto_call(engine: &GameEngine, player_idx: usize, strategy: &dyn AiStrategy) -> Call {
+    let hand = engine.players[player_idx].hand;
+    match &engine.auction {
+        Some(auction) => strategy.choose_call(hand, auction, player_idx),
+        None => Call::Pass,
+    }
+}
 
-                        info!(
-                            "[ENGINE] Trick {} complete, winner: {}, score: HUMAN={} AI={}",
-                            trick_num,
-                            winner_id,
-                            engine.score.human,
-                            engine.score.ai
-                        );
-                        logger.trick_result(&winner_id, trick_num);
-                        // 繼續迴圈處理下一位玩家
-                    }
+/// 處理已到期的出牌期限
+/// 發送 TIMEOUT 錯誤，替該玩家自動出牌；連續超時達上限則判定棄權
+fn handle_turn_timeouts(state: &mut ServerState, logger: &GameLogger, config: &ServerConfig) {
+    for (room_id, deadline) in state.turn_timer.take_expired(Instant::now()) {
+        let engine = match state.games.get_mut(&room_id) {
+            Some(e) => e,
+            None => continue,
+        };
 
-                    TrickResolution::GameOver(result_msg) => {
-                        // 廣播最後一個 TRICK_RESULT
-                        broadcast_to_humans(room_id, &result_msg, state);
+        // 宣告或悔牌的回覆期限已過：視為拒絕，繼續出牌 (不算超時)
+        if let Some(result_msg) = engine.withdraw_claim() {
+            info!("[ENGINE] Claim in room {} expired without an answer", room_id);
+            broadcast_to_humans(&room_id, &result_msg, state);
+            process_ai_turns(&room_id, state, logger);
+            continue;
+        }
+        if let Some(result_msg) = engine.withdraw_undo() {
+            info!("[ENGINE] Undo in room {} expired without an answer", room_id);
+            broadcast_to_humans(&room_id, &result_msg, state);
+            process_ai_turns(&room_id, state, logger);
+            continue;
+        }
 
-                        // 廣播 GAME_OVER
-                        let engine = state.games.get(room_id).unwrap();
-                        let game_over_msg = engine.game_over_message();
-                        broadcast_to_humans(room_id, &game_over_msg, state);
+        // 傳牌階段超時：替所有尚未傳牌的玩家代傳
+        let pending = engine.pending_passers();
+        if !pending.is_empty() {
+            handle_pass_timeout(&room_id, pending, state, logger, config);
+            continue;
+        }
 
-                        let human_score = engine.score.human;
-                        let ai_score = engine.score.ai;
+        // 期限已過時 (玩家在期間已出牌)，忽略
+        if engine.current_turn_idx() != Some(deadline.player_idx)
+            || engine.current_trick != deadline.trick
+        {
+            continue;
+        }
 
-                        info!(
-                            "[ENGINE] Game over! Final score: HUMAN={} AI={}, Winner: {:?}",
-                            human_score,
-                            ai_score,
-                            if human_score > ai_score {
-                                "HUMAN"
-                            } else {
-                                "AI"
-                            }
-                        );
-                        logger.game_end(room_id, human_score, ai_score);
-                        return;
-                    }
-                }
-            }
+        // 夢家的超時算在主打者身上
+        let player_idx = deadline.player_idx;
+        let controller_idx = engine.controller_of(player_idx);
+        let conn_id = engine.players[controller_idx].conn_id;
+        let player_id = engine.players[controller_idx].player_id.clone();
+        let timeouts = engine.record_timeout(controller_idx);
+
+        warn!(
+            "[ENGINE] {} timed out on trick {} ({} in a row)",
+            player_id, deadline.trick, timeouts
+        );
+        send_to(
+            &state.clients,
+            conn_id,
+            &ServerMessage::Error {
+                code: ErrorCode::Timeout,
+                message: format!(
+                    "Turn timed out after {} ms, {} auto-played",
+                    engine.turn_timeout_ms,
+                    if engine.current_bidder_idx().is_some() { "call" } else { "card" }
+                ),
+            },
+        );
+
+        if config.max_timeouts.is_some_and(|max| timeouts >= max) {
+            info!("[ENGINE] {} forfeits room {} after {} timeouts", player_id, room_id, timeouts);
+            engine.forfeit(controller_idx);
+            finish_game(&room_id, state, logger);
+            continue;
+        }
+
+        // 叫牌階段超時：代為叫牌
+        if engine.current_bidder_idx().is_some() {
+            let call = choose_auto_call(engine, player_idx, &SmartStrategy::new());
+            execute_call(&room_id, player_idx, call, state, logger);
+            process_ai_turns(&room_id, state, logger);
+            continue;
         }
+
+        let card = choose_auto_play(engine, player_idx, strategy_for(engine).as_ref());
+        if execute_play(&room_id, player_idx, card, state, logger) {
+            process_ai_turns(&room_id, state, logger);
+        }
+    }
+}
+
+/// 傳牌期限到期：尚未傳牌的玩家記一次超時並由 AI 代傳；連續超時達上限則判定棄權
+fn handle_pass_timeout(
+    room_id: &str,
+    pending: Vec<usize>,
+    state: &mut ServerState,
+    logger: &GameLogger,
+    config: &ServerConfig,
+) {
+    for player_idx in pending {
+        let engine = match state.games.get_mut(room_id) {
+            Some(e) => e,
+            None => return,
+        };
+        let conn_id = engine.players[player_idx].conn_id;
+        let player_id = engine.players[player_idx].player_id.clone();
+        let timeouts = engine.record_timeout(player_idx);
+
+        warn!("[ENGINE] {} timed out passing cards ({} in a row)", player_id, timeouts);
+        send_to(
+            &state.clients,
+            conn_id,
+            &ServerMessage::Error {
+                code: ErrorCode::Timeout,
+                message: format!("Turn timed out after {} ms, cards auto-passed", engine.turn_timeout_ms),
+            },
+        );
+
+        if config.max_timeouts.is_some_and(|max| timeouts >= max) {
+            info!("[ENGINE] {} forfeits room {} after {} timeouts", player_id, room_id, timeouts);
+            engine.forfeit(player_idx);
+            finish_game(room_id, state, logger);
+            return;
+        }
+
+        let cards = choose_auto_pass(engine, player_idx, strategy_for(engine).as_ref());
+        execute_pass(room_id, player_idx, cards, state, logger);
     }
+    process_ai_turns(room_id, state, logger);
 }
 
 /// 廣播訊息給房間內的所有真人玩家
@@ -665,6 +1816,301 @@ fn broadcast_to_humans(room_id: &str, msg: &ServerMessage, state: &ServerState)
     }
 }
 
+/// 房間是否因座位斷線而暫停
+fn is_room_paused(room_id: &str, state: &ServerState) -> bool {
+    state
+        .room_manager
+        .get_room(room_id)
+        .is_some_and(|r| r.is_paused())
+}
+
+/// 遊戲進行中的真人斷線：保留座位並暫停遊戲，等待續連
+/// 回傳 false 表示不適用 (交給原本的斷線流程)
+fn pause_for_resume(
+    conn_id: ConnectionId,
+    room_id: &str,
+    state: &mut ServerState,
+    logger: &GameLogger,
+    config: &ServerConfig,
+) -> bool {
+    let in_progress = state.games.get(room_id).is_some_and(|e| !e.is_game_over());
+    let room = match state.room_manager.get_room_mut(room_id) {
+        Some(r) => r,
+        None => return false,
+    };
+
+    if room.state != RoomState::Playing || !in_progress {
+        return false;
+    }
+
+    let player = match room.find_player(conn_id) {
+        Some(p) if p.role == protocol::Role::Human => p.clone(),
+        _ => return false,
+    };
+
+    let grace = Duration::from_secs(config.resume_grace_secs);
+    if !state.sessions.mark_disconnected(room_id, &player.player_id, grace) {
+        return false;
+    }
+
+    room.set_connected(&player.player_id, false);
+    if let Some(engine) = state.games.get_mut(room_id) {
+        engine.set_connected(&player.player_id, false);
+    }
+    state.turn_timer.disarm(room_id);
+
+    info!(
+        "[GAME] Player '{}' ({}) disconnected from room {}, holding seat for {}s",
+        player.nickname, player.player_id, room_id, config.resume_grace_secs
+    );
+    logger.player_leave(&player.player_id, &player.nickname, room_id);
+
+    let paused_msg = ServerMessage::PlayPaused {
+        player_id: player.player_id,
+        grace_ms: grace.as_millis() as u64,
+    };
+    broadcast_to_humans(room_id, &paused_msg, state);
+    true
+}
+
+/// 以續連 token 取回座位
+/// 重新綁定 conn_id 後重送 DEAL 手牌、桌面與分數
+fn handle_resume(conn_id: ConnectionId, token: &str, state: &mut ServerState, logger: &GameLogger) {
+    let reject = |state: &ServerState, message: &str| {
+        warn!("[LOBBY] #{} cannot resume: {}", conn_id, message);
+        send_to(
+            &state.clients,
+            conn_id,
+            &ServerMessage::Error {
+                code: ErrorCode::InvalidHello,
+                message: message.to_string(),
+            },
+        );
+    };
+    let claim = match state.sessions.claim(token, Instant::now()) {
+        Some(c) => c,
+        None => {
+            reject(state, "Invalid or expired resume token");
+            return;
+        }
+    };
+
+    let room_id = claim.room_id;
+    let player_id = claim.player_id;
+    // 房間或這副牌已不存在 (e.g., 管理員重設房間) 時無法續連
+    let seated = state
+        .room_manager
+        .get_room(&room_id)
+        .is_some_and(|r| r.find_player_by_id(&player_id).is_some());
+    let (player_idx, board) = match state.games.get(&room_id) {
+        Some(e) if seated => match e.players.iter().position(|p| p.player_id == player_id) {
+            Some(idx) => (idx, e.board),
+            None => {
+                reject(state, "Seat is no longer in the game");
+                return;
+            }
+        },
+        _ => {
+            reject(state, "Room is no longer playing");
+            return;
+        }
+    };
+
+    let room = state.room_manager.get_room_mut(&room_id).unwrap();
+    room.rebind_conn(&player_id, conn_id);
+    let nickname = room
+        .find_player_by_id(&player_id)
+        .map(|p| p.nickname.clone())
+        .unwrap_or_default();
+    let start_msg = room.room_start_message(board);
+
+    state.room_manager.associate_conn(conn_id, &room_id);
+    state.conn_to_room.insert(conn_id, room_id.clone());
+
+    let engine = state.games.get_mut(&room_id).unwrap();
+    engine.rebind_conn(&player_id, conn_id);
+    let deal_msg = engine.deal_message(player_idx);
+    let table_msg = engine.table_state_message();
+    let auction_msg = engine.auction.is_some().then(|| engine.auction_update_message());
+    let dummy_msg = engine.dummy_reveal_message();
+    let snapshot_msg = engine.state_snapshot_message(player_idx);
+    let claim_msg = engine.claim_message();
+    let undo_msg = engine.undo_message();
+    let pass_msg = engine
+        .pending_passers()
+        .contains(&player_idx)
+        .then(|| engine.pass_request_message(player_idx))
+        .flatten();
+
+    info!(
+        "[LOBBY] Player '{}' ({}) resumed seat in room {} on #{}",
+        nickname, player_id, room_id, conn_id
+    );
+    logger.player_join(&player_id, &nickname, &room_id);
+
+    let welcome_msg = ServerMessage::Welcome {
+        player_id: player_id.clone(),
+        nickname,
+        room: room_id.clone(),
+        resume_token: Some(token.to_string()),
+    };
+    let resume_msgs = [welcome_msg, start_msg, deal_msg, table_msg]
+        .into_iter()
+        .chain(auction_msg)
+        .chain(dummy_msg)
+        .chain([snapshot_msg])
+        .chain(pass_msg)
+        .chain(claim_msg)
+        .chain(undo_msg);
+    for msg in resume_msgs {
+        send_to(&state.clients, conn_id, &msg);
+    }
+
+    broadcast_to_humans(&room_id, &ServerMessage::PlayResumed { player_id }, state);
+
+    // 所有座位都回來了才繼續 (重送 YOUR_TURN)
+    process_ai_turns(&room_id, state, logger);
+}
+
+/// 寬限期已過仍未續連的座位，改走原本的斷線流程
+fn handle_expired_sessions(state: &mut ServerState, logger: &GameLogger) {
+    for claim in state.sessions.take_expired(Instant::now()) {
+        let conn_id = match state
+            .room_manager
+            .get_room(&claim.room_id)
+            .and_then(|r| r.find_player_by_id(&claim.player_id))
+        {
+            Some(p) if !p.connected => p.conn_id,
+            _ => continue,
+        };
+
+        info!(
+            "[LOBBY] Resume grace period expired for {} in room {}",
+            claim.player_id, claim.room_id
+        );
+        handle_bridge_mode_disconnect(conn_id, &claim.room_id, state, logger);
+    }
+}
+
+/// 寫入房間快照 (發牌後、每個 trick 結束與兩副牌之間)
+fn save_snapshot(room_id: &str, state: &ServerState) {
+    let store = match &state.snapshots {
+        Some(s) => s,
+        None => return,
+    };
+    let (room, engine, match_state) = match (
+        state.room_manager.get_room(room_id),
+        state.games.get(room_id),
+        state.matches.get(room_id),
+    ) {
+        (Some(r), Some(e), Some(m)) => (r, e, m),
+        _ => return,
+    };
+
+    let resume_tokens: Vec<_> = room
+        .players
+        .iter()
+        .filter(|p| p.role == protocol::Role::Human)
+        .filter_map(|p| {
+            state
+                .sessions
+                .token_for(room_id, &p.player_id)
+                .map(|token| (p.player_id.clone(), token))
+        })
+        .collect();
+
+    if let Err(e) = store.save(room, engine, match_state, &resume_tokens) {
+        warn!("[SNAPSHOT] Failed to save room {}: {}", room_id, e);
+    }
+}
+
+/// 刪除房間快照 (比賽結束或房間重置)
+fn discard_snapshot(room_id: &str, state: &ServerState) {
+    if let Some(store) = &state.snapshots
+        && let Err(e) = store.remove(room_id)
+    {
+        warn!("[SNAPSHOT] Failed to remove snapshot of room {}: {}", room_id, e);
+    }
+}
+
+/// 啟動時還原快照中的房間
+/// 真人座位換成新的佔位連線並標記斷線，原玩家在寬限期內以續連 token 取回座位
+fn restore_snapshots(state: &mut ServerState, config: &ServerConfig) {
+    let loaded = match &state.snapshots {
+        Some(store) => match store.load_all() {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                warn!("[SNAPSHOT] Cannot read snapshot dir: {}", e);
+                return;
+            }
+        },
+        None => return,
+    };
+
+    let grace = Duration::from_secs(config.resume_grace_secs);
+    for (path, result) in loaded {
+        let RoomSnapshot {
+            mut room,
+            mut engine,
+            match_state,
+            resume_tokens,
+            ..
+        } = match result {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                warn!("[SNAPSHOT] Skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let room_id = room.id.clone();
+
+        // 沒有 token 的真人座位無法取回，整個房間不還原
+        let humans: Vec<String> = room
+            .players
+            .iter()
+            .filter(|p| p.role == protocol::Role::Human)
+            .map(|p| p.player_id.clone())
+            .collect();
+        if humans.iter().any(|id| !resume_tokens.iter().any(|(p, _)| p == id)) {
+            warn!("[SNAPSHOT] Skipping room {}: missing resume token", room_id);
+            discard_snapshot(&room_id, state);
+            continue;
+        }
+
+        // 佔位連線 ID 由計數器產生，不會與之後的新連線重複
+        for player_id in &humans {
+            let placeholder = next_connection_id();
+            room.rebind_conn(player_id, placeholder);
+            room.set_connected(player_id, false);
+            engine.rebind_conn(player_id, placeholder);
+            engine.set_connected(player_id, false);
+        }
+        for (player_id, token) in &resume_tokens {
+            state.sessions.restore(token, &room_id, player_id);
+            state.sessions.mark_disconnected(&room_id, player_id, grace);
+        }
+
+        // 兩副牌之間重啟：續連後接著發下一副
+        if engine.is_game_over() && !match_state.is_over() {
+            let starts_at = Instant::now() + Duration::from_millis(NEXT_BOARD_DELAY_MS);
+            state.next_boards.insert(room_id.clone(), starts_at);
+        }
+
+        info!(
+            "[SNAPSHOT] Restored room {} (board {}/{}, trick {}), holding {} seat(s) for {}s",
+            room_id,
+            engine.board,
+            match_state.total_boards,
+            engine.current_trick,
+            humans.len(),
+            config.resume_grace_secs
+        );
+        state.room_manager.restore_room(room);
+        state.games.insert(room_id.clone(), engine);
+        state.matches.insert(room_id, match_state);
+    }
+}
+
 /// 處理 Bridge Mode 下的玩家斷線
 /// 如果遊戲進行中有玩家斷線，重置遊戲等待新玩家
 fn handle_bridge_mode_disconnect(
@@ -681,7 +2127,7 @@ fn handle_bridge_mode_disconnect(
 
     let player = room.remove_player(conn_id);
     let is_bridge_mode = room.bridge_mode;
-    let was_playing = room.state == RoomState::Playing;
+    let was_playing = matches!(room.state, RoomState::Playing | RoomState::Finished);
 
     if let Some(ref p) = player {
         info!(
@@ -689,6 +2135,7 @@ fn handle_bridge_mode_disconnect(
             p.nickname, p.player_id, room_id
         );
         logger.player_leave(&p.player_id, &p.nickname, room_id);
+        state.sessions.revoke(room_id, &p.player_id);
     }
 
     // Bridge Mode 且遊戲進行中，需要重置
@@ -697,6 +2144,11 @@ fn handle_bridge_mode_disconnect(
 
         // 移除遊戲引擎
         state.games.remove(room_id);
+        state.matches.remove(room_id);
+        state.next_boards.remove(room_id);
+        state.turn_timer.disarm(room_id);
+        state.sessions.revoke_room(room_id);
+        discard_snapshot(room_id, state);
 
         // 重置房間
         let room = state.room_manager.get_room_mut(room_id).unwrap();
@@ -798,19 +2250,28 @@ fn handle_admin_event(event: AdminEvent, state: &mut ServerState, logger: &GameL
                 state.conn_to_room.remove(&conn_id);
 
                 // 處理房間
+                let mut reset = false;
                 if let Some(room) = state.room_manager.get_room_mut(&room_id) {
-                    let was_playing = room.state == RoomState::Playing;
+                    let was_playing = matches!(room.state, RoomState::Playing | RoomState::Finished);
 
                     if let Some(player) = room.remove_player(conn_id) {
                         logger.admin_action("KICK", &format!("Kicked {} from {}", player.nickname, room_id));
 
                         if room.bridge_mode && was_playing {
                             // 重置房間
-                            state.games.remove(&room_id);
                             room.reset_for_bridge_mode();
+                            reset = true;
                         }
                     }
                 }
+                if reset {
+                    state.games.remove(&room_id);
+                    state.matches.remove(&room_id);
+                    state.next_boards.remove(&room_id);
+                    state.turn_timer.disarm(&room_id);
+                    state.sessions.revoke_room(&room_id);
+                    discard_snapshot(&room_id, state);
+                }
 
                 let _ = reply_tx.send(AdminResponse::Ok(format!(
                     "Player {} kicked from {}",
@@ -824,21 +2285,73 @@ fn handle_admin_event(event: AdminEvent, state: &mut ServerState, logger: &GameL
             }
         }
 
+        AdminEvent::ExportPbn { room_id, reply_tx } => {
+            let _ = reply_tx.send(export_pbn(&room_id, state));
+        }
+
+        AdminEvent::InspectRoom { room_id, reply_tx } => {
+            let _ = reply_tx.send(inspect_room(&room_id, state));
+        }
+
+        AdminEvent::DoubleDummy { room_id, reply_tx } => {
+            double_dummy_room(&room_id, state, reply_tx);
+        }
+
+        AdminEvent::Analyse { room_id, reply_tx } => {
+            analyse_room(&room_id, state, reply_tx);
+        }
+
+        AdminEvent::SetDealConstraints { room_id, constraints, reply_tx } => {
+            let response = match state.room_manager.get_room_mut(&room_id) {
+                Some(room) => {
+                    let mut config = room.config.clone();
+                    config.set_constraints(constraints);
+                    match config.validate() {
+                        Ok(()) => {
+                            room.config = config;
+                            let message = match &room.config.board_source {
+                                BoardSource::Constrained(c) => {
+                                    format!("Room {} will deal next boards with constraints {}", room_id, c)
+                                }
+                                BoardSource::DealSet(set) => {
+                                    format!("Room {} will deal next boards from {}", room_id, set.name)
+                                }
+                                BoardSource::Shuffle => format!("Room {} will shuffle next boards", room_id),
+                            };
+                            info!("[MATCH] {}", message);
+                            save_snapshot(&room_id, state);
+                            AdminResponse::Ok(message)
+                        }
+                        Err(e) => AdminResponse::Error(format!("Room {}: {}", room_id, e)),
+                    }
+                }
+                None => AdminResponse::Error(format!("Room {} not found", room_id)),
+            };
+            let _ = reply_tx.send(response);
+        }
+
         AdminEvent::ResetRoom { room_id, reply_tx } => {
             match room_id {
                 Some(rid) => {
-                    if let Some(room) = state.room_manager.get_room_mut(&rid) {
-                        if room.state != RoomState::Playing {
-                            let _ = reply_tx.send(AdminResponse::Error(format!(
-                                "Room {} is not in playing state",
-                                rid
-                            )));
-                            return;
-                        }
-
+                    let playing = state
+                        .room_manager
+                        .get_room(&rid)
+                        .map(|room| matches!(room.state, RoomState::Playing | RoomState::Finished));
+                    if playing == Some(false) {
+                        let _ = reply_tx.send(AdminResponse::Error(format!("Room {} is not in playing state", rid)));
+                        return;
+                    }
+                    if playing == Some(true) {
                         // 移除遊戲引擎
                         state.games.remove(&rid);
+                        state.matches.remove(&rid);
+                        state.next_boards.remove(&rid);
+                        state.turn_timer.disarm(&rid);
+                        state.sessions.revoke_room(&rid);
+                        discard_snapshot(&rid, state);
+                    }
 
+                    if let Some(room) = state.room_manager.get_room_mut(&rid) {
                         // 通知玩家
                         for player in &room.players {
                             if !Room::is_virtual_conn(player.conn_id) {
@@ -873,6 +2386,11 @@ fn handle_admin_event(event: AdminEvent, state: &mut ServerState, logger: &GameL
 
                     for rid in room_ids {
                         state.games.remove(&rid);
+                        state.matches.remove(&rid);
+                        state.next_boards.remove(&rid);
+                        state.turn_timer.disarm(&rid);
+                        state.sessions.revoke_room(&rid);
+                        discard_snapshot(&rid, state);
                         if let Some(room) = state.room_manager.get_room_mut(&rid) {
                             for player in &room.players {
                                 if !Room::is_virtual_conn(player.conn_id) {
@@ -904,6 +2422,138 @@ fn handle_admin_event(event: AdminEvent, state: &mut ServerState, logger: &GameL
     }
 }
 
+/// 匯出房間目前這副牌的 PBN 記錄 (進行中的牌只含已完成的 trick)
+fn export_pbn(room_id: &str, state: &ServerState) -> AdminResponse {
+    match state.games.get(room_id) {
+        Some(engine) => match pbn::write_game(engine, &format!("Card Arena {}", room_id), &pbn::today()) {
+            Ok(text) => AdminResponse::Ok(text),
+            Err(e) => AdminResponse::Error(format!("Cannot export room {}: {}", room_id, e)),
+        },
+        None => AdminResponse::Error(format!("No game in room {}", room_id)),
+    }
+}
+
+/// 每位玩家這副牌發到的手牌與評估 (Admin INSPECT)
+fn inspect_room(room_id: &str, state: &ServerState) -> AdminResponse {
+    let engine = match state.games.get(room_id) {
+        Some(e) => e,
+        None => return AdminResponse::Error(format!("No game in room {}", room_id)),
+    };
+
+    let mut output = format!("\n=== Room {} board {} ({}) ===\n", room_id, engine.board, engine.rules.name());
+    for (idx, player) in engine.players.iter().enumerate() {
+        let hand = engine.dealt_hands.get(idx).unwrap_or(&player.hand).to_vec();
+        output.push_str(&format!(
+            "{:<4} {:<6} {:<20} {}\n",
+            player.player_id,
+            format!("{:?}", player.team).to_uppercase(),
+            pbn::hand_to_pbn(&hand),
+            HandEvaluation::of(&hand)
+        ));
+    }
+    AdminResponse::Ok(output)
+}
+
+/// 房間這副牌的雙明手分析 (Admin DD)：各王牌、各首引座位的墩數表，出牌中再加上目前局面
+/// 解算排進分析的背景執行緒，不阻塞 game loop
+fn double_dummy_room(room_id: &str, state: &ServerState, reply_tx: mpsc::Sender<AdminResponse>) {
+    let engine = match state.games.get(room_id) {
+        Some(e) => e,
+        None => {
+            let _ = reply_tx.send(AdminResponse::Error(format!("No game in room {}", room_id)));
+            return;
+        }
+    };
+    let (hands, teams) = match solver::dealt_hands(engine) {
+        Ok(deal) => deal,
+        Err(e) => {
+            let _ = reply_tx.send(AdminResponse::Error(format!("Cannot analyse room {}: {}", room_id, e)));
+            return;
+        }
+    };
+
+    let position = solver::Position::from_engine(engine).ok().filter(|p| p.tricks_left() > 0);
+    let labels: Vec<String> = engine.players.iter().map(|p| p.player_id.clone()).collect();
+    let won = (engine.tricks_for(protocol::Team::Human), engine.tricks_for(protocol::Team::Ai));
+    let trick = engine.current_trick;
+    let trump = engine.trump_symbol().unwrap_or_else(|| "NT".to_string());
+    let mut output = format!("\n=== Room {} board {} double dummy ===\n", room_id, engine.board);
+    let room = room_id.to_string();
+
+    let busy_tx = reply_tx.clone();
+    let queued = state.analysis.submit(move || {
+        let started = Instant::now();
+        let table = match solver::trick_table(hands, teams) {
+            Ok(t) => t,
+            Err(e) => {
+                let _ = reply_tx.send(AdminResponse::Error(e.to_string()));
+                return;
+            }
+        };
+        output.push_str(&table.format([0, 1, 2, 3].map(|i| labels[i].as_str()), teams));
+
+        match position.map(|p| (solver::solve(&p, protocol::Team::Human), p)) {
+            Some((Ok(human), p)) => output.push_str(&format!(
+                "Now (trick {}, {}, {} to play): HUMAN {}+{}, AI {}+{}\n",
+                trick,
+                trump,
+                labels[p.to_move()],
+                won.0,
+                human,
+                won.1,
+                p.tricks_left() - human
+            )),
+            Some((Err(e), _)) => output.push_str(&format!("Now: {}\n", e)),
+            None => output.push_str(&format!("Played: HUMAN {}, AI {}\n", won.0, won.1)),
+        }
+        info!("[ADMIN] Double dummy for room {} solved in {:?}", room, started.elapsed());
+        let _ = reply_tx.send(AdminResponse::Ok(output));
+    });
+    if !queued {
+        let _ = busy_tx.send(AdminResponse::Error("Analysis queue is full, try again later".to_string()));
+    }
+}
+
+/// 房間這副牌的賽後分析 (Admin ANALYSE)：已打完的 trick 中代價高的出牌
+fn analyse_room(room_id: &str, state: &ServerState, reply_tx: mpsc::Sender<AdminResponse>) {
+    let engine = match state.games.get(room_id) {
+        Some(e) => e,
+        None => {
+            let _ = reply_tx.send(AdminResponse::Error(format!("No game in room {}", room_id)));
+            return;
+        }
+    };
+    let record = match analysis::GameRecord::from_engine(engine) {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = reply_tx.send(AdminResponse::Error(format!("Cannot analyse room {}: {}", room_id, e)));
+            return;
+        }
+    };
+    let header = format!(
+        "\n=== Room {} board {} analysis ({} of {} tricks played) ===\n",
+        room_id,
+        engine.board,
+        record.tricks.len(),
+        record.hands[0].len()
+    );
+    let room = room_id.to_string();
+
+    let busy_tx = reply_tx.clone();
+    let queued = state.analysis.submit(move || {
+        let started = Instant::now();
+        let response = match record.analyse() {
+            Ok(result) => AdminResponse::Ok(header + &result.format()),
+            Err(e) => AdminResponse::Error(e.to_string()),
+        };
+        info!("[ADMIN] Analysis for room {} solved in {:?}", room, started.elapsed());
+        let _ = reply_tx.send(response);
+    });
+    if !queued {
+        let _ = busy_tx.send(AdminResponse::Error("Analysis queue is full, try again later".to_string()));
+    }
+}
+
 fn parse_port_from_args() -> Option<u16> {
     let args: Vec<String> = env::args().collect();
     let mut i = 1;
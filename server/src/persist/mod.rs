@@ -0,0 +1,8 @@
+//! Persist Module - 房間快照與重啟還原
+//!
+//! 遊戲進行中的房間在每個 trick 結束後寫入磁碟 (Server 環境變數 `SNAPSHOT_DIR`)，
+//! Server 重啟時讀回並保留座位讓原玩家續連。
+
+pub mod snapshot;
+
+pub use snapshot::{RoomSnapshot, SnapshotStore};
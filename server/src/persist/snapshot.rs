@@ -0,0 +1,252 @@
+//! 房間快照 (Snapshot)
+//!
+//! 一個房間一個檔案 `<dir>/<room_id>.json`，內容為房間、GameEngine、比賽進度與
+//! 真人座位的續連 token。檔案帶有格式版本，版本不符的快照不會被載入。
+
+use crate::game::{GameEngine, MatchState};
+use crate::lobby::Room;
+use crate::protocol::PlayerId;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 快照格式版本 (格式不相容時遞增)
+/// - 2: 每個 trick 的出牌與分數記錄、攤牌宣告與悔牌狀態、房間的座位數與悔牌設定
+///   (舊快照缺少這些記錄，還原後無法正確悔牌或結算宣告)
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// 寫入用的快照 (借用目前的遊戲狀態)
+#[derive(Serialize)]
+struct SnapshotRef<'a> {
+    version: u32,
+    room: &'a Room,
+    engine: &'a GameEngine,
+    match_state: &'a MatchState,
+    resume_tokens: &'a [(PlayerId, String)],
+}
+
+/// 從磁碟讀回的房間快照 (`version` 已在 `decode` 檢查過)
+#[derive(Deserialize)]
+pub struct RoomSnapshot {
+    pub room: Room,
+    pub engine: GameEngine,
+    pub match_state: MatchState,
+    /// 真人座位的續連 token (player_id, token)
+    pub resume_tokens: Vec<(PlayerId, String)>,
+}
+
+/// 快照讀寫錯誤
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    /// 快照版本與目前 Server 不相容
+    UnsupportedVersion(u64),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(e) => write!(f, "I/O error: {}", e),
+            SnapshotError::Format(e) => write!(f, "invalid snapshot: {}", e),
+            SnapshotError::UnsupportedVersion(v) => {
+                write!(f, "unsupported snapshot version {} (expected {})", v, SNAPSHOT_VERSION)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(e: io::Error) -> Self {
+        SnapshotError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(e: serde_json::Error) -> Self {
+        SnapshotError::Format(e)
+    }
+}
+
+/// 將房間狀態編碼為快照 JSON
+pub fn encode(
+    room: &Room,
+    engine: &GameEngine,
+    match_state: &MatchState,
+    resume_tokens: &[(PlayerId, String)],
+) -> Result<String, SnapshotError> {
+    let snapshot = SnapshotRef {
+        version: SNAPSHOT_VERSION,
+        room,
+        engine,
+        match_state,
+        resume_tokens,
+    };
+    Ok(serde_json::to_string(&snapshot)?)
+}
+
+/// 解碼快照 JSON (先檢查版本，欄位不相容的舊快照也回報 UnsupportedVersion 而不是格式錯誤)
+pub fn decode(json: &str) -> Result<RoomSnapshot, SnapshotError> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version != SNAPSHOT_VERSION as u64 {
+        return Err(SnapshotError::UnsupportedVersion(version));
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// 快照目錄
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    /// 開啟快照目錄 (不存在時建立)
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path_for(&self, room_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", room_id))
+    }
+
+    /// 寫入房間快照 (先寫暫存檔再 rename，避免留下寫到一半的檔案)
+    pub fn save(
+        &self,
+        room: &Room,
+        engine: &GameEngine,
+        match_state: &MatchState,
+        resume_tokens: &[(PlayerId, String)],
+    ) -> Result<(), SnapshotError> {
+        let json = encode(room, engine, match_state, resume_tokens)?;
+        let path = self.path_for(&room.id);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// 刪除房間快照 (比賽結束或房間重置)
+    pub fn remove(&self, room_id: &str) -> io::Result<()> {
+        match fs::remove_file(self.path_for(room_id)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// 讀取目錄中所有快照 (依檔名排序，各自回報成功或失敗)
+    pub fn load_all(&self) -> io::Result<Vec<(PathBuf, Result<RoomSnapshot, SnapshotError>)>> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+
+        Ok(paths
+            .into_iter()
+            .map(|path| {
+                let result = fs::read_to_string(&path)
+                    .map_err(SnapshotError::from)
+                    .and_then(|json| decode(&json));
+                (path, result)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::RuleKind;
+    use crate::game::engine::GamePhase;
+    use crate::game::testing;
+    use crate::lobby::RoomConfig;
+    use crate::protocol::{Role, Team};
+
+    /// 打完第一個 trick 的房間
+    fn room_after_one_trick() -> (Room, GameEngine, MatchState) {
+        let mut room = Room::with_config("R001", true, RoomConfig::default());
+        room.add_player(11, "P1", "Alice", Role::Human);
+        room.add_player(12, "P2", "Bob", Role::Human);
+
+        let players = room
+            .players
+            .iter()
+            .map(|p| (p.conn_id, p.player_id.clone(), p.team.unwrap_or(Team::Human)))
+            .collect();
+        let mut engine = GameEngine::new(room.deal_seed.seed, players);
+        engine.deal();
+        testing::play_first_legal(&mut engine, 4);
+
+        (room, engine, MatchState::new(2))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let (room, engine, match_state) = room_after_one_trick();
+        let tokens = vec![("P1".to_string(), "aa".repeat(16))];
+        let json = encode(&room, &engine, &match_state, &tokens).unwrap();
+
+        let restored = decode(&json).unwrap();
+        assert_eq!(restored.room.id, "R001");
+        assert_eq!(restored.room.players.len(), 4);
+        assert_eq!(restored.room.deal_seed, room.deal_seed);
+        assert_eq!(restored.engine.current_trick, 2);
+        assert_eq!(restored.engine.phase, engine.phase);
+        assert_eq!(restored.engine.history.len(), 1);
+        assert_eq!(restored.engine.played, engine.played);
+        for (a, b) in restored.engine.players.iter().zip(&engine.players) {
+            assert_eq!(a.hand, b.hand);
+        }
+        assert_eq!(restored.engine.rules.kind(), RuleKind::TrickDuel);
+        assert_eq!(restored.match_state.total_boards, 2);
+        assert_eq!(restored.resume_tokens, tokens);
+        assert_eq!(restored.room.nicknames, room.nicknames);
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        let (room, engine, match_state) = room_after_one_trick();
+        let json = encode(&room, &engine, &match_state, &[]).unwrap();
+        let future = json.replacen("\"version\":2", "\"version\":3", 1);
+        let before_trick_records = json.replacen("\"version\":2", "\"version\":1", 1);
+
+        assert!(matches!(decode(&future), Err(SnapshotError::UnsupportedVersion(3))));
+        assert!(matches!(decode(&before_trick_records), Err(SnapshotError::UnsupportedVersion(1))));
+        assert!(matches!(decode("{}"), Err(SnapshotError::UnsupportedVersion(0))));
+        assert!(matches!(decode("not json"), Err(SnapshotError::Format(_))));
+    }
+
+    #[test]
+    fn test_store_save_load_remove() {
+        let dir = std::env::temp_dir().join(format!("card_arena_snapshots_{}", std::process::id()));
+        let store = SnapshotStore::new(&dir).unwrap();
+        let (mut room, mut engine, match_state) = room_after_one_trick();
+
+        store.save(&room, &engine, &match_state, &[]).unwrap();
+        room.id = "R002".to_string();
+        engine.phase = GamePhase::GameOver;
+        store.save(&room, &engine, &match_state, &[]).unwrap();
+        fs::write(dir.join("R003.json"), "{\"version\":99}").unwrap();
+
+        let loaded = store.load_all().unwrap();
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded[0].1.as_ref().unwrap().room.id, "R001");
+        assert!(loaded[1].1.as_ref().unwrap().engine.is_game_over());
+        assert!(loaded[2].1.is_err());
+
+        store.remove("R001").unwrap();
+        store.remove("R001").unwrap();
+        assert_eq!(store.load_all().unwrap().len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -16,6 +16,25 @@ pub enum Team {
     Ai,
 }
 
+/// 一副牌或一場比賽的勝負 (GAME_OVER / MATCH_OVER 的 winner)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Outcome {
+    Human,
+    Ai,
+    /// 平手 (房間的 tie-break 也無法分出勝負)
+    Draw,
+}
+
+impl From<Team> for Outcome {
+    fn from(team: Team) -> Self {
+        match team {
+            Team::Human => Outcome::Human,
+            Team::Ai => Outcome::Ai,
+        }
+    }
+}
+
 /// 玩家 ID (P1-P4)
 pub type PlayerId = String;
 
@@ -41,6 +60,7 @@ pub enum ErrorCode {
 /// 出牌被拒原因
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[allow(clippy::enum_variant_names)]
 pub enum RejectReason {
     NotInHand,
     NotLegal,
@@ -58,18 +78,64 @@ pub struct PlayerInfo {
 }
 
 /// 桌面上的出牌資訊
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TablePlay {
     pub player_id: PlayerId,
     pub card: Card,
 }
 
-/// Trick 歷史記錄
+/// 座位的即時狀態 (YOUR_TURN / TRICK_RESULT)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeatState {
+    pub player_id: PlayerId,
+    /// 本副牌贏得的 trick 數
+    pub tricks: u32,
+    /// 手上剩下的牌數
+    pub cards_left: u32,
+    /// 是否在線 (斷線等待續連時為 false)
+    pub connected: bool,
+    /// 是否輪到這個座位出牌
+    pub to_play: bool,
+}
+
+/// 叫牌記錄
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuctionCall {
+    pub player_id: PlayerId,
+    /// "PASS" / "1S" / "3NT" / "X" / "XX"
+    pub call: String,
+}
+
+/// Trick 歷史記錄
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TrickHistory {
     pub trick: u32,
     pub winner: PlayerId,
+    /// 依出牌順序的牌 (與 plays 相同，保留給舊版 Client)
     pub cards: Vec<Card>,
+    /// 首引者
+    #[serde(default)]
+    pub leader: PlayerId,
+    /// 領牌花色 (S/H/D/C)
+    #[serde(default)]
+    pub led_suit: String,
+    /// 依出牌順序的 (player_id, card)
+    #[serde(default)]
+    pub plays: Vec<TablePlay>,
+    /// 本 trick 結算後兩隊的累計分數
+    #[serde(default)]
+    pub score: Score,
+}
+
+/// 賽後分析中讓己方少拿墩數的一張牌
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostlyPlay {
+    pub trick: u32,
+    pub player_id: PlayerId,
+    pub card: Card,
+    /// 能保住墩數的另一張合法牌
+    pub better: Card,
+    pub tricks_lost: u32,
 }
 
 /// 分數
@@ -81,6 +147,53 @@ pub struct Score {
     pub ai: u32,
 }
 
+/// 各隊帶正負號的分數：一副牌的複式分數，或比賽累計分數 (叫牌模式為複式分數，否則為墩數)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct SignedScore {
+    #[serde(rename = "HUMAN")]
+    pub human: i32,
+    #[serde(rename = "AI")]
+    pub ai: i32,
+}
+
+/// 個人分數 (非搭檔制規則，e.g., Hearts)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PlayerScore {
+    pub player_id: PlayerId,
+    /// 吃到的 trick 分數
+    pub taken: u32,
+    /// 計分後的罰分 (shooting the moon 後調整)
+    pub points: u32,
+}
+
+/// 被接受的攤牌宣告 (認輸時 tricks 為 0)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ClaimSummary {
+    pub player_id: PlayerId,
+    /// 宣告方拿到的墩數
+    pub tricks: u32,
+    /// 宣告時剩下的 tricks (其餘歸對手)
+    pub remaining: u32,
+}
+
+/// 單副牌結果 (比賽明細)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardResult {
+    pub board: u32,
+    pub dealer: PlayerId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    /// 各隊墩數
+    pub tricks: Score,
+    /// 本副比賽分數
+    pub score: SignedScore,
+    /// 本副個人分數 (非搭檔制規則)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_scores: Option<Vec<PlayerScore>>,
+}
+
 /// 客戶端到伺服器的訊息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -93,12 +206,63 @@ pub enum ClientMessage {
         proto: u32,
         #[serde(skip_serializing_if = "Option::is_none")]
         auth: Option<String>,
+        /// 續連 token (斷線後取回原座位)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        resume: Option<String>,
     },
 
     /// 出牌
     #[serde(rename = "PLAY")]
     Play { card: Card },
 
+    /// 叫品 (e.g., "1S", "3NT")
+    #[serde(rename = "BID")]
+    Bid { bid: String },
+
+    /// 不叫
+    #[serde(rename = "PASS")]
+    Pass,
+
+    /// 賭倍
+    #[serde(rename = "DOUBLE")]
+    Double,
+
+    /// 再賭倍
+    #[serde(rename = "REDOUBLE")]
+    Redouble,
+
+    /// 傳牌 (Hearts 出牌前)
+    #[serde(rename = "PASS_CARDS")]
+    PassCards { cards: Vec<Card> },
+
+    /// 攤牌宣告：剩下的 tricks 中自己這隊拿 `tricks` 墩
+    #[serde(rename = "CLAIM")]
+    Claim { tricks: u32 },
+
+    /// 認輸：剩下的 tricks 全歸對手
+    #[serde(rename = "CONCEDE")]
+    Concede,
+
+    /// 對手回覆攤牌宣告
+    #[serde(rename = "CLAIM_RESPONSE")]
+    ClaimResponse { accept: bool },
+
+    /// 悔牌：收回自己最後出的牌 (練習房，需對手同意)
+    #[serde(rename = "UNDO_REQUEST")]
+    UndoRequest,
+
+    /// 對手回覆悔牌請求
+    #[serde(rename = "UNDO_RESPONSE")]
+    UndoResponse { accept: bool },
+
+    /// 索取本副牌目前的 trick 歷史
+    #[serde(rename = "GET_HISTORY")]
+    GetHistory,
+
+    /// 索取自己座位的完整牌局狀態 (Client 狀態不同步時重建用)
+    #[serde(rename = "GET_STATE")]
+    GetState,
+
     /// Ping (用於測試)
     #[serde(rename = "PING")]
     Ping,
@@ -114,6 +278,9 @@ pub enum ServerMessage {
         player_id: PlayerId,
         nickname: String,
         room: RoomId,
+        /// 續連 token (斷線後 HELLO 帶上即可取回座位)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        resume_token: Option<String>,
     },
 
     /// 錯誤訊息
@@ -133,7 +300,17 @@ pub enum ServerMessage {
     RoomStart {
         room: RoomId,
         players: Vec<PlayerInfo>,
-        seed: u64,
+        /// 第一副牌的承諾值 SHA-256("<seed>:<secret>")，seed 於 GAME_OVER 揭露
+        seed_commitment: String,
+        /// 王牌花色 ("S"/"H"/"D"/"C")，null 表示無王
+        trump: Option<String>,
+        /// 規則名稱 (e.g., "TRICK_DUEL")
+        rules: String,
+        /// 正要進行的牌號
+        board: u32,
+        /// 手牌來自的預設牌組名稱 (以 seed 洗牌時省略)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        deal_set: Option<String>,
     },
 
     /// 發牌
@@ -141,6 +318,44 @@ pub enum ServerMessage {
     Deal {
         hand: Vec<Card>,
         total_tricks: u32,
+        /// 這副牌的承諾值 SHA-256("<seed>:<secret>")
+        seed_commitment: String,
+    },
+
+    /// 要求傳牌 (Hearts 出牌前)
+    #[serde(rename = "PASS_REQUEST")]
+    PassRequest {
+        /// "LEFT" / "RIGHT" / "ACROSS"
+        direction: String,
+        /// 收牌的玩家
+        to: PlayerId,
+        count: u32,
+        timeout_ms: u32,
+    },
+
+    /// 傳牌完成，收到的牌與新的手牌
+    #[serde(rename = "CARDS_PASSED")]
+    CardsPassed {
+        direction: String,
+        /// 傳牌給你的玩家
+        from: PlayerId,
+        received: Vec<Card>,
+        hand: Vec<Card>,
+    },
+
+    /// 叫牌進度
+    #[serde(rename = "AUCTION_UPDATE")]
+    AuctionUpdate {
+        dealer: PlayerId,
+        calls: Vec<AuctionCall>,
+        /// 下一位叫牌者 (叫牌結束時為 null)
+        next: Option<PlayerId>,
+        /// 最終合約 (e.g., "4SX")，叫牌中為 null
+        contract: Option<String>,
+        /// 主打者
+        declarer: Option<PlayerId>,
+        /// 四家都 PASS，將重新發牌
+        passed_out: bool,
     },
 
     /// 輪到你出牌
@@ -150,6 +365,19 @@ pub enum ServerMessage {
         table: Vec<TablePlay>,
         legal: Vec<Card>,
         timeout_ms: u32,
+        /// 代為出牌的座位 (主打者替夢家出牌時)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        seat: Option<PlayerId>,
+        /// 各座位的狀態 (依座位順序)
+        seats: Vec<SeatState>,
+    },
+
+    /// 攤出夢家手牌 (首引之後)
+    #[serde(rename = "DUMMY_REVEAL")]
+    DummyReveal {
+        dummy: PlayerId,
+        declarer: PlayerId,
+        hand: Vec<Card>,
     },
 
     /// 廣播出牌
@@ -171,14 +399,189 @@ pub enum ServerMessage {
         plays: Vec<TablePlay>,
         winner: PlayerId,
         score: Score,
+        /// 王牌花色，null 表示無王
+        trump: Option<String>,
+        /// 結算後各座位的狀態 (依座位順序)
+        seats: Vec<SeatState>,
     },
 
     /// 遊戲結束
     #[serde(rename = "GAME_OVER")]
     GameOver {
         final_score: Score,
-        winner: Team,
+        winner: Outcome,
+        /// 個人計分規則 (e.g., Hearts) 獲勝的座位；平手且 tie-break 也分不出勝負時省略
+        #[serde(skip_serializing_if = "Option::is_none")]
+        winning_seat: Option<PlayerId>,
+        /// 分數平手時決定勝負的規則 (LAST_TRICK / HIGH_CARDS)；未平手或判和時省略
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tie_break: Option<String>,
+        history: Vec<TrickHistory>,
+        /// 揭露的洗牌 seed (可與 secret 驗證 DEAL 的承諾值並重建發牌)
+        seed: u64,
+        /// 揭露的 server secret (hex)
+        secret: String,
+        /// 王牌花色，null 表示無王
+        trump: Option<String>,
+        /// 因連續超時而棄權的玩家
+        #[serde(skip_serializing_if = "Option::is_none")]
+        forfeit: Option<PlayerId>,
+        /// 合約 (叫牌模式)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        contract: Option<String>,
+        /// 合約結果 (e.g., "4S+1")
+        #[serde(skip_serializing_if = "Option::is_none")]
+        result: Option<String>,
+        /// 複式分數
+        #[serde(skip_serializing_if = "Option::is_none")]
+        duplicate_score: Option<SignedScore>,
+        /// 個人分數 (非搭檔制規則，e.g., Hearts)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        player_scores: Option<Vec<PlayerScore>>,
+        /// 以攤牌宣告或認輸結束時的宣告
+        #[serde(skip_serializing_if = "Option::is_none")]
+        claim: Option<ClaimSummary>,
+    },
+
+    /// 賽後分析 (房間開啟分析時，於 GAME_OVER 之後送出)
+    #[serde(rename = "ANALYSIS")]
+    Analysis {
+        board: u32,
+        /// 王牌花色，null 表示無王
+        trump: Option<String>,
+        /// 首引前雙明手 (雙方最佳打法) 的墩數
+        par: Score,
+        /// 實際拿到的墩數
+        actual: Score,
+        mistakes: Vec<CostlyPlay>,
+    },
+
+    /// 比賽進度 (兩副牌之間)
+    #[serde(rename = "MATCH_STATUS")]
+    MatchStatus {
+        /// 剛結束的牌號
+        board: u32,
+        total_boards: u32,
+        last: Option<BoardResult>,
+        /// 下一副的莊家
+        next_dealer: PlayerId,
+        score: SignedScore,
+        /// 累計個人分數 (非搭檔制規則)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        player_scores: Option<Vec<PlayerScore>>,
+    },
+
+    /// 比賽結束
+    #[serde(rename = "MATCH_OVER")]
+    MatchOver {
+        boards: Vec<BoardResult>,
+        score: SignedScore,
+        winner: Outcome,
+        /// 累計個人分數 (非搭檔制規則)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        player_scores: Option<Vec<PlayerScore>>,
+    },
+
+    /// 有玩家斷線，遊戲暫停等待續連
+    #[serde(rename = "PLAY_PAUSED")]
+    PlayPaused { player_id: PlayerId, grace_ms: u64 },
+
+    /// 斷線玩家已回到座位
+    #[serde(rename = "PLAY_RESUMED")]
+    PlayResumed { player_id: PlayerId },
+
+    /// 當前桌面狀態 (續連後重送)
+    #[serde(rename = "TABLE_STATE")]
+    TableState {
+        trick: u32,
+        table: Vec<TablePlay>,
+        score: Score,
+    },
+
+    /// 攤牌宣告 (給所有真人，附宣告者的手牌)
+    #[serde(rename = "CLAIM")]
+    Claim {
+        player_id: PlayerId,
+        tricks: u32,
+        /// 剩下的 tricks (含進行中的 trick)
+        remaining: u32,
+        hand: Vec<Card>,
+    },
+
+    /// 攤牌宣告的結果 (接受時接著送出 GAME_OVER)
+    #[serde(rename = "CLAIM_RESULT")]
+    ClaimResult {
+        player_id: PlayerId,
+        tricks: u32,
+        accepted: bool,
+        /// 做出決定的對手 (回覆期限已過或認輸時沒有)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        by: Option<PlayerId>,
+        /// 內建 AI 拒絕的原因
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+
+    /// 悔牌請求 (給所有真人)
+    #[serde(rename = "UNDO_REQUEST")]
+    UndoRequest {
+        player_id: PlayerId,
+        /// 會被收回的牌 (由最後出的往前)
+        cards: Vec<TablePlay>,
+    },
+
+    /// 悔牌請求的結果 (同意時接著送出 PLAY_CANCEL)
+    #[serde(rename = "UNDO_RESULT")]
+    UndoResult {
+        player_id: PlayerId,
+        accepted: bool,
+        /// 做出決定的對手 (回覆期限已過時沒有)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        by: Option<PlayerId>,
+    },
+
+    /// 收回的出牌 (牌回到各自手中，從收回後的局面繼續)
+    #[serde(rename = "PLAY_CANCEL")]
+    PlayCancel {
+        player_id: PlayerId,
+        /// 收回的牌 (由最後出的往前)
+        cards: Vec<TablePlay>,
+        trick: u32,
+        table: Vec<TablePlay>,
+        score: Score,
+        seats: Vec<SeatState>,
+    },
+
+    /// GET_HISTORY 的回覆
+    #[serde(rename = "HISTORY")]
+    History {
+        /// 已結算的 tricks
+        tricks: Vec<TrickHistory>,
+        /// 進行中的 trick 編號 (一副牌結束後為最後一個)
+        current_trick: u32,
+        /// 進行中 trick 已出的牌
+        table: Vec<TablePlay>,
+        score: Score,
+    },
+
+    /// GET_STATE 的回覆 (續連後也會主動送出)
+    #[serde(rename = "STATE_SNAPSHOT")]
+    StateSnapshot {
+        player_id: PlayerId,
+        board: u32,
+        /// 王牌花色，null 表示無王
+        trump: Option<String>,
+        hand: Vec<Card>,
+        /// 輪到這條連線出牌時的合法牌，否則為空
+        legal: Vec<Card>,
+        /// 代為出牌的座位 (主打者替夢家出牌時)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        seat: Option<PlayerId>,
+        trick: u32,
+        table: Vec<TablePlay>,
+        score: Score,
         history: Vec<TrickHistory>,
+        seats: Vec<SeatState>,
     },
 
     /// Pong (用於測試)
@@ -227,6 +630,7 @@ mod tests {
             nickname: "Alice".to_string(),
             proto: 1,
             auth: None,
+            resume: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("\"type\":\"HELLO\""));
@@ -255,10 +659,43 @@ mod tests {
             player_id: "P1".to_string(),
             nickname: "Alice".to_string(),
             room: "R001".to_string(),
+            resume_token: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("\"type\":\"WELCOME\""));
         assert!(json.contains("\"player_id\":\"P1\""));
+        assert!(!json.contains("resume_token"));
+    }
+
+    #[test]
+    fn test_hello_with_resume_deserialize() {
+        let json = r#"{"type":"HELLO","role":"HUMAN","nickname":"Alice","proto":1,"resume":"abc123"}"#;
+        let msg: ClientMessage = serde_json::from_str(json).unwrap();
+        match msg {
+            ClientMessage::Hello { resume, .. } => assert_eq!(resume.as_deref(), Some("abc123")),
+            _ => panic!("Expected Hello message"),
+        }
+    }
+
+    #[test]
+    fn test_trick_history_defaults_for_old_records() {
+        let msg: ClientMessage = serde_json::from_str(r#"{"type":"GET_HISTORY"}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::GetHistory));
+        let msg: ClientMessage = serde_json::from_str(r#"{"type":"GET_STATE"}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::GetState));
+        let msg: ClientMessage = serde_json::from_str(r#"{"type":"CLAIM","tricks":3}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::Claim { tricks: 3 }));
+        let msg: ClientMessage = serde_json::from_str(r#"{"type":"CLAIM_RESPONSE","accept":false}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::ClaimResponse { accept: false }));
+        let msg: ClientMessage = serde_json::from_str(r#"{"type":"UNDO_REQUEST"}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::UndoRequest));
+
+        // 舊版快照只有 trick, winner, cards
+        let json = r#"{"trick":1,"winner":"P2","cards":["AS","KS","QS","JS"]}"#;
+        let trick: TrickHistory = serde_json::from_str(json).unwrap();
+        assert_eq!(trick.cards.len(), 4);
+        assert!(trick.plays.is_empty());
+        assert_eq!(trick.leader, "");
     }
 
     #[test]
@@ -271,6 +708,23 @@ mod tests {
         assert!(json.contains("\"code\":\"INVALID_HELLO\""));
     }
 
+    #[test]
+    fn test_bid_deserialize() {
+        let json = r#"{"type":"BID","bid":"3NT"}"#;
+        let msg: ClientMessage = serde_json::from_str(json).unwrap();
+        assert!(matches!(msg, ClientMessage::Bid { bid } if bid == "3NT"));
+
+        let msg: ClientMessage = serde_json::from_str(r#"{"type":"REDOUBLE"}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::Redouble));
+    }
+
+    #[test]
+    fn test_pass_cards_deserialize() {
+        let json = r#"{"type":"PASS_CARDS","cards":["QS","AH","2D"]}"#;
+        let msg: ClientMessage = serde_json::from_str(json).unwrap();
+        assert!(matches!(msg, ClientMessage::PassCards { cards } if cards.len() == 3));
+    }
+
     #[test]
     fn test_ping_deserialize() {
         let json = r#"{"type":"PING"}"#;